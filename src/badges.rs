@@ -0,0 +1,71 @@
+use crate::client::sanitize_file_name;
+use crate::report::ProjectData;
+use crate::severity::severity_rank;
+use crate::theme::Theme;
+use crate::thresholds::severity_totals;
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A shields.io endpoint badge, see <https://shields.io/badges/endpoint-badge>.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointBadge {
+    pub schema_version: u8,
+    pub label: String,
+    pub message: String,
+    /// Hex color without `#`, or a shields.io color name.
+    pub color: String,
+}
+
+impl EndpointBadge {
+    /// `5 issues, worst critical` colored like the worst severity, or a green `no issues`.
+    /// Counts come from the counts facet when it was fetched and from the listed issues otherwise.
+    pub fn for_project(project: &ProjectData, theme: &Theme) -> Self {
+        let totals = severity_totals(std::slice::from_ref(project));
+        let total: u64 = totals.values().sum();
+        let worst = totals.iter()
+            .filter(|(severity, count)| **count > 0 && theme.color(severity).is_some())
+            .max_by_key(|(severity, _)| severity_rank(severity))
+            .map(|(severity, _)| severity.as_str());
+
+        let (message, color) = match worst {
+            Some(severity) => (
+                format!("{} {}, worst {}", total, if total == 1 { "issue" } else { "issues" }, severity.to_lowercase()),
+                theme.color(severity).map_or("lightgrey", |color| color.html.trim_start_matches('#')).to_string(),
+            ),
+            None if total > 0 => (format!("{} issues", total), "lightgrey".to_string()),
+            None => ("no issues".to_string(), "brightgreen".to_string()),
+        };
+
+        Self { schema_version: 1, label: "sonarqube".to_string(), message, color }
+    }
+}
+
+/// Writes `<sanitized project key>.json` into `dir` for every project, returning the paths.
+/// Fails before writing anything when two keys sanitize to the same file name.
+pub fn write_badges(dir: &Path, projects_data: &[ProjectData], theme: &Theme) -> Result<Vec<PathBuf>> {
+    let mut file_names: HashMap<String, &str> = HashMap::new();
+    for project in projects_data {
+        let file_name = format!("{}.json", sanitize_file_name(&project.project_key));
+        if let Some(other) = file_names.insert(file_name.clone(), &project.project_key) {
+            if other != project.project_key {
+                bail!("Error: projects {} and {} would both write badge {}", other, project.project_key, file_name);
+            }
+        }
+    }
+
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create --badges-dir {}", dir.display()))?;
+
+    projects_data.iter()
+        .map(|project| {
+            let path = dir.join(format!("{}.json", sanitize_file_name(&project.project_key)));
+            let badge = serde_json::to_string(&EndpointBadge::for_project(project, theme))?;
+            fs::write(&path, badge).with_context(|| format!("Error writing badge {}", path.display()))?;
+            Ok(path)
+        })
+        .collect()
+}
@@ -0,0 +1,26 @@
+//! Fetched project data kept between runs (`--cache-file`), so `--offline` runs can render
+//! reports again without contacting the server.
+
+use crate::report::ProjectData;
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// Writes `projects` to `path` as a JSON array, replacing any previous cache.
+pub fn save_projects(path: &Path, projects: &[ProjectData]) -> Result<()> {
+    let json = serde_json::to_string_pretty(projects).context("Failed to serialize project data")?;
+    fs::write(path, json + "\n")
+        .with_context(|| format!("Error writing cache file: {}", path.display()))
+}
+
+/// Reads the projects saved by [`save_projects`]. Unlike the state file, a missing cache is
+/// an error: there is nothing to report without it.
+pub fn load_projects(path: &Path) -> Result<Vec<ProjectData>> {
+    if !path.exists() {
+        bail!("Error: cache file {} not found; run once without --offline to create it", path.display());
+    }
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read cache file {}", path.display()))?;
+    serde_json::from_str(&text)
+        .with_context(|| format!("Invalid cache file {} (expected the JSON written by --cache-file)", path.display()))
+}
@@ -1,7 +1,20 @@
-use anyhow::{Context, Result};
-use reqwest::blocking::{Client, Response};
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
-use serde::Deserialize;
+use crate::compat::ApiCompat;
+use crate::har::HarRecorder;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::ValueEnum;
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::{Certificate, NoProxy, Proxy};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT_LANGUAGE, AUTHORIZATION, LOCATION, RETRY_AFTER};
+use reqwest::redirect::Policy;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
 use std::time::Duration;
 
 #[derive(Debug, Deserialize)]
@@ -17,100 +30,1567 @@ pub struct Analysis {
 #[derive(Debug, Deserialize)]
 pub struct IssuesResponse {
     pub issues: Vec<Issue>,
+    /// Missing from some older or proxied responses; treat those as a single page.
+    #[serde(default)]
+    pub paging: Option<Paging>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CurrentUserResponse {
+    #[serde(rename = "isLoggedIn")]
+    pub is_logged_in: bool,
+    pub login: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TreeComponent {
+    pub key: String,
+    /// Key of the original project for the copies a portfolio holds.
+    #[serde(rename = "refKey")]
+    pub ref_key: Option<String>,
+    pub path: Option<String>,
+    #[serde(default)]
+    pub measures: Vec<Measure>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Measure {
+    pub metric: String,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ComponentTreeResponse {
+    pub paging: Paging,
+    #[serde(default)]
+    pub components: Vec<TreeComponent>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryValue {
+    pub date: String,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MeasureHistory {
+    pub metric: String,
+    #[serde(default)]
+    pub history: Vec<HistoryValue>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MeasuresHistoryResponse {
+    #[serde(default)]
+    pub measures: Vec<MeasureHistory>,
+}
+
+/// Open issue count at one analysis date.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrendPoint {
+    /// Analysis day, `YYYY-MM-DD`.
+    pub date: String,
+    pub count: u64,
+}
+
+/// Open issue count of one directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DirectoryCount {
+    /// Path relative to the project root.
+    pub path: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectStatusResponse {
+    #[serde(rename = "projectStatus")]
+    pub project_status: QualityGateStatus,
+}
+
+/// Quality gate result from `/api/qualitygates/project_status`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct QualityGateStatus {
+    /// `OK`, `ERROR` or `NONE` (no gate).
+    pub status: String,
+    #[serde(default)]
+    pub conditions: Vec<GateCondition>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct GateCondition {
+    pub status: String,
+    #[serde(rename = "metricKey")]
+    pub metric_key: String,
+    /// `GT` or `LT`: the condition fails when the value is greater / less than the threshold.
+    pub comparator: Option<String>,
+    #[serde(rename = "errorThreshold")]
+    pub error_threshold: Option<String>,
+    #[serde(rename = "actualValue")]
+    pub actual_value: Option<String>,
+}
+
+impl QualityGateStatus {
+    /// Conditions that made the gate fail.
+    pub fn failed_conditions(&self) -> impl Iterator<Item = &GateCondition> {
+        self.conditions.iter().filter(|condition| condition.status == "ERROR")
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QualityProfilesResponse {
+    #[serde(default)]
+    pub profiles: Vec<QualityProfile>,
+}
+
+/// Quality profile analyzing one language of a project.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct QualityProfile {
+    pub name: String,
+    /// Language key, e.g. `java`.
+    pub language: String,
+    /// Display name, e.g. `Java`.
+    #[serde(rename = "languageName")]
+    pub language_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ComponentMeasures {
+    #[serde(default)]
+    pub measures: Vec<Measure>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ComponentMeasuresResponse {
+    pub component: ComponentMeasures,
+}
+
+/// Rating measures fetched by [`SonarQubeClient::get_ratings`].
+pub const RATING_METRICS: [&str; 3] = ["security_rating", "security_review_rating", "reliability_rating"];
+
+/// Measures shown by `--show-measures`: line coverage and duplicated lines, both in percent.
+pub const CODE_METRICS: [&str; 2] = ["coverage", "duplicated_lines_density"];
+
+/// A project's ratings, `1.0` (A) to `5.0` (E); `None` when the project has no such measure
+/// (e.g. security review on servers before 8.2).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ratings {
+    pub security: Option<f64>,
+    pub security_review: Option<f64>,
+    pub reliability: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Paging {
+    #[serde(rename = "pageIndex")]
+    pub page_index: u32,
+    #[serde(rename = "pageSize")]
+    pub page_size: u32,
+    pub total: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FacetValue {
+    pub val: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Facet {
+    pub property: String,
+    pub values: Vec<FacetValue>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IssueFacetsResponse {
+    pub total: Option<u64>,
+    /// Total remediation effort in minutes, only present with `facetMode=effort`.
+    #[serde(rename = "effortTotal")]
+    pub effort_total: Option<u64>,
+    pub paging: Option<Paging>,
+    #[serde(default)]
+    pub facets: Vec<Facet>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IssueCounts {
+    pub total: u64,
+    pub by_severity: BTreeMap<String, u64>,
+}
+
+impl IssueCounts {
+    /// Removes a subset's counts, e.g. the issues of ignored rules, dropping emptied severities.
+    pub fn subtract(&mut self, subset: &IssueCounts) {
+        self.total = self.total.saturating_sub(subset.total);
+        for (severity, count) in &subset.by_severity {
+            if let Some(value) = self.by_severity.get_mut(severity) {
+                *value = value.saturating_sub(*count);
+            }
+        }
+        self.by_severity.retain(|_, count| *count > 0);
+    }
+}
+
+/// Remediation effort of open issues, in minutes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EffortSummary {
+    pub total_minutes: u64,
+    pub by_severity: BTreeMap<String, u64>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Issue {
+    pub key: Option<String>,
+    /// `OPEN`, `CONFIRMED`, `REOPENED`, `RESOLVED` or `CLOSED`.
+    pub status: Option<String>,
+    #[serde(rename = "creationDate")]
+    pub creation_date: Option<String>,
     pub severity: Option<String>,
+    /// `BUG`, `VULNERABILITY`, `CODE_SMELL` or `SECURITY_HOTSPOT`.
+    #[serde(rename = "type")]
+    pub issue_type: Option<String>,
     pub message: Option<String>,
     pub component: Option<String>,
     pub line: Option<i32>,
+    pub author: Option<String>,
+    pub rule: Option<String>,
+    #[serde(rename = "textRange")]
+    pub text_range: Option<TextRange>,
+    #[serde(default)]
+    pub transitions: Vec<String>,
+    #[serde(default)]
+    pub actions: Vec<String>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+/// Location of an issue in its file; lines are 1-based, offsets 0-based columns.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextRange {
+    pub start_line: i32,
+    pub end_line: i32,
+    pub start_offset: Option<i32>,
+    pub end_offset: Option<i32>,
+}
+
+/// One listed issue as reported; also read back from `--format json` reports.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct IssueData {
     pub severity: String,
+    /// `BUG`, `VULNERABILITY` or `CODE_SMELL`; `None` when the server didn't say.
+    pub issue_type: Option<String>,
     pub message: String,
     pub component: String,
     pub line: String,
+    pub key: Option<String>,
+    pub creation_date: Option<String>,
+    /// SCM author (email or login) blamed for the issue's line.
+    pub author: Option<String>,
+    /// Rule key, e.g. `java:S1481`.
+    pub rule: Option<String>,
+    /// Full location; missing for file-level issues and from some older servers.
+    pub text_range: Option<TextRange>,
+    /// Workflow transitions the token's user may apply, e.g. `confirm` or `wontfix`; empty
+    /// unless requested from [`SonarQubeClient::search_issues`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transitions: Vec<String>,
+    /// Other changes the token's user may make, e.g. `assign` or `set_severity`; empty unless
+    /// requested from [`SonarQubeClient::search_issues`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<String>,
+}
+
+impl IssueData {
+    /// Whether the issue can be moved through `transition` (e.g. `resolve`) by the token's user.
+    pub fn can_transition(&self, transition: &str) -> bool {
+        self.transitions.iter().any(|available| available == transition)
+    }
+
+    /// Whether the token's user may perform `action` (e.g. `comment`) on the issue.
+    pub fn can_perform(&self, action: &str) -> bool {
+        self.actions.iter().any(|available| available == action)
+    }
+}
+
+/// Returns `url` with any embedded credentials replaced, safe for reports and logs.
+pub fn redact_url(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut parsed) => {
+            if !parsed.username().is_empty() || parsed.password().is_some() {
+                let _ = parsed.set_username("***");
+                let _ = parsed.set_password(None);
+            }
+            parsed.to_string().trim_end_matches('/').to_string()
+        }
+        Err(_) => url.to_string(),
+    }
+}
+
+/// Context paths tried by [`SonarQubeClient::detect_api_prefix`], in order; `""` is the root.
+const API_PREFIX_CANDIDATES: [&str; 2] = ["", "/sonar"];
+
+/// Largest page size (`ps`) the search web services accept.
+pub const MAX_PAGE_SIZE: usize = 500;
+
+/// Results `/api/issues/search` will page through (`p * ps`) before refusing.
+pub const MAX_SEARCH_RESULTS: usize = 10_000;
+
+/// Default for `--batch-size`: keys sent per request in a comma-separated key list.
+pub const DEFAULT_BATCH_SIZE: usize = 100;
+
+/// Budget for the URL-encoded key list of one request, keeping URLs well under the limits
+/// of proxies and servers (often 4-8 KB for the whole request line).
+pub const MAX_KEY_LIST_CHARS: usize = 2000;
+
+/// Length of `key` once percent-encoded in a query string.
+fn encoded_len(key: &str) -> usize {
+    key.bytes().map(|byte| if byte.is_ascii_alphanumeric() || b"-_.*".contains(&byte) { 1 } else { 3 }).sum()
+}
+
+/// Splits `keys` into consecutive batches of at most `max_keys` keys whose comma-separated,
+/// URL-encoded list fits in `max_chars`. A key too long to fit even on its own still gets a
+/// batch of its own rather than being dropped.
+pub fn batch_keys(keys: &[String], max_keys: usize, max_chars: usize) -> Vec<&[String]> {
+    let max_keys = max_keys.max(1);
+    let mut batches = Vec::new();
+    let mut start = 0;
+    let mut chars = 0;
+    for (index, key) in keys.iter().enumerate() {
+        // Every key after the first in a batch is preceded by an encoded comma (`%2C`).
+        let added = encoded_len(key) + if index > start { 3 } else { 0 };
+        if index > start && (index - start == max_keys || chars + added > max_chars) {
+            batches.push(&keys[start..index]);
+            start = index;
+            chars = encoded_len(key);
+        } else {
+            chars += added;
+        }
+    }
+    if start < keys.len() {
+        batches.push(&keys[start..]);
+    }
+    batches
+}
+
+/// Redirect hops followed before giving up.
+const MAX_REDIRECTS: usize = 10;
+
+/// Exponential backoff `base * 2^attempt`, scaled by a factor in `[1 - jitter, 1 + jitter)`
+/// picked by `random` (uniform in `[0, 1)`).
+pub fn backoff_delay(base: Duration, attempt: u32, jitter: f64, random: f64) -> Duration {
+    let exponential = base * 2u32.saturating_pow(attempt);
+    exponential.mul_f64(1.0 - jitter + 2.0 * jitter * random)
+}
+
+/// Longest `Retry-After` honored; a server asking for more gets retried after this instead.
+pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Delay requested by a `Retry-After` header: delay-seconds or an HTTP date (relative to `now`),
+/// capped at [`MAX_RETRY_AFTER`]. `None` if the value is neither.
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    let delay = match value.parse::<u64>() {
+        Ok(seconds) => Duration::from_secs(seconds),
+        Err(_) => {
+            let date = DateTime::parse_from_rfc2822(value).ok()?;
+            (date.with_timezone(&Utc) - now).to_std().unwrap_or(Duration::ZERO)
+        }
+    };
+    Some(delay.min(MAX_RETRY_AFTER))
+}
+
+fn is_transient_status(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// A 401/403 from an optional endpoint (quality gate, profiles, measures...). Returned as an
+/// error so [`crate::fetch::fetch_project`] can tell missing permissions from other failures
+/// and note them in the report instead of failing the run.
+#[derive(Debug)]
+pub struct PermissionDenied {
+    /// What was being fetched, e.g. `quality gate`.
+    pub what: String,
+    pub project_key: String,
+    pub status: reqwest::StatusCode,
+}
+
+impl fmt::Display for PermissionDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Insufficient permissions to fetch {} for {}: HTTP {}", self.what, self.project_key, self.status)
+    }
+}
+
+impl std::error::Error for PermissionDenied {}
+
+/// A 401/403 on a project's analysis or issues. Unlike a [`PermissionDenied`] section, the
+/// report would look clean without this data, so the project fails instead; a 401 means the
+/// token itself was rejected and aborts the run.
+#[derive(Debug)]
+pub struct AuthenticationFailed {
+    pub project_key: String,
+    pub status: reqwest::StatusCode,
+}
+
+impl AuthenticationFailed {
+    /// Whether the token was rejected outright (HTTP 401), rather than refused this project.
+    pub fn is_invalid_token(&self) -> bool {
+        self.status == reqwest::StatusCode::UNAUTHORIZED
+    }
+}
+
+impl fmt::Display for AuthenticationFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hint = if self.is_invalid_token() {
+            "check that the token is valid and hasn't expired"
+        } else {
+            "check that the token has Browse permission on the project"
+        };
+        write!(f, "Authentication failed for {}: HTTP {}; {}", self.project_key, self.status, hint)
+    }
+}
+
+impl std::error::Error for AuthenticationFailed {}
+
+/// Fails with [`AuthenticationFailed`] when `status` is 401 or 403.
+fn check_authentication(status: reqwest::StatusCode, project_key: &str) -> Result<()> {
+    if matches!(status, reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN) {
+        return Err(AuthenticationFailed { project_key: project_key.to_string(), status }.into());
+    }
+    Ok(())
+}
+
+/// Fails with [`PermissionDenied`] when `status` is 401 or 403.
+fn check_permission(status: reqwest::StatusCode, what: &str, project_key: &str) -> Result<()> {
+    if matches!(status, reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN) {
+        return Err(PermissionDenied { what: what.to_string(), project_key: project_key.to_string(), status }.into());
+    }
+    Ok(())
+}
+
+/// Server-side filters for `/api/issues/search`, shared by issue listing and counting.
+#[derive(Debug, Clone, Default)]
+pub struct IssueFilters {
+    /// Only issues created on or after this date (`createdAfter`).
+    pub created_after: Option<NaiveDate>,
+    /// Only issues created before this date (`createdBefore`).
+    pub created_before: Option<NaiveDate>,
+    /// Only issues in the new code period (`inNewCodePeriod`).
+    pub in_new_code_period: bool,
+    /// Only issues assigned to these comma-separated logins (`assignees`).
+    pub assignees: Option<String>,
+    /// Only assigned (`true`) or only unassigned (`false`) issues (`assigned`).
+    pub assigned: Option<bool>,
+    /// Only issues on the queried component itself, not its subtree (`onComponentOnly`).
+    pub on_component_only: bool,
+    /// Branch to query instead of the main branch (`branch`).
+    pub branch: Option<String>,
+    /// Only issues in these comma-separated directory paths (`directories`).
+    pub directories: Option<String>,
+    /// Only issues raised by these comma-separated rule keys (`rules`).
+    pub rules: Option<String>,
+}
+
+impl IssueFilters {
+    /// Checks the creation date range; `created_after_flag` names the option `created_after`
+    /// came from (`--since` or `--last-days`) in the error.
+    pub fn validate(&self, created_after_flag: &str) -> Result<()> {
+        if let (Some(after), Some(before)) = (self.created_after, self.created_before) {
+            if before <= after {
+                bail!("Error: --created-before ({}) must be after {} ({})", before, created_after_flag, after);
+            }
+        }
+        Ok(())
+    }
+
+    fn query_params(&self, compat: ApiCompat) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(after) = self.created_after {
+            params.push(("createdAfter", after.format("%Y-%m-%d").to_string()));
+        }
+        if let Some(before) = self.created_before {
+            params.push(("createdBefore", before.format("%Y-%m-%d").to_string()));
+        }
+        if self.in_new_code_period {
+            params.push((compat.new_code_param(), "true".to_string()));
+        }
+        if let Some(assignees) = &self.assignees {
+            params.push(("assignees", assignees.clone()));
+        }
+        if let Some(assigned) = self.assigned {
+            params.push(("assigned", assigned.to_string()));
+        }
+        if self.on_component_only {
+            params.push(("onComponentOnly", "true".to_string()));
+        }
+        if let Some(branch) = &self.branch {
+            params.push(("branch", branch.clone()));
+        }
+        if let Some(directories) = &self.directories {
+            params.push(("directories", directories.clone()));
+        }
+        if let Some(rules) = &self.rules {
+            params.push(("rules", rules.clone()));
+        }
+        params
+    }
+}
+
+/// `--dump-raw` file stem for a request, keeping branches of one project apart.
+fn dump_name(project_key: &str, branch: Option<&str>, request: &str) -> String {
+    match branch {
+        Some(branch) => format!("{}@{}.{}", project_key, branch, request),
+        None => format!("{}.{}", project_key, request),
+    }
+}
+
+/// Replaces every character but ASCII letters, digits, `-`, `_` and `.` with `_`, so project
+/// keys like `group:project` make portable file names.
+pub fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || "-_.".contains(c) { c } else { '_' })
+        .collect()
+}
+
+/// Parses a `YYYY-MM-DD` date given on the command line.
+pub fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("invalid date '{}', expected YYYY-MM-DD", value))
+}
+
+/// Parses the `--last-days` count, which must be at least 1.
+pub fn parse_last_days(value: &str) -> Result<u32, String> {
+    match value.parse::<u32>() {
+        Ok(days) if days > 0 => Ok(days),
+        _ => Err(format!("invalid day count '{}', expected a whole number above 0", value)),
+    }
+}
+
+/// `createdAfter` date for `--last-days`: `days` days before `today`.
+pub fn last_days_start(today: NaiveDate, days: u32) -> NaiveDate {
+    today - chrono::Days::new(days.into())
+}
+
+/// Undoes percent-encoding in a project key copied from a URL (`group%3Aproject`), so reqwest
+/// encodes it exactly once. Keys can't contain `%` themselves, so this never alters a real key;
+/// malformed sequences are left as they are.
+pub fn decode_project_key(key: &str) -> String {
+    let bytes = key.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        // from_str_radix alone would accept a sign, decoding `%+1` to a byte.
+        let hex = bytes.get(i + 1..i + 3)
+            .filter(|pair| pair.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|pair| std::str::from_utf8(pair).ok())
+            .and_then(|pair| u8::from_str_radix(pair, 16).ok());
+        match hex {
+            Some(byte) if bytes[i] == b'%' => {
+                decoded.push(byte);
+                i += 3;
+            }
+            _ => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap_or_else(|_| key.to_string())
+}
+
+/// Whether `key` looks like a directory or file key (`my-project:src/main`): a path after the
+/// project key. Project keys often contain `:` themselves (`group:project`), but not `/`.
+pub fn is_path_key(key: &str) -> bool {
+    key.split_once(':').is_some_and(|(_, rest)| rest.contains('/'))
+}
+
+/// Normalizes a comma-separated directory list, rejecting empty entries such as `src,,lib`.
+pub fn parse_directories(value: &str) -> Result<String, String> {
+    let directories: Vec<&str> = value.split(',').map(str::trim).collect();
+    if directories.iter().any(|directory| directory.is_empty()) {
+        return Err(format!("empty directory in '{}', expected e.g. src/main,src/lib", value));
+    }
+    Ok(directories.join(","))
+}
+
+/// Minimum TLS protocol version accepted from the server.
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq)]
+pub enum MinTlsVersion {
+    #[value(name = "1.0")]
+    Tls1_0,
+    #[value(name = "1.1")]
+    Tls1_1,
+    #[value(name = "1.2")]
+    Tls1_2,
+    #[value(name = "1.3")]
+    Tls1_3,
+}
+
+impl MinTlsVersion {
+    fn to_reqwest(self) -> reqwest::tls::Version {
+        match self {
+            MinTlsVersion::Tls1_0 => reqwest::tls::Version::TLS_1_0,
+            MinTlsVersion::Tls1_1 => reqwest::tls::Version::TLS_1_1,
+            MinTlsVersion::Tls1_2 => reqwest::tls::Version::TLS_1_2,
+            MinTlsVersion::Tls1_3 => reqwest::tls::Version::TLS_1_3,
+        }
+    }
+}
+
+/// Environment variables naming a PEM CA bundle, in order of precedence.
+const CA_BUNDLE_ENV_VARS: [&str; 2] = ["SSL_CERT_FILE", "REQUESTS_CA_BUNDLE"];
+
+fn ca_bundle_from_env() -> Option<PathBuf> {
+    CA_BUNDLE_ENV_VARS.iter()
+        .filter_map(std::env::var_os)
+        .find(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Loads every certificate in a PEM bundle, not just the first.
+fn load_ca_bundle(path: &Path) -> Result<Vec<Certificate>> {
+    let pem = fs::read(path)
+        .with_context(|| format!("Failed to read CA bundle {}", path.display()))?;
+    let certificates = Certificate::from_pem_bundle(&pem)
+        .with_context(|| format!("Failed to parse CA bundle {}", path.display()))?;
+    if certificates.is_empty() {
+        bail!("CA bundle {} contains no PEM certificates", path.display());
+    }
+    Ok(certificates)
+}
+
+/// Connection tuning for [`SonarQubeClient::with_options`]. Defaults match reqwest's own and
+/// don't retry.
+#[derive(Debug, Clone)]
+pub struct ClientOptions {
+    /// How long idle pooled connections are kept for reuse.
+    pub pool_idle_timeout: Option<Duration>,
+    /// Speak HTTP/2 without ALPN negotiation. Fails against HTTP/1-only servers, so opt-in only.
+    pub http2_prior_knowledge: bool,
+    /// Refuse handshakes that negotiate an older protocol. The default TLS backend (native-tls)
+    /// cannot enforce 1.3 as a minimum, so that value fails at construction.
+    pub min_tls_version: Option<MinTlsVersion>,
+    /// Retries for the cheap analysis-date call on connection errors, 429 and 5xx.
+    pub analysis_retries: u32,
+    /// Retries for the more expensive issue search calls.
+    pub issues_retries: u32,
+    /// Delay before the first retry; doubled on each further attempt.
+    pub retry_base_delay: Duration,
+    /// Randomizes each backoff delay by ±this fraction so parallel runs don't retry in lockstep.
+    pub retry_jitter: f64,
+    /// Directory receiving every raw JSON response body before it is parsed.
+    pub dump_raw_dir: Option<PathBuf>,
+    /// PEM bundle whose certificates are trusted in addition to the system roots. Falls back
+    /// to `SSL_CERT_FILE`, then `REQUESTS_CA_BUNDLE`.
+    pub ca_bundle: Option<PathBuf>,
+    /// Parameter names to use; see [`ApiCompat`].
+    pub api_compat: ApiCompat,
+    /// `Accept-Language` sent with every request, so servers that localize rule messages
+    /// answer in that language.
+    pub accept_language: Option<String>,
+    /// HAR file receiving every request and response, `Authorization` redacted.
+    pub har: Option<PathBuf>,
+    /// Abort instead of sending more than this many HTTP requests (retries and redirects
+    /// included). `None` means unlimited.
+    pub max_api_calls: Option<usize>,
+    /// Retries allowed across all requests of the client, on top of the per-request limits.
+    /// Once spent, requests fail on their first transient error. `None` means unlimited.
+    pub retry_budget: Option<usize>,
+    /// Proxy for every request. Without it reqwest uses `HTTP_PROXY` / `HTTPS_PROXY`, which
+    /// already honor `NO_PROXY`.
+    pub proxy: Option<String>,
+    /// Comma-separated hosts, domains (`.corp.example`) and CIDR ranges that bypass `proxy`.
+    /// Falls back to `NO_PROXY`, then `no_proxy`.
+    pub no_proxy: Option<String>,
+    /// Total time allowed per HTTP request, connecting included; zero means no timeout.
+    pub timeout: Duration,
+    /// How the token is sent in the `Authorization` header.
+    pub auth_scheme: AuthScheme,
+    /// SonarCloud organization key, sent as `organization` with every request.
+    pub organization: Option<String>,
+    /// Most keys sent in one request's key list; lists are also split to keep URLs short.
+    pub batch_size: usize,
+}
+
+/// How the token is sent: as the user name of HTTP Basic auth (every SonarQube version), or
+/// as a Bearer token (SonarQube 10.0+ and SonarCloud).
+#[derive(Debug, Clone, Copy, Default, ValueEnum, PartialEq, Eq)]
+pub enum AuthScheme {
+    #[default]
+    Basic,
+    Bearer,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            pool_idle_timeout: None,
+            http2_prior_knowledge: false,
+            min_tls_version: None,
+            analysis_retries: 0,
+            issues_retries: 0,
+            retry_base_delay: Duration::from_millis(500),
+            retry_jitter: 0.5,
+            dump_raw_dir: None,
+            ca_bundle: None,
+            api_compat: ApiCompat::default(),
+            accept_language: None,
+            har: None,
+            max_api_calls: None,
+            retry_budget: None,
+            proxy: None,
+            no_proxy: None,
+            timeout: Duration::from_secs(30),
+            auth_scheme: AuthScheme::default(),
+            organization: None,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+}
+
+/// Fluent construction of a [`SonarQubeClient`] for library users:
+///
+/// ```no_run
+/// # use sonarqube_checker::client::{AuthScheme, SonarQubeClient};
+/// # use std::time::Duration;
+/// let client = SonarQubeClient::builder()
+///     .url("https://sonarcloud.io")
+///     .token("squ_...")
+///     .auth_scheme(AuthScheme::Bearer)
+///     .organization("my-org")
+///     .timeout(Duration::from_secs(10))
+///     .build()?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// Settings without a setter of their own go through [`SonarQubeClientBuilder::options`].
+#[derive(Debug, Default)]
+pub struct SonarQubeClientBuilder {
+    base_url: Option<String>,
+    api_token: Option<String>,
+    options: ClientOptions,
+}
+
+impl SonarQubeClientBuilder {
+    pub fn url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    pub fn token(mut self, api_token: impl Into<String>) -> Self {
+        self.api_token = Some(api_token.into());
+        self
+    }
+
+    /// Per-request timeout; [`Duration::ZERO`] disables it.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = timeout;
+        self
+    }
+
+    /// Proxy for every request, bypassed for `NO_PROXY` hosts unless [`Self::no_proxy`] is set.
+    pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.options.proxy = Some(proxy.into());
+        self
+    }
+
+    pub fn no_proxy(mut self, hosts: impl Into<String>) -> Self {
+        self.options.no_proxy = Some(hosts.into());
+        self
+    }
+
+    pub fn auth_scheme(mut self, auth_scheme: AuthScheme) -> Self {
+        self.options.auth_scheme = auth_scheme;
+        self
+    }
+
+    pub fn organization(mut self, organization: impl Into<String>) -> Self {
+        self.options.organization = Some(organization.into());
+        self
+    }
+
+    pub fn accept_language(mut self, language: impl Into<String>) -> Self {
+        self.options.accept_language = Some(language.into());
+        self
+    }
+
+    /// Replaces every option set so far; call it before the specific setters.
+    pub fn options(mut self, options: ClientOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn build(self) -> Result<SonarQubeClient> {
+        let base_url = self.base_url.context("SonarQubeClientBuilder needs a url")?;
+        let api_token = self.api_token.context("SonarQubeClientBuilder needs a token")?;
+        SonarQubeClient::with_options(base_url, api_token, self.options)
+    }
 }
 
 pub struct SonarQubeClient {
     base_url: String,
+    /// Context path between `base_url` and `/api`, e.g. `/sonar`; empty by default.
+    api_prefix: String,
     client: Client,
+    analysis_retries: u32,
+    issues_retries: u32,
+    retry_base_delay: Duration,
+    retry_jitter: f64,
+    api_token: String,
+    dump_raw_dir: Option<PathBuf>,
+    api_compat: ApiCompat,
+    har: Option<HarRecorder>,
+    max_api_calls: Option<usize>,
+    api_calls: AtomicUsize,
+    retry_budget: Option<usize>,
+    retries_used: AtomicUsize,
+    /// Set once the exhausted retry budget has been reported.
+    retry_budget_reported: AtomicBool,
+    organization: Option<String>,
+    batch_size: usize,
+    timeout: Option<Duration>,
 }
 
 impl SonarQubeClient {
     pub fn new(base_url: String, api_token: String) -> Result<Self> {
+        Self::builder().url(base_url).token(api_token).build()
+    }
+
+    pub fn builder() -> SonarQubeClientBuilder {
+        SonarQubeClientBuilder::default()
+    }
+
+    pub fn with_options(base_url: String, api_token: String, options: ClientOptions) -> Result<Self> {
         let mut headers = HeaderMap::new();
         use base64::Engine;
-        let auth_value = base64::engine::general_purpose::STANDARD.encode(format!("{}:", api_token));
+        let auth_value = match options.auth_scheme {
+            AuthScheme::Basic => format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(format!("{}:", api_token))),
+            AuthScheme::Bearer => format!("Bearer {}", api_token),
+        };
         headers.insert(
             AUTHORIZATION,
-            HeaderValue::from_str(&format!("Basic {}", auth_value))?,
+            HeaderValue::from_str(&auth_value)?,
         );
+        if let Some(language) = &options.accept_language {
+            headers.insert(
+                ACCEPT_LANGUAGE,
+                HeaderValue::from_str(language).context("Invalid --accept-language value")?,
+            );
+        }
+
+        let har = options.har.map(|path| HarRecorder::new(path, &headers, &api_token));
 
-        let client = Client::builder()
+        let timeout = Some(options.timeout).filter(|timeout| !timeout.is_zero());
+        let mut builder = Client::builder()
             .default_headers(headers)
-            .timeout(Duration::from_secs(30))
-            .build()?;
+            .redirect(Policy::none())
+            .timeout(timeout);
+
+        if let Some(idle_timeout) = options.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(idle_timeout);
+        }
+        if options.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+
+        if let Some(min_tls_version) = options.min_tls_version {
+            builder = builder.min_tls_version(min_tls_version.to_reqwest());
+        }
+
+        if let Some(proxy) = &options.proxy {
+            let no_proxy = match &options.no_proxy {
+                Some(hosts) => NoProxy::from_string(hosts),
+                None => NoProxy::from_env(),
+            };
+            let proxy = Proxy::all(proxy)
+                .with_context(|| format!("Invalid --proxy URL {}", redact_url(proxy)))?
+                .no_proxy(no_proxy);
+            builder = builder.proxy(proxy);
+        }
+
+        if let Some(ca_bundle) = options.ca_bundle.clone().or_else(ca_bundle_from_env) {
+            for certificate in load_ca_bundle(&ca_bundle)? {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        let client = builder.build().context("Failed to build HTTP client")?;
 
         Ok(Self {
             base_url: base_url.trim_end_matches('/').to_string(),
+            api_prefix: String::new(),
             client,
+            analysis_retries: options.analysis_retries,
+            issues_retries: options.issues_retries,
+            retry_base_delay: options.retry_base_delay,
+            retry_jitter: options.retry_jitter.clamp(0.0, 1.0),
+            api_token,
+            dump_raw_dir: options.dump_raw_dir,
+            api_compat: options.api_compat,
+            har,
+            max_api_calls: options.max_api_calls,
+            api_calls: AtomicUsize::new(0),
+            retry_budget: options.retry_budget,
+            retries_used: AtomicUsize::new(0),
+            retry_budget_reported: AtomicBool::new(false),
+            organization: options.organization,
+            batch_size: options.batch_size.max(1),
+            timeout,
         })
     }
 
+    /// Sends an idempotent request, retrying up to `retries` times with exponential backoff on
+    /// connection errors, timeouts, 429 and 5xx responses.
+    fn send_with_retry(&self, request: RequestBuilder, retries: u32) -> Result<Response> {
+        let mut attempt = 0;
+        loop {
+            let attempt_request = request.try_clone().context("Request cannot be retried")?;
+            let outcome = self.send_following_redirects(attempt_request);
+
+            let transient = match &outcome {
+                Ok(response) => is_transient_status(response.status()),
+                Err(error) => error.downcast_ref::<reqwest::Error>()
+                    .is_some_and(|error| error.is_connect() || error.is_timeout()),
+            };
+            if !transient || attempt >= retries || !self.take_retry() {
+                return outcome.context("Failed to send request");
+            }
+
+            // A 429 saying when to come back overrides the backoff.
+            let retry_after = match &outcome {
+                Ok(response) if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS => response.headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| parse_retry_after(value, Utc::now())),
+                _ => None,
+            };
+            let delay = retry_after
+                .unwrap_or_else(|| backoff_delay(self.retry_base_delay, attempt, self.retry_jitter, fastrand::f64()));
+            match &outcome {
+                Ok(response) => eprintln!("HTTP {}, retrying in {:?} ({}/{})", response.status(), delay, attempt + 1, retries),
+                Err(error) => eprintln!("{}, retrying in {:?} ({}/{})", error, delay, attempt + 1, retries),
+            }
+            thread::sleep(delay);
+            attempt += 1;
+        }
+    }
+
+    /// Sends `request`, following redirects here rather than in reqwest: reqwest drops the
+    /// `Authorization` header whenever scheme or port change (e.g. http→https), which surfaces
+    /// as a confusing 401. Same-host redirects keep credentials; redirects to another host or
+    /// down to plain HTTP fail with a hint to use the final URL.
+    fn send_following_redirects(&self, request: RequestBuilder) -> Result<Response> {
+        let mut request = request.build()?;
+        if let Some(organization) = &self.organization {
+            request.url_mut().query_pairs_mut().append_pair("organization", organization);
+        }
+
+        for _ in 0..=MAX_REDIRECTS {
+            let next_request = request.try_clone();
+            let origin = request.url().clone();
+            self.count_api_call()?;
+            let response = match &self.har {
+                Some(har) => har.execute(&self.client, request)?,
+                None => self.client.execute(request)?,
+            };
+
+            let location = response.headers().get(LOCATION).and_then(|value| value.to_str().ok());
+            let location = match location {
+                Some(location) if response.status().is_redirection() => location,
+                _ => return Ok(response),
+            };
+
+            let target = origin.join(location).context("Invalid redirect location")?;
+            let downgrade = origin.scheme() == "https" && target.scheme() != "https";
+            if target.host_str() != origin.host_str() || downgrade {
+                bail!(
+                    "SonarQube redirected {} to {}; credentials are not forwarded to another host or over plain HTTP, so pass the final URL with --url",
+                    redact_url(origin.as_str()),
+                    redact_url(target.as_str())
+                );
+            }
+
+            request = next_request.context("Request cannot be redirected")?;
+            *request.url_mut() = target;
+        }
+
+        bail!("Too many redirects (more than {})", MAX_REDIRECTS)
+    }
+
+    /// Counts one HTTP request against `--max-api-calls`, failing instead once the limit is used up.
+    fn count_api_call(&self) -> Result<()> {
+        let limit = self.max_api_calls.unwrap_or(usize::MAX);
+        if self.api_calls.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |made| (made < limit).then_some(made + 1)).is_err() {
+            bail!("Stopped after {} API calls (--max-api-calls); raise the limit if this run needs more requests", limit);
+        }
+        Ok(())
+    }
+
+    /// Takes one retry from `--retry-budget`, reporting once when it is used up.
+    fn take_retry(&self) -> bool {
+        let budget = self.retry_budget.unwrap_or(usize::MAX);
+        if self.retries_used.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| (used < budget).then_some(used + 1)).is_ok() {
+            return true;
+        }
+        if !self.retry_budget_reported.swap(true, Ordering::SeqCst) {
+            eprintln!("Retry budget of {} exhausted (--retry-budget); failing fast without retrying", budget);
+        }
+        false
+    }
+
+    /// Number of retries spent so far.
+    pub fn retries_used(&self) -> usize {
+        self.retries_used.load(Ordering::SeqCst)
+    }
+
+    /// Number of HTTP requests sent so far.
+    pub fn api_calls(&self) -> usize {
+        self.api_calls.load(Ordering::SeqCst)
+    }
+
+    /// Reads the body as text, saves it under `--dump-raw` as `<name>.json` (token redacted) and
+    /// parses it from that text, so the dump holds exactly what the server returned.
+    fn parse_json<T: DeserializeOwned>(&self, response: Response, name: &str) -> Result<T> {
+        let body = response.text().context("Failed to read response")?;
+
+        if let Some(dir) = &self.dump_raw_dir {
+            let path = dir.join(format!("{}.json", sanitize_file_name(name)));
+            let redacted = if self.api_token.is_empty() { body.clone() } else { body.replace(&self.api_token, "***") };
+            if let Err(error) = fs::write(&path, redacted) {
+                eprintln!("Warning: could not write {}: {}", path.display(), error);
+            }
+        }
+
+        serde_json::from_str(&body).context("Failed to parse response")
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Per-request timeout the HTTP client was built with; `None` when disabled.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn api_url(&self, endpoint: &str) -> String {
+        format!("{}{}/api/{}", self.base_url, self.api_prefix, endpoint)
+    }
+
+    pub fn api_prefix(&self) -> &str {
+        &self.api_prefix
+    }
+
+    /// Finds the context path the web API is served under by requesting `/api/system/status`
+    /// below each of [`API_PREFIX_CANDIDATES`] until one doesn't answer 404, and uses it for
+    /// every later request. Returns `None`, keeping the current prefix, when none matches.
+    pub fn detect_api_prefix(&mut self) -> Result<Option<String>> {
+        for prefix in API_PREFIX_CANDIDATES {
+            let url = format!("{}{}/api/system/status", self.base_url, prefix);
+            let response: Response = self.send_with_retry(self.client.get(&url), self.analysis_retries)?;
+
+            if response.status() != reqwest::StatusCode::NOT_FOUND {
+                self.api_prefix = prefix.to_string();
+                return Ok(Some(self.api_prefix.clone()));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn api_compat(&self) -> ApiCompat {
+        self.api_compat
+    }
+
+    /// Switches parameter names, e.g. after probing the server version.
+    pub fn set_api_compat(&mut self, api_compat: ApiCompat) {
+        self.api_compat = api_compat;
+    }
+
+    /// Reads the server version from `/api/server/version`, which answers in plain text.
+    pub fn get_server_version(&self) -> Result<Option<String>> {
+        let url = self.api_url("server/version");
+
+        let request = self.client.get(&url);
+        let response: Response = self.send_with_retry(request, self.analysis_retries)?;
+
+        if !response.status().is_success() {
+            eprintln!("Error fetching server version: HTTP {}", response.status());
+            return Ok(None);
+        }
+
+        let version = response.text().context("Failed to read response")?;
+        let version = version.trim();
+        Ok((!version.is_empty()).then(|| version.to_string()))
+    }
+
+    /// Returns the login of the user owning the token, or `None` when the token isn't tied to a
+    /// user (e.g. a project analysis token).
+    pub fn get_current_user(&self) -> Result<Option<String>> {
+        let url = self.api_url("users/current");
+
+        let request = self.client.get(&url);
+        let response: Response = self.send_with_retry(request, self.analysis_retries)?;
+
+        if !response.status().is_success() {
+            eprintln!("Error fetching current user: HTTP {}", response.status());
+            return Ok(None);
+        }
+
+        let data: CurrentUserResponse = self.parse_json(response, "current_user")?;
+
+        Ok(data.login.filter(|_| data.is_logged_in))
+    }
+
+    /// Pages through `/api/measures/component_tree` below `component`. A non-success status is
+    /// handed back as `Err` for the caller to interpret.
+    fn component_tree(
+        &self,
+        component: &str,
+        qualifiers: &str,
+        metric_keys: &str,
+        dump_name: &str,
+    ) -> Result<std::result::Result<Vec<TreeComponent>, reqwest::StatusCode>> {
+        let url = self.api_url("measures/component_tree");
+        let mut components = Vec::new();
+        let mut page = 1u32;
+
+        loop {
+            let request = self.client.get(&url).query(&[
+                ("component", component),
+                ("metricKeys", metric_keys),
+                ("qualifiers", qualifiers),
+                ("strategy", "all"),
+                ("ps", "500"),
+                ("p", &page.to_string()),
+            ]);
+            let response: Response = self.send_with_retry(request, self.analysis_retries)?;
+
+            if !response.status().is_success() {
+                return Ok(Err(response.status()));
+            }
+
+            let data: ComponentTreeResponse =
+                self.parse_json(response, &format!("{}.{}.{}", component, dump_name, page))?;
+
+            let fetched = data.components.len();
+            components.extend(data.components);
+
+            if fetched == 0 || u64::from(data.paging.page_index) * u64::from(data.paging.page_size) >= data.paging.total {
+                return Ok(Ok(components));
+            }
+            page += 1;
+        }
+    }
+
+    /// Resolves the keys of all projects in a portfolio or application via
+    /// `/api/measures/component_tree`. Portfolios need an Enterprise edition or above, and the
+    /// token needs Browse permission on the portfolio, so both cases fail with a clear error
+    /// rather than an empty report.
+    pub fn get_portfolio_projects(&self, portfolio_key: &str) -> Result<Vec<String>> {
+        let components = match self.component_tree(portfolio_key, "TRK", "ncloc", "portfolio")? {
+            Ok(components) => components,
+            Err(reqwest::StatusCode::NOT_FOUND) => bail!(
+                "Error: portfolio '{}' not found; portfolios require SonarQube Enterprise edition or above",
+                portfolio_key
+            ),
+            Err(status @ (reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN)) => bail!(
+                "Error: the token lacks Browse permission on portfolio '{}' (HTTP {})",
+                portfolio_key,
+                status
+            ),
+            Err(status) => bail!("Error resolving portfolio '{}': HTTP {}", portfolio_key, status),
+        };
+
+        let mut keys: Vec<String> = components.into_iter()
+            .map(|c| c.ref_key.unwrap_or(c.key))
+            .collect();
+        keys.sort();
+        keys.dedup();
+        Ok(keys)
+    }
+
+    /// Lists the project's directories with at least one open issue, worst first, using the
+    /// `violations` measure from the component tree.
+    pub fn get_directory_issue_counts(&self, project_key: &str) -> Result<Vec<DirectoryCount>> {
+        let components = match self.component_tree(project_key, "DIR", "violations", "directories")? {
+            Ok(components) => components,
+            Err(status) => {
+                check_permission(status, "directory measures", project_key)?;
+                eprintln!("Error fetching directory measures for {}: HTTP {}", project_key, status);
+                return Ok(Vec::new());
+            }
+        };
+
+        let mut directories: Vec<DirectoryCount> = components.into_iter()
+            .filter_map(|component| {
+                let count = component.measures.iter()
+                    .find(|measure| measure.metric == "violations")
+                    .and_then(|measure| measure.value.as_deref())
+                    .and_then(|value| value.parse::<u64>().ok())
+                    .filter(|count| *count > 0)?;
+                Some(DirectoryCount { path: component.path.unwrap_or(component.key), count })
+            })
+            .collect();
+        directories.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.path.cmp(&b.path)));
+
+        Ok(directories)
+    }
+
     pub fn get_last_analysis_date(&self, project_key: &str) -> Result<Option<String>> {
-        let url = format!("{}/api/project_analyses/search", self.base_url);
+        self.get_branch_analysis_date(project_key, None)
+    }
+
+    /// Date of the latest analysis of `branch` (the main branch when `None`).
+    pub fn get_branch_analysis_date(&self, project_key: &str, branch: Option<&str>) -> Result<Option<String>> {
+        let url = self.api_url("project_analyses/search");
         
-        let response: Response = self.client
+        let mut query = vec![("project", project_key), ("ps", "1")];
+        if let Some(branch) = branch {
+            query.push(("branch", branch));
+        }
+        let request = self.client
             .get(&url)
-            .query(&[("project", project_key), ("ps", "1")])
-            .send()
-            .context("Failed to send request")?;
+            .query(&query);
+        let response: Response = self.send_with_retry(request, self.analysis_retries)?;
 
+        check_authentication(response.status(), project_key)?;
         if !response.status().is_success() {
             eprintln!("Error fetching analysis date for {}: HTTP {}", project_key, response.status());
             return Ok(None);
         }
 
-        let data: ProjectAnalysesResponse = response.json()
-            .context("Failed to parse response")?;
+        let data: ProjectAnalysesResponse = self.parse_json(response, &dump_name(project_key, branch, "analysis"))?;
 
         Ok(data.analyses.first().map(|a| a.date.clone()))
     }
 
     pub fn get_latest_issues(&self, project_key: &str, max_issues: i32) -> Result<Vec<IssueData>> {
-        let url = format!("{}/api/issues/search", self.base_url);
-        
-        let response: Response = self.client
+        self.search_issues(project_key, max_issues, &IssueFilters::default(), false)
+    }
+
+    /// Lists up to `max_issues` open issues, newest first, reading as many pages of
+    /// [`MAX_PAGE_SIZE`] as needed. Stops early when the server has no more results or at its
+    /// [`MAX_SEARCH_RESULTS`] window. With `with_transitions`, each issue also carries the
+    /// transitions and actions available to the token's user (`additionalFields`).
+    pub fn search_issues(&self, project_key: &str, max_issues: i32, filters: &IssueFilters, with_transitions: bool) -> Result<Vec<IssueData>> {
+        let url = self.api_url("issues/search");
+        let max_issues = max_issues.max(0) as usize;
+        let page_size = max_issues.clamp(1, MAX_PAGE_SIZE);
+
+        let mut query = vec![
+            ("componentKeys", project_key.to_string()),
+            (self.api_compat.status_param(), "OPEN,CONFIRMED".to_string()),
+            ("ps", page_size.to_string()),
+            ("s", "CREATION_DATE".to_string()),
+            ("asc", "false".to_string()),
+        ];
+        query.extend(filters.query_params(self.api_compat));
+        if with_transitions {
+            query.push(("additionalFields", "transitions,actions".to_string()));
+        }
+
+        let mut issues = Vec::new();
+        let mut page = 1;
+        loop {
+            let request = self.client.get(&url).query(&query).query(&[("p", page.to_string())]);
+            let response: Response = self.send_with_retry(request, self.issues_retries)?;
+
+            check_authentication(response.status(), project_key)?;
+            if !response.status().is_success() {
+                eprintln!("Error fetching issues for {} (page {}): HTTP {}", project_key, page, response.status());
+                break;
+            }
+
+            let request_name = if page == 1 { "issues".to_string() } else { format!("issues.{}", page) };
+            let data: IssuesResponse = self.parse_json(response, &dump_name(project_key, filters.branch.as_deref(), &request_name))?;
+
+            let fetched = data.issues.len();
+            let total = data.paging.map_or(0, |paging| paging.total as usize);
+            issues.extend(data.issues.into_iter().map(|issue| IssueData {
+                severity: issue.severity.unwrap_or_else(|| "N/A".to_string()),
+                issue_type: issue.issue_type,
+                message: issue.message.unwrap_or_else(|| "N/A".to_string()),
+                component: issue.component.unwrap_or_else(|| "N/A".to_string()),
+                line: issue.line.map(|l| l.to_string()).unwrap_or_else(|| "N/A".to_string()),
+                key: issue.key,
+                creation_date: issue.creation_date,
+                author: issue.author.filter(|author| !author.is_empty()),
+                rule: issue.rule,
+                text_range: issue.text_range,
+                transitions: issue.transitions,
+                actions: issue.actions,
+            }));
+
+            let next_page_end = (page + 1) * page_size;
+            if issues.len() >= max_issues || fetched < page_size || issues.len() >= total {
+                break;
+            }
+            if next_page_end > MAX_SEARCH_RESULTS {
+                eprintln!("Warning: {} has more than {} matching issues; the server lists only the first {}", project_key, issues.len(), MAX_SEARCH_RESULTS);
+                break;
+            }
+            page += 1;
+        }
+
+        issues.truncate(max_issues);
+        Ok(issues)
+    }
+
+    /// Looks up issues by key in batches (see [`batch_keys`]), returning the status of every
+    /// key the server still knows. Keys of issues that were purged or never existed are absent.
+    /// Unlike the listing calls, a failed lookup is an error: it must not read as "every issue
+    /// is gone".
+    pub fn get_issue_statuses(&self, issue_keys: &[String]) -> Result<BTreeMap<String, String>> {
+        let url = self.api_url("issues/search");
+        let mut statuses = BTreeMap::new();
+
+        let batches = batch_keys(issue_keys, self.batch_size, MAX_KEY_LIST_CHARS);
+        for (batch_index, batch) in batches.iter().enumerate() {
+            if batches.len() > 1 {
+                eprintln!("Looking up issue keys: batch {}/{} ({} keys)", batch_index + 1, batches.len(), batch.len());
+            }
+            let request = self.client.get(&url).query(&[
+                ("issues", batch.join(",")),
+                ("ps", batch.len().to_string()),
+            ]);
+            let response: Response = self.send_with_retry(request, self.issues_retries)?;
+
+            if !response.status().is_success() {
+                bail!("Error looking up issue keys: HTTP {}", response.status());
+            }
+
+            let data: IssuesResponse = self.parse_json(response, &format!("issue_keys.{}", batch_index + 1))?;
+            for issue in data.issues {
+                if let Some(key) = issue.key {
+                    statuses.insert(key, issue.status.unwrap_or_else(|| "N/A".to_string()));
+                }
+            }
+        }
+
+        Ok(statuses)
+    }
+
+    /// Adds a comment to an issue (`POST /api/issues/add_comment`; needs Browse permission on
+    /// the project). Never retried, so a comment is posted at most once.
+    pub fn add_issue_comment(&self, issue_key: &str, text: &str) -> Result<()> {
+        let url = self.api_url("issues/add_comment");
+
+        let request = self.client.post(&url).form(&[("issue", issue_key), ("text", text)]);
+        let response: Response = self.send_with_retry(request, 0)?;
+
+        if !response.status().is_success() {
+            bail!("HTTP {}", response.status());
+        }
+        Ok(())
+    }
+
+    /// Fetches the `violations` history from `/api/measures/search_history`, keeping the last
+    /// analysis of each day, oldest first. Analyses without a value are skipped.
+    pub fn get_issue_history(&self, project_key: &str) -> Result<Vec<TrendPoint>> {
+        let url = self.api_url("measures/search_history");
+
+        let request = self.client
             .get(&url)
-            .query(&[
-                ("componentKeys", project_key),
-                ("statuses", "OPEN,CONFIRMED"),
-                ("ps", &max_issues.to_string()),
-                ("s", "CREATION_DATE"),
-                ("asc", "false"),
-            ])
-            .send()
-            .context("Failed to send request")?;
+            .query(&[("component", project_key), ("metrics", "violations"), ("ps", "1000")]);
+        let response: Response = self.send_with_retry(request, self.analysis_retries)?;
 
+        check_permission(response.status(), "issue history", project_key)?;
         if !response.status().is_success() {
-            eprintln!("Error fetching issues for {}: HTTP {}", project_key, response.status());
+            eprintln!("Error fetching issue history for {}: HTTP {}", project_key, response.status());
             return Ok(Vec::new());
         }
 
-        let data: IssuesResponse = response.json()
-            .context("Failed to parse response")?;
+        let data: MeasuresHistoryResponse = self.parse_json(response, &format!("{}.history", project_key))?;
 
-        let issues = data.issues.into_iter().map(|issue| IssueData {
-            severity: issue.severity.unwrap_or_else(|| "N/A".to_string()),
-            message: issue.message.unwrap_or_else(|| "N/A".to_string()),
-            component: issue.component.unwrap_or_else(|| "N/A".to_string()),
-            line: issue.line.map(|l| l.to_string()).unwrap_or_else(|| "N/A".to_string()),
-        }).collect();
+        let mut by_day: BTreeMap<String, u64> = BTreeMap::new();
+        for entry in data.measures.into_iter()
+            .filter(|measure| measure.metric == "violations")
+            .flat_map(|measure| measure.history)
+        {
+            let count = entry.value.as_deref()
+                .and_then(|value| value.parse::<f64>().ok())
+                .filter(|count| *count >= 0.0);
+            if let Some(count) = count {
+                let day = entry.date.get(..10).unwrap_or(&entry.date).to_string();
+                by_day.insert(day, count as u64);
+            }
+        }
 
-        Ok(issues)
+        Ok(by_day.into_iter().map(|(date, count)| TrendPoint { date, count }).collect())
     }
-}
\ No newline at end of file
+
+    /// Fetches the quality gate status with its conditions, for `branch` when given (the main
+    /// branch otherwise); `None` when the request fails, and a [`PermissionDenied`] error when
+    /// the token lacks Browse permission.
+    pub fn get_quality_gate_status(&self, project_key: &str, branch: Option<&str>) -> Result<Option<QualityGateStatus>> {
+        let url = self.api_url("qualitygates/project_status");
+
+        let mut query = vec![("projectKey", project_key)];
+        if let Some(branch) = branch {
+            query.push(("branch", branch));
+        }
+        let request = self.client.get(&url).query(&query);
+        let response: Response = self.send_with_retry(request, self.analysis_retries)?;
+
+        check_permission(response.status(), "quality gate", project_key)?;
+        if !response.status().is_success() {
+            eprintln!("Error fetching quality gate for {}: HTTP {}", project_key, response.status());
+            return Ok(None);
+        }
+
+        let data: ProjectStatusResponse = self.parse_json(response, &dump_name(project_key, branch, "quality_gate"))?;
+
+        Ok(Some(data.project_status))
+    }
+
+    /// Lists the quality profiles used for the project, one per language, sorted by language;
+    /// a [`PermissionDenied`] error when the server refuses (older servers need Administer
+    /// Quality Profiles permission for `project=`).
+    pub fn get_quality_profiles(&self, project_key: &str) -> Result<Option<Vec<QualityProfile>>> {
+        let url = self.api_url("qualityprofiles/search");
+
+        let request = self.client.get(&url).query(&[("project", project_key)]);
+        let response: Response = self.send_with_retry(request, self.analysis_retries)?;
+
+        check_permission(response.status(), "quality profiles", project_key)?;
+        if !response.status().is_success() {
+            eprintln!("Error fetching quality profiles for {}: HTTP {}", project_key, response.status());
+            return Ok(None);
+        }
+
+        let data: QualityProfilesResponse = self.parse_json(response, &dump_name(project_key, None, "quality_profiles"))?;
+
+        let mut profiles = data.profiles;
+        profiles.sort_by(|a, b| a.language.cmp(&b.language).then_with(|| a.name.cmp(&b.name)));
+        Ok(Some(profiles))
+    }
+
+    /// Fetches the [`RATING_METRICS`] from `/api/measures/component`; `None` when the request fails.
+    pub fn get_ratings(&self, project_key: &str, branch: Option<&str>) -> Result<Option<Ratings>> {
+        let url = self.api_url("measures/component");
+
+        let mut query = vec![("component", project_key.to_string()), ("metricKeys", RATING_METRICS.join(","))];
+        if let Some(branch) = branch {
+            query.push(("branch", branch.to_string()));
+        }
+
+        let request = self.client.get(&url).query(&query);
+        let response: Response = self.send_with_retry(request, self.analysis_retries)?;
+
+        check_permission(response.status(), "ratings", project_key)?;
+        if !response.status().is_success() {
+            eprintln!("Error fetching ratings for {}: HTTP {}", project_key, response.status());
+            return Ok(None);
+        }
+
+        let data: ComponentMeasuresResponse = self.parse_json(response, &dump_name(project_key, branch, "ratings"))?;
+
+        let value = |metric: &str| data.component.measures.iter()
+            .find(|measure| measure.metric == metric)
+            .and_then(|measure| measure.value.as_deref()?.parse().ok());
+        Ok(Some(Ratings {
+            security: value("security_rating"),
+            security_review: value("security_review_rating"),
+            reliability: value("reliability_rating"),
+        }))
+    }
+
+    /// Fetches `metric_keys` from `/api/measures/component` for `branch` when given (the main
+    /// branch otherwise), keyed by metric. Metrics the project has no value for (e.g. coverage
+    /// that was never imported) are left out; a failed request yields an empty map.
+    pub fn get_measures(&self, project_key: &str, branch: Option<&str>, metric_keys: &[&str]) -> Result<HashMap<String, String>> {
+        let url = self.api_url("measures/component");
+
+        let mut query = vec![("component", project_key.to_string()), ("metricKeys", metric_keys.join(","))];
+        if let Some(branch) = branch {
+            query.push(("branch", branch.to_string()));
+        }
+
+        let request = self.client.get(&url).query(&query);
+        let response: Response = self.send_with_retry(request, self.analysis_retries)?;
+
+        check_permission(response.status(), "measures", project_key)?;
+        if !response.status().is_success() {
+            eprintln!("Error fetching measures for {}: HTTP {}", project_key, response.status());
+            return Ok(HashMap::new());
+        }
+
+        let data: ComponentMeasuresResponse = self.parse_json(response, &dump_name(project_key, branch, "measures"))?;
+
+        Ok(data.component.measures.into_iter()
+            .filter_map(|measure| Some((measure.metric, measure.value?)))
+            .collect())
+    }
+
+    /// Sums remediation effort per severity with `facetMode=effort`. Servers that reject or
+    /// ignore `facetMode` (no `effortTotal` in the response) yield `None`.
+    pub fn get_effort_summary(&self, project_key: &str, filters: &IssueFilters) -> Result<Option<EffortSummary>> {
+        let url = self.api_url("issues/search");
+
+        let mut query = vec![
+            ("componentKeys", project_key.to_string()),
+            (self.api_compat.status_param(), "OPEN,CONFIRMED".to_string()),
+            ("ps", "1".to_string()),
+            ("facets", "severities".to_string()),
+            ("facetMode", "effort".to_string()),
+        ];
+        query.extend(filters.query_params(self.api_compat));
+
+        let request = self.client.get(&url).query(&query);
+        let response: Response = self.send_with_retry(request, self.issues_retries)?;
+
+        check_permission(response.status(), "remediation effort", project_key)?;
+        if !response.status().is_success() {
+            eprintln!("Error fetching remediation effort for {}: HTTP {}", project_key, response.status());
+            return Ok(None);
+        }
+
+        let data: IssueFacetsResponse = self.parse_json(response, &dump_name(project_key, filters.branch.as_deref(), "effort"))?;
+
+        let Some(total_minutes) = data.effort_total else {
+            return Ok(None);
+        };
+        let by_severity = data.facets.into_iter()
+            .filter(|facet| facet.property == "severities")
+            .flat_map(|facet| facet.values)
+            .map(|value| (value.val, value.count))
+            .collect();
+
+        Ok(Some(EffortSummary { total_minutes, by_severity }))
+    }
+
+    /// Counts open issues per severity using the `severities` facet, without fetching issue bodies.
+    /// Servers that reject a filter (e.g. `inNewCodePeriod` on old versions) yield `None`.
+    pub fn get_issue_counts(&self, project_key: &str, filters: &IssueFilters) -> Result<Option<IssueCounts>> {
+        let url = self.api_url("issues/search");
+
+        let mut query = vec![
+            ("componentKeys", project_key.to_string()),
+            (self.api_compat.status_param(), "OPEN,CONFIRMED".to_string()),
+            ("ps", "1".to_string()),
+            ("facets", "severities".to_string()),
+        ];
+        query.extend(filters.query_params(self.api_compat));
+
+        let request = self.client.get(&url).query(&query);
+        let response: Response = self.send_with_retry(request, self.issues_retries)?;
+
+        check_authentication(response.status(), project_key)?;
+        if !response.status().is_success() {
+            eprintln!("Error fetching issue counts for {}: HTTP {}", project_key, response.status());
+            return Ok(None);
+        }
+
+        let suffix = if filters.in_new_code_period { "new_code_counts" } else { "issue_counts" };
+        let suffix = if filters.rules.is_some() { format!("rules_{}", suffix) } else { suffix.to_string() };
+        let data: IssueFacetsResponse = self.parse_json(response, &dump_name(project_key, filters.branch.as_deref(), &suffix))?;
+
+        let by_severity = data.facets.into_iter()
+            .filter(|facet| facet.property == "severities")
+            .flat_map(|facet| facet.values)
+            .map(|value| (value.val, value.count))
+            .collect();
+
+        Ok(Some(IssueCounts {
+            total: data.paging.map(|p| p.total).or(data.total).unwrap_or(0),
+            by_severity,
+        }))
+    }
+}
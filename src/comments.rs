@@ -0,0 +1,49 @@
+use crate::client::SonarQubeClient;
+use crate::report::ProjectData;
+use crate::severity::meets_threshold;
+
+/// An issue selected for `--comment`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommentTarget {
+    pub issue_key: String,
+    /// `project (component:line) SEVERITY rule`, for the dry-run listing and failures.
+    pub description: String,
+}
+
+/// What [`post_comments`] did.
+#[derive(Debug, PartialEq)]
+pub enum CommentOutcome {
+    /// Nothing was posted because writes weren't confirmed.
+    DryRun,
+    /// Every target was tried; the ones that failed, with the reason.
+    Posted { failures: Vec<String> },
+}
+
+/// Listed issues at or above `min_severity`, restricted to `rules` unless empty. Issues
+/// without a key can't be commented on and are skipped.
+pub fn comment_targets(projects_data: &[ProjectData], min_severity: &str, rules: &[String]) -> Vec<CommentTarget> {
+    projects_data.iter()
+        .flat_map(|project| project.issues.iter().map(move |issue| (project, issue)))
+        .filter(|(_, issue)| meets_threshold(&issue.severity, min_severity))
+        .filter(|(_, issue)| rules.is_empty() || issue.rule.as_ref().is_some_and(|rule| rules.contains(rule)))
+        .filter_map(|(project, issue)| Some(CommentTarget {
+            issue_key: issue.key.clone()?,
+            description: format!("{} ({}:{}) {} {}", project.project_key, issue.component, issue.line,
+                issue.severity, issue.rule.as_deref().unwrap_or("-")),
+        }))
+        .collect()
+}
+
+/// Posts `text` on every target, or nothing at all unless `confirm_writes`. A failed comment
+/// doesn't stop the others.
+pub fn post_comments(client: &SonarQubeClient, targets: &[CommentTarget], text: &str, confirm_writes: bool) -> CommentOutcome {
+    if !confirm_writes {
+        return CommentOutcome::DryRun;
+    }
+
+    let failures = targets.iter()
+        .filter_map(|target| client.add_issue_comment(&target.issue_key, text).err()
+            .map(|error| format!("{} [{}]: {:#}", target.description, target.issue_key, error)))
+        .collect();
+    CommentOutcome::Posted { failures }
+}
@@ -0,0 +1,32 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+/// Collects the `--env-file` values from raw command-line arguments. The files have to be loaded
+/// before clap parses the arguments, since they can provide `env = ...` defaults.
+pub fn env_file_args<I: IntoIterator<Item = String>>(args: I) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        if arg == "--env-file" {
+            if let Some(path) = args.next() {
+                paths.push(PathBuf::from(path));
+            }
+        } else if let Some(path) = arg.strip_prefix("--env-file=") {
+            paths.push(PathBuf::from(path));
+        }
+    }
+    paths
+}
+
+/// Loads env files so that later files override earlier ones. dotenv never overwrites a variable
+/// that is already set, so the files are applied last to first; the real environment still wins
+/// over all of them. Call before `dotenv()` so these also override the default `.env`.
+pub fn load_env_files(paths: &[PathBuf]) -> Result<()> {
+    for path in paths.iter().rev() {
+        dotenv::from_path(path).context(format!("Error reading env file: {}", path.display()))?;
+    }
+    Ok(())
+}
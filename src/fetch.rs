@@ -0,0 +1,255 @@
+use crate::client::{IssueCounts, CODE_METRICS, IssueFilters, PermissionDenied, SonarQubeClient, MAX_SEARCH_RESULTS};
+use crate::report::{new_issues, sort_issues, without_rules, ProjectData};
+use anyhow::Result;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+
+/// What to fetch for each project.
+#[derive(Debug, Clone)]
+pub struct FetchOptions {
+    /// Maximum number of issues to fetch; `0` fetches only the per-severity counts.
+    pub max_issues: i32,
+    /// Always fetch per-severity issue counts, even alongside issue details.
+    pub issue_counts: bool,
+    /// Also fetch issue counts restricted to the new code period.
+    pub new_vs_overall: bool,
+    /// Also fetch the issue count history for a trend table.
+    pub trend: bool,
+    /// Also fetch per-directory issue counts.
+    pub hotspots_by_dir: bool,
+    /// Also fetch remediation effort per severity.
+    pub effort_summary: bool,
+    /// Also fetch the quality gate status and conditions.
+    pub quality_gate: bool,
+    /// Also fetch the quality profile used per language.
+    pub quality_profiles: bool,
+    /// Also fetch the security, security review and reliability ratings.
+    pub ratings: bool,
+    /// Also fetch the coverage and duplication measures.
+    pub measures: bool,
+    /// Fail on a 401/403 from an optional endpoint instead of noting it in the report.
+    pub strict_permissions: bool,
+    /// Record how long each project took to fetch.
+    pub timings: bool,
+    /// Keep only issues absent from this base branch.
+    pub compare_branch: Option<String>,
+    /// Rule keys whose issues are dropped from the listed issues and subtracted from counts.
+    pub ignore_rules: Vec<String>,
+    /// Also fetch the transitions and actions available on each listed issue.
+    pub with_transitions: bool,
+    pub filters: IssueFilters,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            max_issues: 10,
+            issue_counts: false,
+            new_vs_overall: false,
+            trend: false,
+            hotspots_by_dir: false,
+            effort_summary: false,
+            quality_gate: false,
+            quality_profiles: false,
+            ratings: false,
+            measures: false,
+            strict_permissions: false,
+            timings: false,
+            compare_branch: None,
+            ignore_rules: Vec::new(),
+            with_transitions: false,
+            filters: IssueFilters::default(),
+        }
+    }
+}
+
+/// Issues fetched when some are dropped after fetching, by `--compare-branch` or
+/// `--ignore-rules` (the server's page size limit).
+const LOCAL_FILTER_PAGE_SIZE: i32 = 500;
+
+/// Per-severity counts matching `filters`, minus the issues of `ignore_rules`. The API can't
+/// exclude rules, so their counts are fetched separately and subtracted.
+fn counts_without_rules(
+    client: &SonarQubeClient,
+    project_key: &str,
+    filters: &IssueFilters,
+    ignore_rules: &[String],
+) -> Result<Option<IssueCounts>> {
+    let Some(mut counts) = client.get_issue_counts(project_key, filters)? else {
+        return Ok(None);
+    };
+    if !ignore_rules.is_empty() {
+        let ignored_filters = IssueFilters { rules: Some(ignore_rules.join(",")), ..filters.clone() };
+        if let Some(ignored) = client.get_issue_counts(project_key, &ignored_filters)? {
+            counts.subtract(&ignored);
+        }
+    }
+    Ok(Some(counts))
+}
+
+/// Result of an optional fetch: unless `strict`, a [`PermissionDenied`] becomes `None` and
+/// `section` (the translation key of its report heading) is added to `denied`.
+fn optional<T>(result: Result<Option<T>>, section: &str, strict: bool, denied: &mut Vec<String>) -> Result<Option<T>> {
+    match result {
+        Err(error) if !strict && error.is::<PermissionDenied>() => {
+            eprintln!("Warning: {}", error);
+            denied.push(section.to_string());
+            Ok(None)
+        }
+        other => other,
+    }
+}
+
+pub fn fetch_project(client: &SonarQubeClient, project_key: &str, options: &FetchOptions) -> Result<ProjectData> {
+    let last_analysis = client.get_branch_analysis_date(project_key, options.filters.branch.as_deref())?;
+
+    let counts_only = options.max_issues == 0;
+    let base_branch = options.compare_branch.as_deref().filter(|_| !counts_only);
+    // Dropping issues locally needs more than the displayed ones, or a project whose newest
+    // issues are all on the base branch or of ignored rules would show none.
+    let filters_locally = base_branch.is_some() || !options.ignore_rules.is_empty();
+    let page_size = if filters_locally { LOCAL_FILTER_PAGE_SIZE.max(options.max_issues) } else { options.max_issues };
+    let mut issues = if counts_only {
+        Vec::new()
+    } else {
+        client.search_issues(project_key, page_size, &options.filters, options.with_transitions)?
+    };
+    if !options.ignore_rules.is_empty() {
+        issues = without_rules(&issues, &options.ignore_rules);
+    }
+    sort_issues(&mut issues);
+
+    let mut compared_to = None;
+    if let Some(base) = base_branch {
+        if client.get_branch_analysis_date(project_key, Some(base))?.is_none() {
+            eprintln!("Warning: branch {} of {} has no analysis; showing all issues", base, project_key);
+        } else {
+            let base_filters = IssueFilters { branch: Some(base.to_string()), ..options.filters.clone() };
+            // All of the base branch, as far as the server pages: an issue left out would be
+            // reported as new. Small branches still take a single request.
+            let base_issues = client.search_issues(project_key, MAX_SEARCH_RESULTS as i32, &base_filters, false)?;
+            issues = new_issues(&issues, &base_issues);
+            compared_to = Some(base.to_string());
+        }
+    }
+    issues.truncate(options.max_issues.max(0) as usize);
+
+    let issue_counts = if counts_only || options.issue_counts || options.new_vs_overall {
+        counts_without_rules(client, project_key, &options.filters, &options.ignore_rules)?
+    } else {
+        None
+    };
+    let new_code_counts = if options.new_vs_overall {
+        let new_code_filters = IssueFilters { in_new_code_period: true, ..options.filters.clone() };
+        counts_without_rules(client, project_key, &new_code_filters, &options.ignore_rules)?
+    } else {
+        None
+    };
+
+    let strict = options.strict_permissions;
+    let mut denied_sections = Vec::new();
+
+    let trend = if options.trend {
+        optional(client.get_issue_history(project_key).map(Some), "issue_trend", strict, &mut denied_sections)?
+    } else {
+        None
+    };
+
+    let worst_directories = if options.hotspots_by_dir {
+        optional(client.get_directory_issue_counts(project_key).map(Some), "worst_directories", strict, &mut denied_sections)?
+    } else {
+        None
+    };
+
+    let effort = if options.effort_summary {
+        optional(client.get_effort_summary(project_key, &options.filters), "remediation_effort", strict, &mut denied_sections)?
+    } else {
+        None
+    };
+
+    let quality_gate = if options.quality_gate {
+        optional(client.get_quality_gate_status(project_key, options.filters.branch.as_deref()), "quality_gate", strict, &mut denied_sections)?
+    } else {
+        None
+    };
+
+    let quality_profiles = if options.quality_profiles {
+        optional(client.get_quality_profiles(project_key), "quality_profiles", strict, &mut denied_sections)?
+    } else {
+        None
+    };
+
+    let ratings = if options.ratings {
+        optional(client.get_ratings(project_key, options.filters.branch.as_deref()), "ratings", strict, &mut denied_sections)?
+    } else {
+        None
+    };
+
+    let measures = if options.measures {
+        optional(client.get_measures(project_key, options.filters.branch.as_deref(), &CODE_METRICS)
+            // Sorted for stable JSON output.
+            .map(|measures| Some(measures.into_iter().collect())), "measures", strict, &mut denied_sections)?
+    } else {
+        None
+    };
+
+    Ok(ProjectData {
+        project_key: project_key.to_string(),
+        last_analysis,
+        issues,
+        issue_counts,
+        counts_only,
+        new_code_counts,
+        trend,
+        worst_directories,
+        effort,
+        compared_to,
+        quality_gate,
+        quality_profiles,
+        ratings,
+        measures,
+        denied_sections,
+        fetch_time: None,
+    })
+}
+
+/// Fetches `project_keys` on up to `concurrency` threads sharing `client`. Results are in the
+/// order of `project_keys` whatever order they complete in, and a project that fails doesn't
+/// stop the others.
+pub fn fetch_projects(
+    client: &SonarQubeClient,
+    project_keys: &[String],
+    options: &FetchOptions,
+    concurrency: usize,
+) -> Vec<Result<ProjectData>> {
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<ProjectData>>>> = Mutex::new(project_keys.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..concurrency.clamp(1, project_keys.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let Some(project_key) = project_keys.get(index) else {
+                    break;
+                };
+                // One eprintln! per line: stderr is locked per call, so lines never interleave.
+                eprintln!("Fetching data for project: {}...", project_key);
+
+                let started = Instant::now();
+                let result = fetch_project(client, project_key, options).map(|mut project| {
+                    if options.timings {
+                        project.fetch_time = Some(started.elapsed());
+                    }
+                    project
+                });
+                results.lock().expect("a fetch thread panicked")[index] = Some(result);
+            });
+        }
+    });
+
+    results.into_inner().expect("a fetch thread panicked").into_iter()
+        .map(|result| result.expect("every project is fetched"))
+        .collect()
+}
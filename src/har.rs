@@ -0,0 +1,270 @@
+use anyhow::Result;
+use chrono::{SecondsFormat, Utc};
+use reqwest::blocking::{Client, Request, Response};
+use reqwest::ResponseBuilderExt;
+use reqwest::header::{HeaderMap, AUTHORIZATION, CONTENT_TYPE, LOCATION};
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Records every HTTP exchange into an HTTP Archive (HAR 1.2) file for `--har`, which browser
+/// devtools and most HTTP debuggers can open.
+///
+/// Entries are kept in memory and the file is written once, by [`HarRecorder::finish`] or when
+/// the recorder is dropped, so it also covers runs that stop with an error.
+pub struct HarRecorder {
+    path: PathBuf,
+    /// Headers the client adds to every request (not visible on the built `Request`).
+    default_headers: Vec<HarHeader>,
+    api_token: String,
+    entries: Mutex<Vec<HarEntry>>,
+}
+
+#[derive(Debug, Serialize)]
+struct HarFile<'a> {
+    log: HarLog<'a>,
+}
+
+#[derive(Debug, Serialize)]
+struct HarLog<'a> {
+    version: &'static str,
+    creator: HarCreator,
+    entries: &'a [HarEntry],
+}
+
+#[derive(Debug, Serialize)]
+struct HarCreator {
+    name: &'static str,
+    version: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct HarHeader {
+    name: String,
+    value: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HarEntry {
+    started_date_time: String,
+    /// Milliseconds from sending the request to reading the whole body.
+    time: f64,
+    request: HarRequest,
+    response: HarResponse,
+    cache: serde_json::Value,
+    timings: HarTimings,
+    /// Transport error when no response arrived; `response.status` is 0 then.
+    #[serde(rename = "_error", skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HarRequest {
+    method: String,
+    url: String,
+    http_version: String,
+    headers: Vec<HarHeader>,
+    query_string: Vec<HarHeader>,
+    cookies: Vec<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    post_data: Option<HarPostData>,
+    headers_size: i64,
+    body_size: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HarPostData {
+    mime_type: String,
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HarResponse {
+    status: u16,
+    status_text: String,
+    http_version: String,
+    headers: Vec<HarHeader>,
+    cookies: Vec<serde_json::Value>,
+    content: HarContent,
+    #[serde(rename = "redirectURL")]
+    redirect_url: String,
+    headers_size: i64,
+    body_size: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HarContent {
+    size: usize,
+    mime_type: String,
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+struct HarTimings {
+    send: f64,
+    wait: f64,
+    receive: f64,
+}
+
+/// HAR headers with `Authorization` replaced by `***`.
+fn har_headers(headers: &HeaderMap) -> Vec<HarHeader> {
+    headers.iter()
+        .map(|(name, value)| HarHeader {
+            name: name.to_string(),
+            value: if *name == AUTHORIZATION {
+                "***".to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            },
+        })
+        .collect()
+}
+
+/// Value of `name` in `headers`, or an empty string.
+fn header_text(headers: &HeaderMap, name: reqwest::header::HeaderName) -> String {
+    headers.get(name).and_then(|value| value.to_str().ok()).unwrap_or_default().to_string()
+}
+
+impl HarRecorder {
+    pub fn new(path: PathBuf, default_headers: &HeaderMap, api_token: &str) -> Self {
+        Self {
+            path,
+            default_headers: har_headers(default_headers),
+            api_token: api_token.to_string(),
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Executes `request` and records the exchange. The body is read here and handed back in
+    /// a rebuilt response, so callers read it as usual.
+    pub fn execute(&self, client: &Client, request: Request) -> Result<Response> {
+        let mut headers = self.default_headers.clone();
+        for header in har_headers(request.headers()) {
+            headers.retain(|existing| !existing.name.eq_ignore_ascii_case(&header.name));
+            headers.push(header);
+        }
+        let body = request.body().and_then(|body| body.as_bytes()).unwrap_or_default();
+        let post_data = (!body.is_empty()).then(|| HarPostData {
+            mime_type: header_text(request.headers(), CONTENT_TYPE),
+            text: self.redact(&String::from_utf8_lossy(body)),
+        });
+        let har_request = HarRequest {
+            method: request.method().to_string(),
+            url: request.url().to_string(),
+            http_version: format!("{:?}", request.version()),
+            headers,
+            query_string: request.url().query_pairs()
+                .map(|(name, value)| HarHeader { name: name.into_owned(), value: value.into_owned() })
+                .collect(),
+            cookies: Vec::new(),
+            body_size: body.len() as i64,
+            post_data,
+            headers_size: -1,
+        };
+
+        let started_date_time = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+        let start = Instant::now();
+        let outcome = client.execute(request).and_then(|response| {
+            let status = response.status();
+            let version = response.version();
+            let headers = response.headers().clone();
+            let url = response.url().clone();
+            response.bytes().map(|body| (status, version, headers, url, body))
+        });
+        let time = start.elapsed().as_secs_f64() * 1000.0;
+
+        let (har_response, error, rebuilt) = match outcome {
+            Ok((status, version, headers, url, body)) => {
+                let text = self.redact(&String::from_utf8_lossy(&body));
+                let har_response = HarResponse {
+                    status: status.as_u16(),
+                    status_text: status.canonical_reason().unwrap_or_default().to_string(),
+                    http_version: format!("{:?}", version),
+                    headers: har_headers(&headers),
+                    cookies: Vec::new(),
+                    content: HarContent {
+                        size: body.len(),
+                        mime_type: header_text(&headers, CONTENT_TYPE),
+                        text,
+                    },
+                    redirect_url: header_text(&headers, LOCATION),
+                    headers_size: -1,
+                    body_size: body.len() as i64,
+                };
+
+                let mut rebuilt = http::Response::builder()
+                    .status(status)
+                    .version(version)
+                    .url(url)
+                    .body(body.to_vec())
+                    .expect("status and version come from a valid response");
+                *rebuilt.headers_mut() = headers;
+                (har_response, None, Ok(Response::from(rebuilt)))
+            }
+            Err(error) => {
+                let har_response = HarResponse {
+                    status: 0,
+                    status_text: String::new(),
+                    http_version: String::new(),
+                    headers: Vec::new(),
+                    cookies: Vec::new(),
+                    content: HarContent { size: 0, mime_type: String::new(), text: String::new() },
+                    redirect_url: String::new(),
+                    headers_size: -1,
+                    body_size: -1,
+                };
+                (har_response, Some(error.to_string()), Err(error.into()))
+            }
+        };
+
+        self.record(HarEntry {
+            started_date_time,
+            time,
+            request: har_request,
+            response: har_response,
+            cache: serde_json::json!({}),
+            timings: HarTimings { send: 0.0, wait: time, receive: 0.0 },
+            error,
+        });
+        rebuilt
+    }
+
+    /// `text` with the API token replaced by `***`.
+    fn redact(&self, text: &str) -> String {
+        if self.api_token.is_empty() { text.to_string() } else { text.replace(&self.api_token, "***") }
+    }
+
+    fn record(&self, entry: HarEntry) {
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(entry);
+    }
+
+    /// Writes the recorded exchanges to the HAR file, replacing it.
+    pub fn finish(&self) -> Result<()> {
+        let entries = self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let file = HarFile {
+            log: HarLog {
+                version: "1.2",
+                creator: HarCreator { name: env!("CARGO_PKG_NAME"), version: env!("CARGO_PKG_VERSION") },
+                entries: &entries,
+            },
+        };
+        let json = serde_json::to_string_pretty(&file)?;
+        fs::write(&self.path, json)?;
+        Ok(())
+    }
+}
+
+impl Drop for HarRecorder {
+    fn drop(&mut self) {
+        if let Err(error) = self.finish() {
+            eprintln!("Warning: could not write {}: {}", self.path.display(), error);
+        }
+    }
+}
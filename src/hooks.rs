@@ -0,0 +1,60 @@
+use crate::client::IssueData;
+use crate::report::ProjectData;
+use crate::severity::meets_threshold;
+use std::process::Command;
+
+/// Runs `command` once per issue at or above `min_severity`, passing the issue through
+/// environment variables rather than the command line so no shell escaping is involved:
+///
+/// - `SQ_PROJECT` — project key
+/// - `SQ_SEVERITY` — e.g. `CRITICAL`
+/// - `SQ_MESSAGE` — rule message
+/// - `SQ_COMPONENT` — component key
+/// - `SQ_LINE` — line number, or `N/A`
+///
+/// The command's stdout goes to our stderr so it can't interleave with a report on stdout.
+/// Returns one description per invocation that failed to start or exited non-zero.
+pub fn run_issue_hook(command: &str, min_severity: &str, projects_data: &[ProjectData]) -> Vec<String> {
+    let mut failures = Vec::new();
+
+    for project in projects_data {
+        for issue in project.issues.iter().filter(|issue| meets_threshold(&issue.severity, min_severity)) {
+            let outcome = shell_command(command)
+                .envs(issue_env(&project.project_key, issue))
+                .stdout(std::io::stderr())
+                .status();
+
+            match outcome {
+                Ok(status) if status.success() => {}
+                Ok(status) => failures.push(format!("{} ({}:{}): {}", project.project_key, issue.component, issue.line, status)),
+                Err(error) => failures.push(format!("{} ({}:{}): {}", project.project_key, issue.component, issue.line, error)),
+            }
+        }
+    }
+
+    failures
+}
+
+fn issue_env(project_key: &str, issue: &IssueData) -> [(&'static str, String); 5] {
+    [
+        ("SQ_PROJECT", project_key.to_string()),
+        ("SQ_SEVERITY", issue.severity.clone()),
+        ("SQ_MESSAGE", issue.message.clone()),
+        ("SQ_COMPONENT", issue.component.clone()),
+        ("SQ_LINE", issue.line.clone()),
+    ]
+}
+
+#[cfg(unix)]
+fn shell_command(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
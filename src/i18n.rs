@@ -1,59 +1,151 @@
-use clap::ValueEnum;
-use once_cell::sync::Lazy;
+use anyhow::{Context, Result};
+use once_cell::sync::{Lazy, OnceCell};
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
 
-#[derive(Debug, Deserialize)]
+/// Language used when a text is missing in the requested one.
+pub const FALLBACK_LANGUAGE: &str = "en";
+
+/// Report texts per language code (e.g. `en`, `pt-BR`), then per key.
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
 pub struct Translations {
-    pub en: HashMap<String, String>,
-    pub ru: HashMap<String, String>,
+    pub languages: HashMap<String, HashMap<String, String>>,
+}
+
+impl Translations {
+    /// Parses the `translations.yaml` layout: a map of key: text per language code. Any codes
+    /// may appear, and keys may be left out.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let translations: Self = serde_yaml::from_str(text)
+            .map_err(|error| format!("expected maps of key: text per language code: {}", error))?;
+        if let Some(code) = translations.languages.keys().find(|code| !is_language_code(code)) {
+            return Err(format!("'{}' is not a language code like en or pt-BR", code));
+        }
+        Ok(translations)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read translations file {}", path.display()))?;
+        Self::parse(&text)
+            .map_err(|error| anyhow::anyhow!("Invalid translations file {}: {}", path.display(), error))
+    }
+
+    /// `code` as spelled in these translations, matched case-insensitively.
+    fn find_language(&self, code: &str) -> Option<&str> {
+        self.languages.keys().find(|known| known.eq_ignore_ascii_case(code)).map(String::as_str)
+    }
+
+    /// Text of `key` in the language `code` only, without falling back to English.
+    pub fn translate(&self, key: &str, code: &str) -> Option<&str> {
+        let language = self.find_language(code)?;
+        self.languages[language].get(key).map(String::as_str)
+    }
+}
+
+/// Letters, optionally followed by `-` or `_` separated letter/digit subtags: `en`, `pt-BR`.
+fn is_language_code(code: &str) -> bool {
+    let mut parts = code.split(['-', '_']);
+    parts.next().is_some_and(|primary| !primary.is_empty() && primary.chars().all(|c| c.is_ascii_alphabetic()))
+        && parts.all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// Translations loaded with `--translations-file`, consulted before the built-in ones.
+static CUSTOM_TRANSLATIONS: OnceCell<Translations> = OnceCell::new();
+
+/// Installs `translations` over the built-in ones for the rest of the process. Returns
+/// `false`, leaving the first set in place, when called more than once.
+pub fn set_custom_translations(translations: Translations) -> bool {
+    CUSTOM_TRANSLATIONS.set(translations).is_ok()
 }
 
 pub static TRANSLATIONS: Lazy<Translations> = Lazy::new(|| {
     let yaml_content = include_str!("../translations.yaml");
-    serde_yaml::from_str(yaml_content).unwrap_or_else(|_| {
-        let mut en = HashMap::new();
-        en.insert("report_title".to_string(), "SonarQube Analysis Report".to_string());
-        en.insert("generated".to_string(), "Generated".to_string());
-        en.insert("project".to_string(), "Project".to_string());
-        en.insert("last_analysis".to_string(), "Last Analysis".to_string());
-        en.insert("latest_issues".to_string(), "Latest Issues".to_string());
-        en.insert("no_analysis_available".to_string(), "No analysis available".to_string());
-        en.insert("no_open_issues".to_string(), "No open issues found.".to_string());
-        en.insert("severity".to_string(), "Severity".to_string());
-        en.insert("message".to_string(), "Message".to_string());
-        en.insert("component".to_string(), "Component".to_string());
-        en.insert("line".to_string(), "Line".to_string());
-
-        let ru = HashMap::new();
-        
-        Translations { en, ru }
+    Translations::parse(yaml_content).unwrap_or_else(|_| {
+        let en: HashMap<String, String> = [
+            ("report_title", "SonarQube Analysis Report"),
+            ("generated", "Generated"),
+            ("project", "Project"),
+            ("last_analysis", "Last Analysis"),
+            ("latest_issues", "Latest Issues"),
+            ("no_analysis_available", "No analysis available"),
+            ("no_open_issues", "No open issues found."),
+            ("severity", "Severity"),
+            ("message", "Message"),
+            ("component", "Component"),
+            ("line", "Line"),
+        ].into_iter().map(|(key, text)| (key.to_string(), text.to_string())).collect();
+
+        Translations { languages: HashMap::from([(FALLBACK_LANGUAGE.to_string(), en)]) }
     })
 });
 
-#[derive(Debug, Clone, Copy, ValueEnum, PartialEq)]
-pub enum Language {
-    En,
-    Ru,
+/// Translation layers in lookup order: `--translations-file` first, then the built-in ones.
+fn layers() -> Vec<&'static Translations> {
+    CUSTOM_TRANSLATIONS.get().into_iter().chain([&*TRANSLATIONS]).collect()
 }
 
+/// A report language, identified by its code in the loaded translations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Language(&'static str);
+
 impl Language {
-    pub fn from_str(s: &str) -> Self {
-        match s.to_lowercase().as_str() {
-            "ru" => Language::Ru,
-            _ => Language::En,
-        }
+    pub const EN: Language = Language("en");
+    pub const RU: Language = Language("ru");
+
+    /// The language `code` names in the loaded translations (case-insensitively), spelled as
+    /// there; `None` when no translations exist for it.
+    pub fn known(code: &str) -> Option<Self> {
+        layers().into_iter().find_map(|layer| layer.find_language(code)).map(Language)
+    }
+
+    /// Codes of every loaded language, sorted.
+    pub fn available() -> Vec<&'static str> {
+        let mut codes: Vec<&'static str> = layers().into_iter()
+            .flat_map(|layer| layer.languages.keys().map(String::as_str))
+            .collect();
+        codes.sort_unstable();
+        codes.dedup();
+        codes
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.0
     }
 }
 
-pub fn get_translation(key: &str, language: &Language) -> String {
-    let translations = match language {
-        Language::En => &TRANSLATIONS.en,
-        Language::Ru => &TRANSLATIONS.ru,
-    };
-    
-    translations.get(key)
-        .or_else(|| TRANSLATIONS.en.get(key))
-        .map(|s| s.clone())
-        .unwrap_or_else(|| key.to_string())
-}
\ No newline at end of file
+/// Like [`Language::known`], but unknown codes map to English, so parsing never fails.
+impl FromStr for Language {
+    type Err = Infallible;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Ok(Self::known(code).unwrap_or(Language::EN))
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+/// Text of `key` in `locale`, looked up in `layers` in order, then again in English; the key
+/// itself when no layer has it.
+pub fn translate_with(layers: &[&Translations], key: &str, locale: &str) -> String {
+    [locale, FALLBACK_LANGUAGE].iter()
+        .find_map(|locale| layers.iter().find_map(|layer| layer.translate(key, locale)))
+        .unwrap_or(key)
+        .to_string()
+}
+
+/// Text of `key` in the language `locale` (e.g. `ru`, or [`Language::code`]), falling back
+/// to English.
+pub fn get_translation(key: &str, locale: &str) -> String {
+    translate_with(&layers(), key, locale)
+}
@@ -1,5 +1,20 @@
+pub mod badges;
+pub mod cache;
 pub mod client;
+pub mod comments;
+pub mod compat;
+pub mod env;
+pub mod fetch;
+pub mod har;
+pub mod hooks;
+pub mod manifest;
 pub mod report;
+pub mod severity;
+pub mod state;
+pub mod sink;
+pub mod suppressions;
+pub mod theme;
+pub mod thresholds;
 pub mod i18n;
 
 #[cfg(test)]
@@ -1,15 +1,30 @@
-mod client;
-mod i18n;
-mod report;
-
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use chrono::{Local, NaiveDate, Utc};
 use clap::Parser;
 use dotenv::dotenv;
+use encoding_rs::Encoding;
 use std::fs;
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::time::Duration;
 
-use crate::client::SonarQubeClient;
-use crate::i18n::Language;
-use crate::report::{MarkdownReportGenerator, ProjectData};
+use sonarqube_checker::badges::write_badges;
+use sonarqube_checker::cache::{load_projects, save_projects};
+use sonarqube_checker::comments::{comment_targets, post_comments, CommentOutcome};
+use sonarqube_checker::compat::ApiCompat;
+use sonarqube_checker::env::{env_file_args, load_env_files};
+use sonarqube_checker::client::{decode_project_key, is_path_key, DEFAULT_BATCH_SIZE, last_days_start, parse_date, parse_last_days, parse_directories, redact_url, AuthScheme, AuthenticationFailed, ClientOptions, IssueFilters, MinTlsVersion, SonarQubeClient};
+use sonarqube_checker::fetch::{fetch_projects, FetchOptions};
+use sonarqube_checker::hooks::run_issue_hook;
+use sonarqube_checker::i18n::{set_custom_translations, Language, Translations};
+use sonarqube_checker::manifest::RunManifest;
+use sonarqube_checker::report::{render_report, GroupBy, OutputFormat, ProjectData, ReportOptions};
+use sonarqube_checker::severity::is_known_severity;
+use sonarqube_checker::state::{truncated_projects, RunState};
+use sonarqube_checker::sink::{encode_report, parse_encoding, sink_for, GithubSummarySink, OutputSink};
+use sonarqube_checker::suppressions::{load_suppressions, SuppressionCheck};
+use sonarqube_checker::theme::Theme;
+use sonarqube_checker::thresholds::{failed_gates, unchecked_gates, SeverityExitCodes, Thresholds, DEFAULT_SEVERITY_EXIT_CODES, GATE_FAILED_EXIT_CODE};
 
 #[derive(Parser)]
 #[command(
@@ -32,6 +47,15 @@ struct Args {
     )]
     token: Option<String>,
 
+    #[arg(
+        long,
+        value_enum,
+        env = "SONARQUBE_AUTH_MODE",
+        default_value_t = AuthScheme::Basic,
+        help = "How to send the token: as the user name of HTTP Basic auth (every version), or as a Bearer token (SonarQube 10.0+, SonarCloud)"
+    )]
+    auth_mode: AuthScheme,
+
     #[arg(
         long,
         env = "SONARQUBE_PROJECTS",
@@ -39,73 +63,1056 @@ struct Args {
     )]
     projects: Option<String>,
 
+    #[arg(
+        long = "env-file",
+        value_name = "PATH",
+        help = "Load environment variables from this file after .env; repeatable, later files override earlier ones"
+    )]
+    env_files: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "KEY",
+        help = "Report on every project in this portfolio or application instead of --projects (Enterprise edition and above)"
+    )]
+    portfolio: Option<String>,
+
+    #[arg(
+        long,
+        help = "Fail instead of writing an empty report when no projects are resolved"
+    )]
+    error_on_empty: bool,
+
     #[arg(
         long,
         env = "SONARQUBE_MAX_ISSUES",
         default_value = "10",
-        help = "Maximum number of issues to fetch per project"
+        help = "Maximum number of issues to fetch per project (0 shows only per-severity counts)"
     )]
     max_issues: i32,
 
     #[arg(
         long,
-        help = "Output file path (e.g., report.md). If not specified, prints to console"
+        help = "Output file path (e.g., report.md) or s3://bucket/key / gs://bucket/key URL (needs the s3 / gcs build feature); repeat to write several formats, inferred from each extension. If not specified, prints to console"
+    )]
+    output: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "ENCODING",
+        value_parser = parse_encoding,
+        help = "Character encoding of --output files (e.g. windows-1251); unrepresentable characters become &#NNNN; references [default: utf-8]"
+    )]
+    output_encoding: Option<&'static Encoding>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Report format for console output and every --output path, overriding their extensions [default: markdown, or each output's extension]"
+    )]
+    format: Option<OutputFormat>,
+
+    #[arg(
+        long,
+        value_name = "PREFIX",
+        help = "Prefix stripped from component keys to make paths repository-relative (e.g. my-project:), used by --format github"
+    )]
+    repo_root: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "YYYY-MM-DD",
+        value_parser = parse_date,
+        help = "Only include issues created on or after this date"
+    )]
+    since: Option<NaiveDate>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = parse_last_days,
+        conflicts_with = "since",
+        help = "Only include issues created in the last N days (a rolling --since)"
+    )]
+    last_days: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "YYYY-MM-DD",
+        value_parser = parse_date,
+        help = "Only include issues created before this date; must be after --since or --last-days"
+    )]
+    created_before: Option<NaiveDate>,
+
+    #[arg(
+        long,
+        help = "Only include issues assigned to the user owning the token"
+    )]
+    mine: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "unassigned",
+        help = "Only include issues assigned to someone (triaged)"
+    )]
+    assigned: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "mine",
+        help = "Only include issues assigned to nobody (untriaged)"
+    )]
+    unassigned: bool,
+
+    #[arg(
+        long,
+        help = "Only show issues on the exact component keys given (e.g. a file), not on everything below them"
+    )]
+    component_keys_exact: bool,
+
+    #[arg(
+        long,
+        value_name = "DIRS",
+        value_parser = parse_directories,
+        help = "Only include issues in these comma-separated directories (e.g. src/main,src/lib), filtered by the server"
+    )]
+    directories: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "RULES",
+        value_delimiter = ',',
+        help = "Leave out issues of these comma-separated rule keys (e.g. java:S1481,python:S125), also from counts and --thresholds"
     )]
-    output: Option<String>,
+    ignore_rules: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Report on this branch instead of the main branch"
+    )]
+    branch: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "BASE",
+        help = "Only show issues on --branch (or the main branch) that are not on branch BASE"
+    )]
+    compare_branch: Option<String>,
 
     #[arg(
         long,
         env = "SONARQUBE_REPORT_LANGUAGE",
+        value_name = "CODE",
         default_value = "en",
+        help = "Report language: en, ru, or any language code in --translations-file"
+    )]
+    language: String,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "YAML file overriding report wording, in the layout of translations.yaml (en:/ru: maps of key: text); keys left out keep the built-in text"
+    )]
+    translations_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        overrides_with = "no_footer",
+        help = "Append a \"Generated by\" footer with the tool version and server URL"
+    )]
+    footer: bool,
+
+    #[arg(
+        long,
+        overrides_with = "footer",
+        help = "Omit the \"Generated by\" footer (default)"
+    )]
+    no_footer: bool,
+
+    #[arg(
+        long,
+        help = "Show issue counts in new code next to overall counts for each project"
+    )]
+    new_vs_overall: bool,
+
+    #[arg(
+        long,
+        help = "Append a legend explaining the severity levels"
+    )]
+    legend: bool,
+
+    #[arg(
+        long,
+        help = "Add a \"Found by\" column with each issue's SCM author"
+    )]
+    show_author: bool,
+
+    #[arg(
+        long,
+        help = "Start the report with a table of issue counts per severity for each project and in total"
+    )]
+    summary: bool,
+
+    #[arg(
+        long,
+        help = "Add a summary of issue counts per SCM author"
+    )]
+    author_summary: bool,
+
+    #[arg(
+        long,
+        help = "Add a report-wide table of listed issues per file extension"
+    )]
+    by_extension: bool,
+
+    #[arg(
+        long,
+        help = "Drop issue table columns (e.g. line) that are N/A for every issue of a project"
+    )]
+    hide_na_columns: bool,
+
+    #[arg(
+        long,
+        help = "Within each project, list issues under one subheading per severity, worst first"
+    )]
+    severity_first: bool,
+
+    #[arg(
+        long,
         value_enum,
-        help = "Report language"
+        default_value_t = GroupBy::Project,
+        help = "Section the Markdown report per project, per severity across projects (worst first), or per rule across projects (most occurrences first)"
+    )]
+    group_by: GroupBy,
+
+    #[arg(
+        long,
+        help = "Only report issues in new code, grouped under the SCM author who introduced them"
+    )]
+    new_code_by_author: bool,
+
+    #[arg(
+        long,
+        help = "Show the line range (e.g. 42-47) instead of the first line for issues spanning several lines"
+    )]
+    line_ranges: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "2",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+        help = "Show only the last N path segments of components in issue tables, e.g. …/foo/Bar.java (N defaults to 2)"
+    )]
+    shorten_components: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Link components in Markdown and HTML issue tables to the issue in SonarQube"
+    )]
+    issue_links: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Truncate the Markdown report at a project boundary so it stays within N bytes (e.g. 65536 for GitHub comments)"
+    )]
+    max_report_bytes: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Show the SonarQube server version in the report header"
+    )]
+    show_server_version: bool,
+
+    #[arg(
+        long,
+        help = "Add a per-project table and sparkline of open issue counts over past analyses"
+    )]
+    trend: bool,
+
+    #[arg(
+        long,
+        help = "Add a per-project table of the 10 directories with the most open issues"
+    )]
+    hotspots_by_dir: bool,
+
+    #[arg(
+        long,
+        help = "Add a per-project table of remediation effort per severity (facetMode=effort)"
+    )]
+    effort_summary: bool,
+
+    #[arg(
+        long,
+        value_name = "LANG",
+        help = "Accept-Language header (e.g. ru, de-DE) asking the server for localized rule messages where it supports them; report labels use --language"
+    )]
+    accept_language: Option<String>,
+
+    #[arg(
+        long,
+        help = "Before anything else, find the web API's context path by probing /api/system/status at the root, then below /sonar"
+    )]
+    autodetect_path: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Web API parameter names to use (8.x, 9.x, 10.x) [default: probe /api/server/version, else 9.x]"
+    )]
+    api_compat: Option<ApiCompat>,
+
+    #[arg(
+        short,
+        long,
+        help = "Print extra diagnostics to stderr"
+    )]
+    verbose: bool,
+
+    #[arg(
+        long,
+        help = "Note each project's fetch time under its report section"
+    )]
+    timings: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 4,
+        value_parser = clap::value_parser!(u16).range(1..),
+        help = "Fetch up to N projects at the same time"
+    )]
+    concurrency: u16,
+
+    #[arg(
+        long,
+        help = "Omit the generation time so reports of unchanged data are identical (diff-friendly)"
+    )]
+    no_timestamp: bool,
+
+    #[arg(
+        long,
+        help = "Omit the --- separators between report sections (avoids page breaks in PDF/print)"
+    )]
+    no_separators: bool,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Save every raw JSON API response (token redacted) to DIR before parsing, for debugging"
+    )]
+    dump_raw: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Record every HTTP request and response (Authorization redacted) into a HAR file, for debugging"
+    )]
+    har: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Write a shields.io endpoint badge (<project>.json) per project into DIR, showing its issue count and worst severity"
+    )]
+    badges_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "YAML or JSON file overriding the severity colors of the HTML report, badges and plain text on a terminal, e.g. blocker: {html: \"#ff0000\", ansi: \"1;31\"}"
     )]
-    language: Language,
+    theme: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Write a JSON manifest of the run (version, server, filters, projects and their issue counts) to PATH"
+    )]
+    manifest: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with = "compare_branch",
+        help = "Report only issues new since the previous run recorded in PATH, then update it (the first run reports everything); every open issue must fit in --max-issues"
+    )]
+    state_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Save the fetched project data as JSON to PATH, for re-rendering reports with --offline"
+    )]
+    cache_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        requires = "cache_file",
+        conflicts_with_all = ["check_suppressions", "portfolio", "mine", "comment", "state_file", "manifest", "autodetect_path", "show_server_version"],
+        help = "Render the reports from the data saved with --cache-file instead of contacting the server"
+    )]
+    offline: bool,
+
+    #[arg(
+        long,
+        help = "Also append the Markdown report to the GitHub Actions job summary ($GITHUB_STEP_SUMMARY)"
+    )]
+    github_summary: bool,
+
+    #[arg(
+        long,
+        env = "SONARQUBE_RETRIES",
+        default_value = "3",
+        help = "Retries for connection errors, HTTP 429 and 5xx responses, with exponential backoff"
+    )]
+    retries: u32,
+
+    #[arg(
+        long,
+        help = "Retries for the analysis-date request [default: --retries]"
+    )]
+    analysis_retries: Option<u32>,
+
+    #[arg(
+        long,
+        help = "Retries for issue search requests [default: --retries]"
+    )]
+    issues_retries: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Abort once N HTTP requests (retries and redirects included) have been sent, protecting shared servers from runaway runs [default: unlimited]"
+    )]
+    max_api_calls: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Allow at most N retries across the whole run; after that, requests fail on their first error [default: unlimited]"
+    )]
+    retry_budget: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = DEFAULT_BATCH_SIZE,
+        value_parser = parse_batch_size,
+        help = "Most keys per request when looking up many keys at once (e.g. --check-suppressions); lists are also split to keep URLs under ~2000 characters"
+    )]
+    batch_size: usize,
+
+    #[arg(
+        long,
+        default_value = "0.5",
+        value_parser = parse_jitter,
+        help = "Randomize each retry delay by up to this fraction (0-1) so parallel CI jobs don't retry in lockstep"
+    )]
+    retry_jitter: f64,
+
+    #[arg(
+        long,
+        value_name = "SECS",
+        env = "SONARQUBE_TIMEOUT",
+        default_value_t = 30,
+        help = "Give up on an HTTP request after this many seconds, connecting included; 0 disables the timeout"
+    )]
+    timeout_secs: u64,
+
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Keep idle pooled connections open for this many seconds (default: reqwest's)"
+    )]
+    pool_idle_timeout: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "Send requests through this proxy (e.g. http://proxy.corp:3128) instead of HTTP_PROXY/HTTPS_PROXY"
+    )]
+    proxy: Option<String>,
+
+    #[arg(
+        long,
+        env = "NO_PROXY",
+        value_name = "HOSTS",
+        help = "Comma-separated hosts, .domains and CIDR ranges that bypass --proxy (falls back to no_proxy)"
+    )]
+    no_proxy: Option<String>,
+
+    #[arg(
+        long,
+        help = "Use HTTP/2 without negotiation; fails against HTTP/1-only servers"
+    )]
+    http2_prior_knowledge: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Reject TLS connections below this protocol version (default: the TLS backend's minimum)"
+    )]
+    min_tls_version: Option<MinTlsVersion>,
+
+    #[arg(
+        long,
+        help = "Fetch each project's quality gate and list the conditions that made it fail"
+    )]
+    gate_conditions: bool,
+
+    #[arg(
+        long,
+        help = "Fetch each project's quality gate and show whether it passed"
+    )]
+    quality_gate: bool,
+
+    #[arg(
+        long,
+        help = "Fetch each project's quality gate and exit with code 2, after writing the reports, if any failed"
+    )]
+    fail_on_gate: bool,
+
+    #[arg(
+        long,
+        help = "Show the quality profile used for each language of each project"
+    )]
+    show_profiles: bool,
+
+    #[arg(
+        long,
+        help = "Show the security, security review and reliability ratings (A-E) of each project"
+    )]
+    show_ratings: bool,
+
+    #[arg(
+        long,
+        help = "Show the line coverage and duplicated lines density (%) of each project"
+    )]
+    show_measures: bool,
+
+    #[arg(
+        long,
+        help = "Fail when the token lacks permission for an optional section (gate, profiles, ratings, trend...) instead of noting it in the report"
+    )]
+    strict_permissions: bool,
+
+    #[arg(
+        long,
+        value_name = "SPEC",
+        value_parser = Thresholds::parse,
+        help = "Fail if open issues across all projects exceed a per-severity maximum, e.g. blocker=0,critical=2"
+    )]
+    thresholds: Option<Thresholds>,
+
+    #[arg(
+        long,
+        value_name = "SPEC",
+        num_args = 0..=1,
+        default_missing_value = DEFAULT_SEVERITY_EXIT_CODES,
+        value_parser = SeverityExitCodes::parse,
+        help = "Exit with a code chosen by the worst severity with open issues across all projects, e.g. blocker=5,critical=4; alone it uses blocker=5,critical=4,major=3,minor=2,info=1"
+    )]
+    severity_exit_codes: Option<SeverityExitCodes>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Instead of a report, check which issue keys in PATH (one per line) are resolved or gone and can be removed"
+    )]
+    check_suppressions: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "CMD",
+        help = "Run CMD once per issue after the report is written; the issue is passed as SQ_PROJECT, SQ_SEVERITY, SQ_MESSAGE, SQ_COMPONENT and SQ_LINE environment variables"
+    )]
+    on_issue: Option<String>,
+
+    #[arg(
+        long,
+        default_value = "INFO",
+        value_parser = parse_severity,
+        help = "Only run --on-issue for issues at or above this severity (BLOCKER, CRITICAL, MAJOR, MINOR, INFO)"
+    )]
+    on_issue_severity: String,
+
+    #[arg(
+        long,
+        value_name = "TEXT",
+        help = "Comment TEXT on each listed issue in SonarQube (see --comment-severity, --comment-rules); only lists the issues unless --confirm-writes"
+    )]
+    comment: Option<String>,
+
+    #[arg(
+        long,
+        default_value = "INFO",
+        value_parser = parse_severity,
+        help = "Only --comment on issues at or above this severity (BLOCKER, CRITICAL, MAJOR, MINOR, INFO)"
+    )]
+    comment_severity: String,
+
+    #[arg(
+        long,
+        value_name = "RULES",
+        value_delimiter = ',',
+        help = "Only --comment on issues of these comma-separated rule keys"
+    )]
+    comment_rules: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Allow --comment to actually post comments"
+    )]
+    confirm_writes: bool,
+}
+
+fn parse_jitter(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(jitter) if (0.0..=1.0).contains(&jitter) => Ok(jitter),
+        _ => Err(format!("'{}' is not a fraction between 0 and 1", value)),
+    }
+}
+
+fn parse_batch_size(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(size) if (1..=500).contains(&size) => Ok(size),
+        _ => Err(format!("'{}' is not a batch size between 1 and 500 (the server's page size limit)", value)),
+    }
+}
+
+fn parse_severity(value: &str) -> Result<String, String> {
+    if is_known_severity(value) {
+        Ok(value.to_uppercase())
+    } else {
+        Err(format!("unknown severity '{}'", value))
+    }
 }
 
 fn main() -> Result<()> {
+    load_env_files(&env_file_args(std::env::args().skip(1)))?;
     dotenv().ok();
 
     let args = Args::parse();
 
-    let url = args.url
+    if let Some(path) = &args.translations_file {
+        match Translations::load(path) {
+            Ok(translations) => {
+                set_custom_translations(translations);
+            }
+            Err(error) => eprintln!("Warning: {:#}; using the built-in translations", error),
+        }
+    }
+    let language = Language::known(&args.language).with_context(|| format!(
+        "Error: no translations for language '{}' (available: {}); add it with --translations-file",
+        args.language,
+        Language::available().join(", ")
+    ))?;
+
+    let github_summary = if args.github_summary { Some(GithubSummarySink::from_env()?) } else { None };
+    let outputs = args.output.iter()
+        .map(|path| Ok((sink_for(path)?, OutputFormat::for_output(path, args.format)?)))
+        .collect::<Result<Vec<_>>>()?;
+    let theme = match &args.theme {
+        Some(path) => Theme::load(path)?,
+        None => Theme::default(),
+    };
+
+    let fetched = if args.offline {
+        // clap only accepts --offline together with --cache-file.
+        let path = args.cache_file.as_deref().context("Error: --offline needs --cache-file")?;
+        let projects_data = load_projects(path)?;
+        eprintln!("Loaded {} project(s) from: {}", projects_data.len(), path.display());
+        Fetched { projects_data, server_url: args.url.clone(), ..Default::default() }
+    } else {
+        match fetch_online(&args, language, &outputs)? {
+            Some(fetched) => fetched,
+            // --check-suppressions reported instead.
+            None => return Ok(()),
+        }
+    };
+    let Fetched { projects_data, failed_projects, run_state, server_version, server_url, filters, client } = fetched;
+    let console_format = args.format.unwrap_or(OutputFormat::Markdown);
+
+    let options = ReportOptions {
+        footer: args.footer && !args.no_footer,
+        server_url,
+        new_vs_overall: args.new_vs_overall,
+        legend: args.legend,
+        repo_root: args.repo_root.clone(),
+        show_author: args.show_author,
+        summary: args.summary,
+        author_summary: args.author_summary,
+        max_report_bytes: args.max_report_bytes,
+        server_version,
+        created_after: filters.created_after,
+        created_before: filters.created_before,
+        no_separators: args.no_separators,
+        no_timestamp: args.no_timestamp,
+        effort_summary: args.effort_summary,
+        by_extension: args.by_extension,
+        hide_na_columns: args.hide_na_columns,
+        severity_first: args.severity_first,
+        // Set per output by render_report for --format markdown-compact.
+        compact: false,
+        line_ranges: args.line_ranges,
+        shorten_components: args.shorten_components,
+        issue_links: args.issue_links,
+        by_author: args.new_code_by_author,
+        group_by: args.group_by,
+        theme: theme.clone(),
+        // Set below for a terminal; files and sinks never get escape codes.
+        color: false,
+    };
+
+    if outputs.is_empty() {
+        let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        let console_options = ReportOptions { color, ..options.clone() };
+        print!("{}", render_report(console_format, language, &console_options, &projects_data));
+    }
+
+    for (sink, format) in &outputs {
+        let report = render_report(*format, language, &options, &projects_data);
+        match args.output_encoding {
+            Some(encoding) => {
+                let (bytes, replaced) = encode_report(&report, encoding);
+                if replaced {
+                    eprintln!("Warning: {} can't represent some characters of {}; they were written as &#NNNN; references", encoding.name(), sink.location());
+                }
+                sink.write_bytes(&bytes)?;
+            }
+            None => sink.write(&report)?,
+        }
+        eprintln!("Report saved to: {}", sink.location());
+    }
+
+    if let Some(sink) = &github_summary {
+        sink.write(&render_report(OutputFormat::Markdown, language, &options, &projects_data))?;
+        eprintln!("Report saved to: {}", sink.location());
+    }
+
+    if let Some(dir) = &args.badges_dir {
+        let badges = write_badges(dir, &projects_data, &theme)?;
+        eprintln!("{} badge(s) saved to: {}", badges.len(), dir.display());
+    }
+
+    // Saved only once the reports are out, so a failed run reports the same delta again.
+    if let (Some(path), Some(state)) = (&args.state_file, &run_state) {
+        state.save(path)?;
+        eprintln!("State saved to: {}", path.display());
+    }
+
+    if let Some(command) = &args.on_issue {
+        let failures = run_issue_hook(command, &args.on_issue_severity, &projects_data);
+        if !failures.is_empty() {
+            eprintln!("Warning: --on-issue command failed for {} issue(s):", failures.len());
+            for failure in failures {
+                eprintln!("  {}", failure);
+            }
+        }
+    }
+
+    if let Some(text) = &args.comment {
+        let rules: Vec<String> = args.comment_rules.iter().map(|rule| rule.trim().to_string()).filter(|rule| !rule.is_empty()).collect();
+        let targets = comment_targets(&projects_data, &args.comment_severity, &rules);
+        let verb = if args.confirm_writes { "Commenting on" } else { "Dry run: would comment on" };
+        eprintln!("{} {} issue(s):", verb, targets.len());
+        for target in &targets {
+            eprintln!("  {} [{}]", target.description, target.issue_key);
+        }
+        let client = client.as_ref().context("Error: --comment can't be used with --offline")?;
+        match post_comments(client, &targets, text, args.confirm_writes) {
+            CommentOutcome::DryRun => eprintln!("Nothing was posted; pass --confirm-writes to post the comments"),
+            CommentOutcome::Posted { failures } if failures.is_empty() => eprintln!("Commented on {} issue(s)", targets.len()),
+            CommentOutcome::Posted { failures } => bail!(
+                "Error: failed to comment on {} of {} issue(s):\n  {}",
+                failures.len(), targets.len(), failures.join("\n  ")
+            ),
+        }
+    }
+
+    // Dropping the client writes the --har file, which process::exit below would skip.
+    drop(client);
+
+    // The others were still reported; fail only now so CI notices the missing projects.
+    if !failed_projects.is_empty() {
+        bail!("Error: {} project(s) could not be fetched: {}", failed_projects.len(), failed_projects.join(", "));
+    }
+
+    // Before --thresholds, so a failed gate exits 2 even when thresholds are exceeded too.
+    if args.fail_on_gate {
+        let failed = failed_gates(&projects_data);
+        let unchecked = unchecked_gates(&projects_data);
+        if !unchecked.is_empty() {
+            eprintln!("Error: quality gate could not be checked for {} project(s): {}", unchecked.len(), unchecked.join(", "));
+        }
+        if !failed.is_empty() {
+            eprintln!("Error: quality gate failed for {} project(s): {}", failed.len(), failed.join(", "));
+            std::io::stdout().flush()?;
+            std::process::exit(GATE_FAILED_EXIT_CODE.into());
+        }
+        if !unchecked.is_empty() {
+            bail!("Error: --fail-on-gate needs every project's quality gate");
+        }
+    }
+
+    if let Some(thresholds) = &args.thresholds {
+        let breaches = thresholds.breaches(&projects_data);
+        if !breaches.is_empty() {
+            bail!("Error: severity thresholds exceeded:\n  {}", breaches.join("\n  "));
+        }
+    }
+
+    if let Some(exit_codes) = &args.severity_exit_codes {
+        let code = exit_codes.exit_code(&projects_data);
+        if code != 0 {
+            // process::exit skips destructors, so flush the console report first.
+            std::io::stdout().flush()?;
+            std::process::exit(code.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// What the server returned for the report, or what `--offline` read back from `--cache-file`.
+#[derive(Default)]
+struct Fetched {
+    projects_data: Vec<ProjectData>,
+    /// Projects whose data couldn't be fetched; the others are still reported.
+    failed_projects: Vec<String>,
+    run_state: Option<RunState>,
+    server_version: Option<String>,
+    server_url: Option<String>,
+    /// Filters the issues were fetched with; the defaults offline.
+    filters: IssueFilters,
+    /// `None` offline, where nothing may be sent to the server.
+    client: Option<SonarQubeClient>,
+}
+
+/// Connects to the server and fetches every project's data; `None` when
+/// `--check-suppressions` wrote its own report instead.
+fn fetch_online(args: &Args, language: Language, outputs: &[(Box<dyn OutputSink>, OutputFormat)]) -> Result<Option<Fetched>> {
+    let url = args.url.clone()
         .context("Error: --url is required (or set SONARQUBE_URL environment variable)")?;
     
-    let token = args.token
+    let token = args.token.clone()
         .context("Error: --token is required (or set SONARQUBE_TOKEN environment variable)")?;
     
-    let projects = args.projects
-        .context("Error: --projects is required (or set SONARQUBE_PROJECTS environment variable)")?;
+    let mut filters = IssueFilters {
+        created_after: args.since.or_else(|| args.last_days.map(|days| last_days_start(Local::now().date_naive(), days))),
+        created_before: args.created_before,
+        on_component_only: args.component_keys_exact,
+        branch: args.branch.clone(),
+        directories: args.directories.clone(),
+        in_new_code_period: args.new_code_by_author,
+        assigned: match (args.assigned, args.unassigned) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        },
+        ..Default::default()
+    };
+    filters.validate(if args.last_days.is_some() { "--last-days" } else { "--since" })?;
 
-    let project_keys: Vec<&str> = projects.split(',').map(|s| s.trim()).collect();
+    let client_options = ClientOptions {
+        timeout: Duration::from_secs(args.timeout_secs),
+        pool_idle_timeout: args.pool_idle_timeout.map(Duration::from_secs),
+        http2_prior_knowledge: args.http2_prior_knowledge,
+        min_tls_version: args.min_tls_version,
+        analysis_retries: args.analysis_retries.unwrap_or(args.retries),
+        issues_retries: args.issues_retries.unwrap_or(args.retries),
+        retry_jitter: args.retry_jitter,
+        accept_language: args.accept_language.clone(),
+        dump_raw_dir: args.dump_raw.clone(),
+        har: args.har.clone(),
+        max_api_calls: args.max_api_calls,
+        retry_budget: args.retry_budget,
+        batch_size: args.batch_size,
+        proxy: args.proxy.clone(),
+        no_proxy: args.no_proxy.clone(),
+        auth_scheme: args.auth_mode,
+        ..Default::default()
+    };
+    if let Some(dir) = &args.dump_raw {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create --dump-raw directory {}", dir.display()))?;
+    }
+    let mut client = SonarQubeClient::with_options(url.clone(), token, client_options)?;
 
-    let client = SonarQubeClient::new(url, token)?;
+    if args.autodetect_path {
+        match client.detect_api_prefix()? {
+            Some(prefix) if args.verbose => eprintln!("Detected web API at: {}{}/api", redact_url(client.base_url()), prefix),
+            Some(_) => {}
+            None => eprintln!("Warning: /api/system/status not found at {} or below /sonar; keeping the URL as given", redact_url(client.base_url())),
+        }
+    }
 
-    let mut projects_data = Vec::new();
-    
-    for project_key in project_keys {
-        eprintln!("Fetching data for project: {}...", project_key);
+    // Fetched at most once: both the compat probe and --show-server-version need it.
+    let mut probed_version = None;
+    let mut probe_server_version = |client: &SonarQubeClient| -> Option<String> {
+        probed_version.get_or_insert_with(|| client.get_server_version().unwrap_or_else(|error| {
+            eprintln!("Error fetching server version: {:#}", error);
+            None
+        })).clone()
+    };
 
-        let last_analysis = client.get_last_analysis_date(project_key)?;
-        let issues = client.get_latest_issues(project_key, args.max_issues)?;
+    match args.api_compat {
+        Some(api_compat) => client.set_api_compat(api_compat),
+        None => match probe_server_version(&client).as_deref().and_then(ApiCompat::for_server_version) {
+            Some(api_compat) => client.set_api_compat(api_compat),
+            None => eprintln!("Warning: could not detect the server version; using the 9.x API parameters every version accepts (use --api-compat to override)"),
+        },
+    }
 
-        projects_data.push(ProjectData {
-            project_key: project_key.to_string(),
-            last_analysis,
-            issues,
-        });
+    if let Some(path) = &args.check_suppressions {
+        let keys = load_suppressions(path)?;
+        let check = SuppressionCheck::run(&client, &keys)?;
+        let report = check.render(language);
+        if outputs.is_empty() {
+            print!("{}", report);
+        }
+        for (sink, _) in outputs {
+            sink.write(&report)?;
+            eprintln!("Report saved to: {}", sink.location());
+        }
+        eprintln!("{} of {} suppressed issue(s) can be removed", check.resolved.len() + check.missing.len(), keys.len());
+        return Ok(None);
     }
 
-    let generator = MarkdownReportGenerator::new(args.language);
-    let report = generator.generate_report(&projects_data);
+    let project_keys: Vec<String> = match &args.portfolio {
+        Some(portfolio) => {
+            let keys = client.get_portfolio_projects(portfolio)?;
+            eprintln!("Portfolio {} contains {} project(s)", portfolio, keys.len());
+            keys
+        }
+        None => args.projects.as_deref()
+            .context("Error: --projects or --portfolio is required (or set SONARQUBE_PROJECTS environment variable)")?
+            .split(',')
+            .map(|s| decode_project_key(s.trim()))
+            .filter(|key| !key.is_empty())
+            .collect(),
+    };
+
+    if !args.component_keys_exact {
+        if let Some(key) = project_keys.iter().find(|key| is_path_key(key)) {
+            eprintln!(
+                "Note: '{}' looks like a directory or file key; issues below it are included too (use --component-keys-exact to restrict to it)",
+                key
+            );
+        }
+    }
 
-    if let Some(output_path) = args.output {
-        fs::write(&output_path, report)
-            .context(format!("Error writing to file: {}", output_path))?;
-        eprintln!("Report saved to: {}", output_path);
+    if project_keys.is_empty() {
+        if args.error_on_empty {
+            bail!("Error: no projects to report on (check --projects / --portfolio)");
+        }
+        eprintln!("Warning: no projects to report on");
+    }
+
+    if args.mine {
+        let login = client.get_current_user()?
+            .context("Error: --mine needs a token that belongs to a user, not a project or global analysis token")?;
+        eprintln!("Showing issues assigned to: {}", login);
+        filters.assignees = Some(login);
+    }
+
+    let console_format = args.format.unwrap_or(OutputFormat::Markdown);
+    // Formats that report every open issue per severity, not just the listed ones.
+    let counts_formats = [OutputFormat::NdjsonMetrics, OutputFormat::GithubComment];
+    let wants_counts = if outputs.is_empty() {
+        counts_formats.contains(&console_format)
     } else {
-        print!("{}", report);
+        outputs.iter().any(|(_, format)| counts_formats.contains(format))
+    };
+
+    let fetch_options = FetchOptions {
+        max_issues: args.max_issues,
+        issue_counts: wants_counts || args.summary || args.thresholds.is_some() || args.severity_exit_codes.is_some() || args.badges_dir.is_some() || args.state_file.is_some(),
+        new_vs_overall: args.new_vs_overall,
+        trend: args.trend,
+        hotspots_by_dir: args.hotspots_by_dir,
+        effort_summary: args.effort_summary,
+        quality_gate: args.quality_gate || args.gate_conditions || args.fail_on_gate,
+        quality_profiles: args.show_profiles,
+        ratings: args.show_ratings,
+        measures: args.show_measures,
+        strict_permissions: args.strict_permissions,
+        timings: args.timings,
+        compare_branch: args.compare_branch.clone(),
+        ignore_rules: args.ignore_rules.iter().map(|rule| rule.trim().to_string()).filter(|rule| !rule.is_empty()).collect(),
+        with_transitions: false,
+        filters,
+    };
+
+    let mut projects_data = Vec::new();
+    let mut failed_projects = Vec::new();
+    for (project_key, result) in project_keys.iter().zip(fetch_projects(&client, &project_keys, &fetch_options, args.concurrency.into())) {
+        match result {
+            Ok(project) => projects_data.push(project),
+            // A rejected token fails every project alike; stop rather than report nothing.
+            Err(error) if error.downcast_ref::<AuthenticationFailed>().is_some_and(AuthenticationFailed::is_invalid_token) => {
+                return Err(error);
+            }
+            Err(error) => {
+                eprintln!("Error fetching {}: {:#}", project_key, error);
+                failed_projects.push(project_key.clone());
+            }
+        }
     }
 
-    Ok(())
-}
\ No newline at end of file
+    if args.state_file.is_some() {
+        let truncated = truncated_projects(&projects_data);
+        if !truncated.is_empty() {
+            bail!(
+                "Error: --state-file needs every open issue listed, but --max-issues {} left some out for: {}; raise --max-issues",
+                args.max_issues,
+                truncated.join(", ")
+            );
+        }
+    }
+    let mut run_state = match &args.state_file {
+        Some(path) => Some(RunState::load(path)?),
+        None => None,
+    };
+    if let Some(state) = run_state.as_mut() {
+        for project in projects_data.iter_mut() {
+            if !state.has_project(&project.project_key) {
+                eprintln!("No previous run of {} in the state file; reporting all its issues", project.project_key);
+            }
+            let listed = std::mem::take(&mut project.issues);
+            project.issues = state.new_since(&project.project_key, &listed);
+            state.record(&project.project_key, &listed);
+        }
+    }
+
+    let server_version = if args.show_server_version {
+        Some(probe_server_version(&client).unwrap_or_else(|| "unknown".to_string()))
+    } else {
+        None
+    };
+
+    if let Some(path) = &args.manifest {
+        RunManifest::new(&url, &fetch_options, run_state.is_some(), &projects_data, Utc::now())
+            .write(path)?;
+        eprintln!("Manifest saved to: {}", path.display());
+    }
+
+    if let Some(path) = &args.cache_file {
+        save_projects(path, &projects_data)?;
+        eprintln!("Cache saved to: {}", path.display());
+    }
+
+    Ok(Some(Fetched {
+        projects_data,
+        failed_projects,
+        run_state,
+        server_version,
+        server_url: Some(url),
+        filters: fetch_options.filters,
+        client: Some(client),
+    }))
+}
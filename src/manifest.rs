@@ -0,0 +1,98 @@
+use crate::client::redact_url;
+use crate::fetch::FetchOptions;
+use crate::report::ProjectData;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// Provenance record of one run, written next to the report by `--manifest`.
+#[derive(Debug, Serialize)]
+pub struct RunManifest {
+    pub tool_version: String,
+    /// Server URL with any embedded credentials redacted.
+    pub server_url: String,
+    /// RFC 3339 time the manifest was built.
+    pub generated_at: String,
+    pub filters: ManifestFilters,
+    pub projects: Vec<ManifestProject>,
+}
+
+/// Everything that narrowed the listed issues of every project: the filters sent to the
+/// server, then those applied locally.
+#[derive(Debug, Serialize)]
+pub struct ManifestFilters {
+    pub max_issues: i32,
+    pub created_after: Option<String>,
+    pub created_before: Option<String>,
+    pub in_new_code_period: bool,
+    pub assignees: Option<String>,
+    pub assigned: Option<bool>,
+    pub on_component_only: bool,
+    pub branch: Option<String>,
+    pub directories: Option<String>,
+    pub rules: Option<String>,
+    /// Rules whose issues were dropped after fetching (`--ignore-rules`).
+    pub ignore_rules: Vec<String>,
+    /// Branch whose issues were left out (`--compare-branch`).
+    pub compare_branch: Option<String>,
+    /// Whether only issues new since the previous run were listed (`--state-file`).
+    pub new_since_last_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ManifestProject {
+    pub key: String,
+    pub last_analysis: Option<String>,
+    /// Issues listed in the report.
+    pub issues_listed: usize,
+    /// Total open issues from the counts facet, when it was fetched.
+    pub open_issues: Option<u64>,
+}
+
+impl RunManifest {
+    pub fn new(
+        server_url: &str,
+        options: &FetchOptions,
+        new_since_last_run: bool,
+        projects_data: &[ProjectData],
+        generated_at: DateTime<Utc>,
+    ) -> Self {
+        let filters = &options.filters;
+        Self {
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            server_url: redact_url(server_url),
+            generated_at: generated_at.to_rfc3339(),
+            filters: ManifestFilters {
+                max_issues: options.max_issues,
+                created_after: filters.created_after.map(|date| date.format("%Y-%m-%d").to_string()),
+                created_before: filters.created_before.map(|date| date.format("%Y-%m-%d").to_string()),
+                in_new_code_period: filters.in_new_code_period,
+                assignees: filters.assignees.clone(),
+                assigned: filters.assigned,
+                on_component_only: filters.on_component_only,
+                branch: filters.branch.clone(),
+                directories: filters.directories.clone(),
+                rules: filters.rules.clone(),
+                ignore_rules: options.ignore_rules.clone(),
+                compare_branch: options.compare_branch.clone(),
+                new_since_last_run,
+            },
+            projects: projects_data.iter()
+                .map(|project| ManifestProject {
+                    key: project.project_key.clone(),
+                    last_analysis: project.last_analysis.clone(),
+                    issues_listed: project.issues.len(),
+                    open_issues: project.issue_counts.as_ref().map(|counts| counts.total),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize manifest")?;
+        fs::write(path, json + "\n")
+            .with_context(|| format!("Error writing manifest: {}", path.display()))
+    }
+}
@@ -1,27 +1,637 @@
-use crate::client::IssueData;
+mod confluence;
+mod csv;
+mod github;
+mod github_comment;
+mod gitlab;
+mod html;
+mod json;
+mod metrics;
+mod plain;
+
+pub use confluence::ConfluenceReportGenerator;
+pub use csv::{CsvReportGenerator, CSV_HEADER};
+pub use github::GithubAnnotationGenerator;
+pub use github_comment::{GithubCommentGenerator, GITHUB_COMMENT_MARKER, GITHUB_COMMENT_MAX_CHARS};
+pub use gitlab::GitlabCodeQualityGenerator;
+pub use html::HtmlReportGenerator;
+pub use json::{JsonReport, JsonReportGenerator};
+pub use metrics::MetricsReportGenerator;
+pub use plain::PlainReportGenerator;
+
+use crate::client::{redact_url, CODE_METRICS, DirectoryCount, EffortSummary, IssueCounts, IssueData, QualityGateStatus, QualityProfile, Ratings, TrendPoint};
 use crate::i18n::{get_translation, Language};
-use chrono::{DateTime, Utc};
-use serde::Serialize;
+use crate::severity::{is_known_severity, severity_rank, SEVERITIES};
+use crate::theme::Theme;
+use anyhow::{bail, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq)]
+pub enum OutputFormat {
+    Markdown,
+    /// Markdown with each clean project reduced to a single line.
+    MarkdownCompact,
+    /// Plain text without Markdown syntax, for log sinks that mangle it.
+    Plain,
+    /// GitHub Actions workflow commands (`::error file=...,line=...::message`).
+    Github,
+    /// A single pull request comment body with a hidden marker, for bots that update it in place.
+    GithubComment,
+    /// GitLab CI Code Quality JSON artifact.
+    Gitlab,
+    /// The fetched project data as indented JSON.
+    Json,
+    /// The same JSON on a single line, for storage and transfer.
+    JsonCompact,
+    /// Confluence wiki markup.
+    Confluence,
+    /// A standalone HTML document with inline styles.
+    Html,
+    /// One CSV row per issue, for spreadsheets.
+    Csv,
+    /// One JSON line per issue-count metric per project, for time-series databases.
+    NdjsonMetrics,
+}
+
+impl OutputFormat {
+    /// Infers the format from a file extension, e.g. `report.md`.
+    pub fn from_extension(path: &str) -> Option<Self> {
+        let extension = Path::new(path).extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "md" | "markdown" => Some(OutputFormat::Markdown),
+            "txt" => Some(OutputFormat::Plain),
+            "json" => Some(OutputFormat::Json),
+            "html" | "htm" => Some(OutputFormat::Html),
+            "csv" => Some(OutputFormat::Csv),
+            _ => None,
+        }
+    }
+
+    /// Picks the format for an output path: an explicit `--format` wins, otherwise it is
+    /// inferred from the extension. Paths without an extension default to Markdown.
+    pub fn for_output(path: &str, explicit: Option<OutputFormat>) -> Result<Self> {
+        if let Some(format) = explicit.or_else(|| Self::from_extension(path)) {
+            return Ok(format);
+        }
+        match Path::new(path).extension() {
+            None => Ok(OutputFormat::Markdown),
+            Some(extension) => bail!(
+                "Error: cannot infer the report format from extension '.{}' of {}; pass --format explicitly",
+                extension.to_string_lossy(),
+                path
+            ),
+        }
+    }
+}
+
+/// Renders `projects_data` in the requested format.
+pub fn render_report(format: OutputFormat, language: Language, options: &ReportOptions, projects_data: &[ProjectData]) -> String {
+    match format {
+        OutputFormat::Markdown => MarkdownReportGenerator::with_options(language, options.clone())
+            .generate_report(projects_data),
+        OutputFormat::MarkdownCompact => {
+            let options = ReportOptions { compact: true, ..options.clone() };
+            MarkdownReportGenerator::with_options(language, options).generate_report(projects_data)
+        }
+        OutputFormat::Plain => PlainReportGenerator::new(language)
+            .with_timestamp(!options.no_timestamp)
+            .with_colors(options.color.then(|| options.theme.clone()))
+            .generate_report(projects_data),
+        OutputFormat::Github => GithubAnnotationGenerator::new(options.repo_root.clone())
+            .generate_report(projects_data),
+        OutputFormat::GithubComment => GithubCommentGenerator::new(language, options.clone())
+            .generate_report(projects_data),
+        OutputFormat::Gitlab => GitlabCodeQualityGenerator::new(options.repo_root.clone())
+            .generate_report(projects_data),
+        OutputFormat::Json | OutputFormat::JsonCompact => JsonReportGenerator::new()
+            .with_compact(format == OutputFormat::JsonCompact)
+            .with_timestamp(!options.no_timestamp)
+            .generate_report(projects_data),
+        OutputFormat::Confluence => ConfluenceReportGenerator::new(language)
+            .with_timestamp(!options.no_timestamp)
+            .with_new_vs_overall(options.new_vs_overall)
+            .with_footer(options.footer, options.server_url.clone())
+            .generate_report(projects_data),
+        OutputFormat::Html => HtmlReportGenerator::new(language)
+            .with_timestamp(!options.no_timestamp)
+            .with_theme(options.theme.clone())
+            .with_shortened_components(options.shorten_components)
+            .with_issue_links(options.server_url.clone().filter(|_| options.issue_links))
+            .with_footer(options.footer, options.server_url.clone())
+            .generate_report(projects_data),
+        OutputFormat::Csv => CsvReportGenerator::new()
+            .generate_report(projects_data),
+        OutputFormat::NdjsonMetrics => MetricsReportGenerator::new()
+            .generate_report(projects_data),
+    }
+}
+
+/// Renders `values` as Unicode block characters scaled between their minimum and maximum.
+pub fn sparkline(values: &[u64]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let (Some(&min), Some(&max)) = (values.iter().min(), values.iter().max()) else {
+        return String::new();
+    };
+    let range = (max - min).max(1) as f64;
+
+    values.iter()
+        .map(|value| BLOCKS[(((value - min) as f64 / range) * (BLOCKS.len() - 1) as f64).round() as usize])
+        .collect()
+}
+
+/// Makes a component key repository-relative by removing `repo_root` (e.g. `my-project:`)
+/// and any leading slashes.
+pub fn strip_repo_root<'a>(component: &'a str, repo_root: Option<&str>) -> &'a str {
+    let path = match repo_root {
+        Some(root) => component.strip_prefix(root).unwrap_or(component),
+        None => component,
+    };
+    path.trim_start_matches('/')
+}
+
+/// Page of issue `issue_key` among `project_key`'s issues on the server at `server_url`,
+/// without any credentials the URL embeds; `None` when `server_url` isn't a valid URL.
+pub fn issue_url(server_url: &str, project_key: &str, issue_key: &str) -> Option<String> {
+    let mut url = reqwest::Url::parse(server_url).ok()?;
+    let _ = url.set_username("");
+    let _ = url.set_password(None);
+    url.set_fragment(None);
+    url.path_segments_mut().ok()?.pop_if_empty().extend(["project", "issues"]);
+    url.query_pairs_mut().clear().append_pair("id", project_key).append_pair("open", issue_key);
+    Some(url.to_string())
+}
+
+/// Creation dates covered by `--since`/`--last-days` and `--created-before`, both ends
+/// inclusive, e.g. `2024-01-01 – 2024-01-14`; an open end shows as `…`. `None` without either.
+pub fn created_window(after: Option<NaiveDate>, before: Option<NaiveDate>) -> Option<String> {
+    if after.is_none() && before.is_none() {
+        return None;
+    }
+    let last_day = before.and_then(|before| before.pred_opt());
+    let format = |date: Option<NaiveDate>| date.map_or_else(|| "…".to_string(), |date| date.format("%Y-%m-%d").to_string());
+    Some(format!("{} – {}", format(after), format(last_day)))
+}
+
+/// Keeps only the last `segments` `/`-separated parts of a component key behind a leading
+/// `…`, e.g. `…/foo/Bar.java`; keys with no more parts than that are returned unchanged.
+pub fn shorten_component(component: &str, segments: usize) -> String {
+    match component.rmatch_indices('/').nth(segments.max(1) - 1) {
+        Some((index, _)) if index > 0 => format!("…{}", &component[index..]),
+        _ => component.to_string(),
+    }
+}
+
+/// Open issues per severity of one project, from the counts facet where it was fetched and
+/// from the listed issues otherwise. Severities are upper-cased.
+pub fn severity_counts(project: &ProjectData) -> BTreeMap<String, u64> {
+    let mut counts = BTreeMap::new();
+    match &project.issue_counts {
+        Some(issue_counts) => {
+            for (severity, count) in &issue_counts.by_severity {
+                *counts.entry(severity.to_uppercase()).or_default() += count;
+            }
+        }
+        None => {
+            for issue in &project.issues {
+                *counts.entry(issue.severity.to_uppercase()).or_default() += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// The `--footer` provenance line, e.g. `Generated by sonarqube_checker v0.1.0 against
+/// https://sonar.example.com`, with credentials in `server_url` redacted.
+pub fn footer_text(language: Language, server_url: Option<&str>) -> String {
+    let mut footer = format!("{} sonarqube_checker v{}", get_translation("generated_by", language.code()), env!("CARGO_PKG_VERSION"));
+    if let Some(server_url) = server_url {
+        footer.push_str(&format!(" {} {}", get_translation("against", language.code()), redact_url(server_url)));
+    }
+    footer
+}
+
+/// Translation key of a quality gate status, e.g. `gate_passed` for `OK`.
+pub fn gate_status_key(status: &str) -> &'static str {
+    match status {
+        "OK" => "gate_passed",
+        "ERROR" => "gate_failed",
+        "WARN" => "gate_warning",
+        _ => "gate_none",
+    }
+}
+
+/// Metric key and `actual (fails when > threshold)` text per failing condition of a failed
+/// gate; empty when the gate didn't fail.
+pub fn gate_failures(gate: &QualityGateStatus, language: Language) -> Vec<(&str, String)> {
+    if gate.status != "ERROR" {
+        return Vec::new();
+    }
+    gate.failed_conditions()
+        .map(|condition| {
+            let comparator = match condition.comparator.as_deref() {
+                Some("GT") => ">",
+                Some("LT") => "<",
+                Some(other) => other,
+                None => "?",
+            };
+            (condition.metric_key.as_str(), format!("{} ({} {} {})",
+                condition.actual_value.as_deref().unwrap_or("N/A"),
+                get_translation("fails_when", language.code()), comparator,
+                condition.error_threshold.as_deref().unwrap_or("N/A")))
+        })
+        .collect()
+}
+
+/// Label and grade (e.g. `🟢 A`) per rating; `None` for ratings the server had no value for.
+pub fn rating_grades(ratings: &Ratings, language: Language) -> Vec<(String, Option<String>)> {
+    [
+        ("security_rating", ratings.security),
+        ("security_review_rating", ratings.security_review),
+        ("reliability_rating", ratings.reliability),
+    ].into_iter()
+        .map(|(key, value)| (
+            get_translation(key, language.code()),
+            value.and_then(rating_letter).map(|letter| format!("{} {}", rating_marker(letter), letter)),
+        ))
+        .collect()
+}
+
+/// `Coverage: 81.3%` per [`CODE_METRICS`] entry; metrics without a value (e.g. no coverage
+/// report imported) show as N/A.
+pub fn measure_values(measures: &BTreeMap<String, String>, language: Language) -> Vec<String> {
+    CODE_METRICS.iter()
+        .map(|metric| {
+            let value = measures.get(*metric).map_or_else(|| "N/A".to_string(), |value| format!("{}%", value));
+            format!("{}: {}", get_translation(metric, language.code()), value)
+        })
+        .collect()
+}
+
+/// Rows in the "Worst directories" table.
+const WORST_DIRECTORIES_SHOWN: usize = 10;
+
+/// Makes `value` safe for a Markdown table cell: whitespace runs (including newlines and
+/// tabs, which would split the row) collapse to single spaces, and pipes are escaped.
+fn table_cell(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ").replace('|', "\\|")
+}
+
+/// Non-zero per-severity values, known severities worst first, then any others by name.
+fn nonzero_by_severity(by_severity: &BTreeMap<String, u64>) -> Vec<(&str, u64)> {
+    SEVERITIES.iter()
+        .filter_map(|severity| by_severity.get(*severity).map(|value| (*severity, *value)))
+        .chain(by_severity.iter()
+            .filter(|(severity, _)| !SEVERITIES.contains(&severity.as_str()))
+            .map(|(severity, value)| (severity.as_str(), *value)))
+        .filter(|(_, value)| *value > 0)
+        .collect()
+}
+
+/// `42-47` for an issue whose text range spans several lines, otherwise its single line.
+pub fn line_range(issue: &IssueData) -> String {
+    match &issue.text_range {
+        Some(range) if range.end_line > range.start_line => format!("{}-{}", range.start_line, range.end_line),
+        _ => issue.line.clone(),
+    }
+}
+
+/// Whether a project has nothing to show: no listed issues (or, for counts-only data, no
+/// counted ones) and no failed gate conditions.
+pub fn is_clean(project: &ProjectData) -> bool {
+    project.issues.is_empty()
+        && project.counts_instead_of_issues().is_none()
+        && project.quality_gate.as_ref().is_none_or(|gate| gate.failed_conditions().next().is_none())
+}
+
+/// Splits issues by severity, worst first (unknown severities last, by name), keeping each
+/// group's order.
+pub fn group_by_severity(issues: &[IssueData]) -> Vec<(String, Vec<IssueData>)> {
+    let mut groups: Vec<(String, Vec<IssueData>)> = Vec::new();
+    for issue in issues {
+        match groups.iter_mut().find(|(severity, _)| *severity == issue.severity) {
+            Some((_, group)) => group.push(issue.clone()),
+            None => groups.push((issue.severity.clone(), vec![issue.clone()])),
+        }
+    }
+
+    groups.sort_by(|a, b| worst_severity_first(&a.0, &b.0));
+    groups
+}
+
+/// Orders severities worst first, unknown severities last by name.
+fn worst_severity_first(a: &str, b: &str) -> Ordering {
+    let rank = |severity: &str| if is_known_severity(severity) { i16::from(severity_rank(severity)) } else { -1 };
+    rank(b).cmp(&rank(a)).then_with(|| a.cmp(b))
+}
+
+/// Splits the listed issues of all projects by severity like [`group_by_severity`], as
+/// `(project key, issue)` pairs in report order.
+pub fn group_projects_by_severity(projects_data: &[ProjectData]) -> Vec<(String, Vec<(String, IssueData)>)> {
+    let mut groups: Vec<(String, Vec<(String, IssueData)>)> = Vec::new();
+    for project in projects_data {
+        for issue in &project.issues {
+            let occurrence = (project.project_key.clone(), issue.clone());
+            match groups.iter_mut().find(|(severity, _)| *severity == issue.severity) {
+                Some((_, group)) => group.push(occurrence),
+                None => groups.push((issue.severity.clone(), vec![occurrence])),
+            }
+        }
+    }
+
+    groups.sort_by(|a, b| worst_severity_first(&a.0, &b.0));
+    groups
+}
+
+/// Splits issues by SCM author, most issues first (ties by name), with issues of unknown
+/// authors (`None`) last. Keeps each group's order.
+pub fn group_by_author(issues: &[IssueData]) -> Vec<(Option<String>, Vec<IssueData>)> {
+    let mut groups: Vec<(Option<String>, Vec<IssueData>)> = Vec::new();
+    for issue in issues {
+        match groups.iter_mut().find(|(author, _)| *author == issue.author) {
+            Some((_, group)) => group.push(issue.clone()),
+            None => groups.push((issue.author.clone(), vec![issue.clone()])),
+        }
+    }
+
+    groups.sort_by(|a, b| a.0.is_none().cmp(&b.0.is_none())
+        .then_with(|| b.1.len().cmp(&a.1.len()))
+        .then_with(|| a.0.cmp(&b.0)));
+    groups
+}
+
+/// How the Markdown report is sectioned.
+#[derive(Debug, Clone, Copy, Default, ValueEnum, PartialEq)]
+pub enum GroupBy {
+    /// One section per project (the default).
+    #[default]
+    Project,
+    /// One section per severity across all projects, worst first.
+    Severity,
+    /// One section per rule across all projects, most occurrences first.
+    Rule,
+}
+
+/// All listed occurrences of one rule across projects.
+#[derive(Debug, Clone)]
+pub struct RuleGroup {
+    /// Rule key; `None` for issues the server returned without one.
+    pub rule: Option<String>,
+    /// Worst severity among the occurrences.
+    pub severity: String,
+    /// `(project key, issue)` pairs in report order.
+    pub occurrences: Vec<(String, IssueData)>,
+}
+
+/// Groups the listed issues of all projects by rule, most occurrences first (ties by rule
+/// key), with rule-less issues last.
+pub fn group_by_rule(projects_data: &[ProjectData]) -> Vec<RuleGroup> {
+    let mut groups: Vec<RuleGroup> = Vec::new();
+    for project in projects_data {
+        for issue in &project.issues {
+            let occurrence = (project.project_key.clone(), issue.clone());
+            match groups.iter_mut().find(|group| group.rule == issue.rule) {
+                Some(group) => {
+                    if is_known_severity(&issue.severity)
+                        && (!is_known_severity(&group.severity) || severity_rank(&issue.severity) > severity_rank(&group.severity))
+                    {
+                        group.severity = issue.severity.clone();
+                    }
+                    group.occurrences.push(occurrence);
+                }
+                None => groups.push(RuleGroup { rule: issue.rule.clone(), severity: issue.severity.clone(), occurrences: vec![occurrence] }),
+            }
+        }
+    }
+
+    groups.sort_by(|a, b| a.rule.is_none().cmp(&b.rule.is_none())
+        .then_with(|| b.occurrences.len().cmp(&a.occurrences.len()))
+        .then_with(|| a.rule.cmp(&b.rule)));
+    groups
+}
+
+/// Keeps the entries of a table row whose column is flagged in `keep`.
+fn retain_columns<T>(values: &mut Vec<T>, keep: &[bool]) {
+    let mut column = keep.iter();
+    values.retain(|_| *column.next().unwrap_or(&true));
+}
+
+/// Lowercased file extension of a component key such as `project:src/Main.java`, or `None`
+/// for components without one (`Makefile`, `.gitignore`, directories).
+pub fn component_extension(component: &str) -> Option<String> {
+    let file_name = component.rsplit(['/', ':']).next().unwrap_or(component);
+    Path::new(file_name).extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+}
+
+/// Counts issues per component extension, most issues first, ties by extension (`None` last).
+pub fn count_by_extension(issues: &[IssueData]) -> Vec<(Option<String>, usize)> {
+    let mut counts: HashMap<Option<String>, usize> = HashMap::new();
+    for issue in issues {
+        *counts.entry(component_extension(&issue.component)).or_default() += 1;
+    }
+
+    let mut counts: Vec<(Option<String>, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1)
+        .then_with(|| a.0.is_none().cmp(&b.0.is_none()))
+        .then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// Formats minutes of effort the way SonarQube does, with 8-hour days: `1d 2h 30min`.
+pub fn format_effort(minutes: u64) -> String {
+    let (days, hours, mins) = (minutes / 480, minutes % 480 / 60, minutes % 60);
+
+    let parts: Vec<String> = [(days, "d"), (hours, "h"), (mins, "min")].iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect();
+
+    if parts.is_empty() { "0min".to_string() } else { parts.join(" ") }
+}
+
+/// Issues in `target` with no counterpart in `base`, matched on rule, component, message and
+/// line (issue keys differ between branches). Keeps the order of `target`.
+pub fn new_issues(target: &[IssueData], base: &[IssueData]) -> Vec<IssueData> {
+    let identity = |issue: &IssueData| (issue.rule.clone(), issue.component.clone(), issue.message.clone(), issue.line.clone());
+    let base: HashSet<_> = base.iter().map(identity).collect();
+
+    target.iter()
+        .filter(|issue| !base.contains(&identity(issue)))
+        .cloned()
+        .collect()
+}
+
+/// Issues not raised by any of `rules` (rule keys such as `java:S1481`). Keeps the order.
+pub fn without_rules(issues: &[IssueData], rules: &[String]) -> Vec<IssueData> {
+    issues.iter()
+        .filter(|issue| !issue.rule.as_ref().is_some_and(|rule| rules.contains(rule)))
+        .cloned()
+        .collect()
+}
+
+/// Letter grade of a rating measure: `1.0` is A through `5.0` is E; `None` outside that range.
+pub fn rating_letter(value: f64) -> Option<char> {
+    match value.round() as i64 {
+        grade @ 1..=5 => Some((b'A' + (grade - 1) as u8) as char),
+        _ => None,
+    }
+}
+
+/// Colored marker shown before a rating letter, green (A) to red (E).
+fn rating_marker(letter: char) -> &'static str {
+    match letter {
+        'A' => "🟢",
+        'B' => "🟡",
+        'C' => "🟠",
+        'D' => "🔴",
+        _ => "⛔",
+    }
+}
+
+/// Human-readable duration: `850 ms` below a second, `12.3 s` from there on.
+pub fn format_duration(duration: Duration) -> String {
+    if duration < Duration::from_secs(1) {
+        format!("{} ms", duration.as_millis())
+    } else {
+        format!("{:.1} s", duration.as_secs_f64())
+    }
+}
+
+/// Orders issues newest first, breaking creation-date ties by issue key, so repeated runs
+/// against unchanged data render byte-identical reports.
+pub fn sort_issues(issues: &mut [IssueData]) {
+    let parse = |date: &Option<String>| date.as_deref()
+        .and_then(|d| DateTime::parse_from_str(d, "%Y-%m-%dT%H:%M:%S%z").ok());
 
-#[derive(Debug, Serialize)]
+    issues.sort_by(|a, b| {
+        parse(&b.creation_date).cmp(&parse(&a.creation_date))
+            .then_with(|| b.creation_date.cmp(&a.creation_date))
+            .then_with(|| a.key.cmp(&b.key))
+    });
+}
+
+/// Fetched data of one project; also read back from `--cache-file`.
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct ProjectData {
     pub project_key: String,
     pub last_analysis: Option<String>,
     pub issues: Vec<IssueData>,
+    pub issue_counts: Option<IssueCounts>,
+    /// Only the counts were fetched (`--max-issues 0`), so `issues` is empty by request
+    /// rather than because nothing matched.
+    #[serde(default)]
+    pub counts_only: bool,
+    pub new_code_counts: Option<IssueCounts>,
+    /// Issue count per analysis day, oldest first; `None` unless `--trend` was requested.
+    pub trend: Option<Vec<TrendPoint>>,
+    /// Directories with open issues, worst first; `None` unless `--hotspots-by-dir` was requested.
+    pub worst_directories: Option<Vec<DirectoryCount>>,
+    /// Remediation effort; `None` when not requested or not supported by the server.
+    pub effort: Option<EffortSummary>,
+    /// Base branch whose issues were subtracted from `issues` (`--compare-branch`).
+    pub compared_to: Option<String>,
+    /// Quality gate status and conditions, when fetched.
+    pub quality_gate: Option<QualityGateStatus>,
+    /// Quality profile per language; `None` unless `--show-profiles` was requested and allowed.
+    pub quality_profiles: Option<Vec<QualityProfile>>,
+    /// Security and reliability ratings; `None` unless `--show-ratings` was requested and allowed.
+    pub ratings: Option<Ratings>,
+    /// Values of the [`CODE_METRICS`] the project has, by metric key; `None` unless
+    /// `--show-measures` was requested and allowed.
+    pub measures: Option<BTreeMap<String, String>>,
+    /// Translation keys of the sections the token lacked permission for, e.g. `quality_gate`.
+    #[serde(default)]
+    pub denied_sections: Vec<String>,
+    /// Time spent fetching this project; `None` unless `--timings` was requested.
+    #[serde(skip)]
+    pub fetch_time: Option<Duration>,
+}
+
+impl ProjectData {
+    /// Counts to render in place of the issue list: set for counts-only data with open issues.
+    pub fn counts_instead_of_issues(&self) -> Option<&IssueCounts> {
+        self.issue_counts.as_ref().filter(|counts| self.counts_only && counts.total > 0)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReportOptions {
+    /// Append a "Generated by" provenance line at the end of the report.
+    pub footer: bool,
+    /// Server URL mentioned in the footer; credentials are redacted before rendering.
+    pub server_url: Option<String>,
+    /// Render a "New code / Overall" issue count line per project.
+    pub new_vs_overall: bool,
+    /// Append a legend explaining the severity levels.
+    pub legend: bool,
+    /// Prefix stripped from component keys to get repository-relative paths.
+    pub repo_root: Option<String>,
+    /// Add a "Found by" column with the issue's SCM author.
+    pub show_author: bool,
+    /// Add a table of issue counts per severity and project before the project sections.
+    pub summary: bool,
+    /// Add a report-wide issue count per SCM author.
+    pub author_summary: bool,
+    /// Drop trailing project sections so the Markdown report stays within this many bytes.
+    pub max_report_bytes: Option<usize>,
+    /// SonarQube server version shown in the report header.
+    pub server_version: Option<String>,
+    /// Issue creation date filters, noted in the report header.
+    pub created_after: Option<NaiveDate>,
+    pub created_before: Option<NaiveDate>,
+    /// Omit the `---` rules between report sections.
+    pub no_separators: bool,
+    /// Omit the "Generated" time so unchanged data renders identical reports.
+    pub no_timestamp: bool,
+    /// Render a remediation effort table per project.
+    pub effort_summary: bool,
+    /// Add a report-wide issue count per file extension.
+    pub by_extension: bool,
+    /// Drop issue table columns that are "N/A" in every row of a project.
+    pub hide_na_columns: bool,
+    /// Split each project's issues into one table per severity, worst first.
+    pub severity_first: bool,
+    /// Render projects with no issues and no failed gate conditions as a single line.
+    pub compact: bool,
+    /// Show `start-end` in the line column for issues spanning several lines.
+    pub line_ranges: bool,
+    /// Show only this many trailing component path segments in issue tables.
+    pub shorten_components: Option<usize>,
+    /// Link components in issue tables to the issue on `server_url`.
+    pub issue_links: bool,
+    /// List each project's (new-code) issues under one subheading per SCM author.
+    pub by_author: bool,
+    /// Section the report per project, per severity or per rule.
+    pub group_by: GroupBy,
+    /// Severity colors of the HTML report and of colored plain text.
+    pub theme: Theme,
+    /// Color the severities of plain text with the theme's ANSI codes, for a terminal.
+    pub color: bool,
 }
 
 pub struct MarkdownReportGenerator {
     language: Language,
+    options: ReportOptions,
 }
 
 impl MarkdownReportGenerator {
     pub fn new(language: Language) -> Self {
-        Self { language }
+        Self::with_options(language, ReportOptions::default())
+    }
+
+    pub fn with_options(language: Language, options: ReportOptions) -> Self {
+        Self { language, options }
     }
 
     pub fn format_analysis_date(&self, date_str: Option<&str>) -> String {
         match date_str {
-            None => get_translation("no_analysis_available", &self.language),
+            None => get_translation("no_analysis_available", self.language.code()),
             Some(date) => {
                 match DateTime::parse_from_rfc3339(date) {
                     Ok(dt) => dt.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
@@ -32,51 +642,609 @@ impl MarkdownReportGenerator {
     }
 
     pub fn generate_issues_table(&self, issues: &[IssueData]) -> String {
+        self.issues_table(issues, true, None)
+    }
+
+    /// Issues table of `project_key`, whose components link to the issues when
+    /// `issue_links` is on.
+    pub fn generate_project_issues_table(&self, project_key: &str, issues: &[IssueData]) -> String {
+        self.issues_table(issues, true, Some(project_key))
+    }
+
+    fn issues_table(&self, issues: &[IssueData], severity_column: bool, project_key: Option<&str>) -> String {
         if issues.is_empty() {
-            return get_translation("no_open_issues", &self.language);
+            return get_translation("no_open_issues", self.language.code());
         }
 
-        let severity_col = get_translation("severity", &self.language);
-        let message_col = get_translation("message", &self.language);
-        let component_col = get_translation("component", &self.language);
-        let line_col = get_translation("line", &self.language);
+        let mut headers = vec![
+            get_translation("severity", self.language.code()),
+            get_translation("issue_type", self.language.code()),
+            get_translation("message", self.language.code()),
+            get_translation("component", self.language.code()),
+            get_translation("line", self.language.code()),
+        ];
+        let mut separators = vec!["----------", "------", "---------", "-----------", "------"];
+        if self.options.show_author {
+            headers.push(get_translation("found_by", self.language.code()));
+            separators.push("----------");
+        }
 
-        let mut table = format!("| {} | {} | {} | {} |\n", severity_col, message_col, component_col, line_col);
-        table.push_str("|----------|---------|-----------|------|\n");
+        let mut rows: Vec<Vec<String>> = issues.iter().map(|issue| {
+            let mut cells = vec![
+                issue.severity.clone(),
+                issue.issue_type.as_deref().map_or_else(|| "N/A".to_string(), table_cell),
+                table_cell(&issue.message),
+                self.component_cell(&issue.component, self.issue_link(project_key, issue)),
+                if self.options.line_ranges { line_range(issue) } else { issue.line.clone() },
+            ];
+            if self.options.show_author {
+                cells.push(table_cell(&self.author_name(issue)));
+            }
+            cells
+        }).collect();
 
-        for issue in issues {
-            let message = issue.message.replace('|', "\\|");
-            let component = issue.component.replace('|', "\\|");
-            table.push_str(&format!("| {} | {} | {} | {} |\n", 
-                issue.severity, message, component, issue.line));
+        if !severity_column {
+            let keep: Vec<bool> = (0..headers.len()).map(|column| column != 0).collect();
+            retain_columns(&mut headers, &keep);
+            retain_columns(&mut separators, &keep);
+            for row in &mut rows {
+                retain_columns(row, &keep);
+            }
+        }
+
+        if self.options.hide_na_columns {
+            let keep: Vec<bool> = (0..headers.len())
+                .map(|column| rows.iter().any(|row| row[column] != "N/A"))
+                .collect();
+            if keep.contains(&true) {
+                retain_columns(&mut headers, &keep);
+                retain_columns(&mut separators, &keep);
+                for row in &mut rows {
+                    retain_columns(row, &keep);
+                }
+            }
+        }
+
+        let mut table = format!("| {} |\n", headers.join(" | "));
+        table.push_str(&format!("|{}|\n", separators.join("|")));
+        for cells in rows {
+            table.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+        // Per-severity tables are already counted in their subheading.
+        if severity_column {
+            table.push_str(&self.totals_row(issues, headers.len()));
         }
 
         table
     }
 
-    pub fn generate_report(&self, projects_data: &[ProjectData]) -> String {
-        let report_title = get_translation("report_title", &self.language);
-        let generated_label = get_translation("generated", &self.language);
-        let project_label = get_translation("project", &self.language);
-        let last_analysis_label = get_translation("last_analysis", &self.language);
-        let latest_issues_label = get_translation("latest_issues", &self.language);
+    /// Table cell for a component key, shortened after escaping; pipes are escaped as `\|`,
+    /// which has no `/` to cut at. With a `link` the cell is the text of a Markdown link.
+    fn component_cell(&self, component: &str, link: Option<String>) -> String {
+        let cell = table_cell(component);
+        let cell = match self.options.shorten_components {
+            Some(segments) => shorten_component(&cell, segments),
+            None => cell,
+        };
+        match link {
+            Some(url) => format!("[{}]({})", cell.replace('[', "\\[").replace(']', "\\]"), url),
+            None => cell,
+        }
+    }
 
-        let mut report = format!("# {}\n\n", report_title);
-        let now: DateTime<Utc> = Utc::now();
-        report.push_str(&format!("{}: {}\n\n", generated_label, now.format("%Y-%m-%d %H:%M:%S")));
-        report.push_str("---\n\n");
+    /// URL of `issue` when `issue_links` is on and its project, key and the server are known.
+    fn issue_link(&self, project_key: Option<&str>, issue: &IssueData) -> Option<String> {
+        if !self.options.issue_links {
+            return None;
+        }
+        issue_url(self.options.server_url.as_deref()?, project_key?, issue.key.as_deref()?)
+    }
+
+    /// Closing row like `| **Total** | 3 issues (1 blocker, 2 minor) | | |`. Markdown has no
+    /// colspan, so the label and the summary take the first two cells and the rest stay empty.
+    fn totals_row(&self, issues: &[IssueData], columns: usize) -> String {
+        let by_severity: Vec<String> = group_by_severity(issues).iter()
+            .map(|(severity, group)| format!("{} {}", group.len(), severity.to_lowercase()))
+            .collect();
+        let summary = format!("{} ({})",
+            get_translation(if issues.len() == 1 { "total_issue" } else { "total_issues" }, self.language.code())
+                .replace("{count}", &issues.len().to_string()),
+            by_severity.join(", "));
+        let label = format!("**{}**", get_translation("total", self.language.code()));
+
+        let mut cells = vec![String::new(); columns];
+        if columns > 1 {
+            cells[0] = label;
+            cells[1] = summary;
+        } else {
+            cells[0] = format!("{} {}", label, summary);
+        }
+        format!("| {} |\n", cells.join(" | "))
+    }
 
+    /// One subheading and severity-less table per severity present, worst first.
+    pub fn generate_severity_groups(&self, issues: &[IssueData]) -> String {
+        self.severity_groups(issues, None)
+    }
+
+    fn severity_groups(&self, issues: &[IssueData], project_key: Option<&str>) -> String {
+        if issues.is_empty() {
+            return get_translation("no_open_issues", self.language.code());
+        }
+
+        group_by_severity(issues).into_iter()
+            .map(|(severity, group)| format!("### {} ({})\n\n{}", severity, group.len(), self.issues_table(&group, false, project_key)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// One subheading and table per SCM author, most issues first, unknown authors last.
+    pub fn generate_author_groups(&self, issues: &[IssueData]) -> String {
+        self.author_groups(issues, None)
+    }
+
+    fn author_groups(&self, issues: &[IssueData], project_key: Option<&str>) -> String {
+        if issues.is_empty() {
+            return get_translation("no_open_issues", self.language.code());
+        }
+
+        group_by_author(issues).into_iter()
+            .map(|(author, group)| {
+                let author = author.unwrap_or_else(|| get_translation("unknown_author", self.language.code()));
+                format!("### 👤 {} ({})\n\n{}", author, group.len(), self.issues_table(&group, true, project_key))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// `## rule (count, severity)` followed by a table of the rule's occurrences.
+    pub fn generate_rule_section(&self, group: &RuleGroup) -> String {
+        let rule = group.rule.clone().unwrap_or_else(|| get_translation("unknown_rule", self.language.code()));
+        let mut section = format!("## 📏 {} ({}, {})\n\n", rule, group.occurrences.len(), group.severity);
+
+        section.push_str(&format!("| {} | {} | {} |\n", get_translation("project", self.language.code()),
+            get_translation("component", self.language.code()), get_translation("line", self.language.code())));
+        section.push_str("|-----------|-----------|------|\n");
+        for (project_key, issue) in &group.occurrences {
+            let line = if self.options.line_ranges { line_range(issue) } else { issue.line.clone() };
+            section.push_str(&format!("| {} | {} | {} |\n", table_cell(project_key),
+                self.component_cell(&issue.component, self.issue_link(Some(project_key), issue)), line));
+        }
+        section.push('\n');
+        section.push_str(self.separator());
+        section
+    }
+
+    /// `## severity (count)` followed by a table of the severity's issues across projects.
+    pub fn generate_severity_section(&self, severity: &str, occurrences: &[(String, IssueData)]) -> String {
+        let mut section = format!("## {} ({})\n\n", severity, occurrences.len());
+
+        section.push_str(&format!("| {} | {} | {} | {} |\n", get_translation("project", self.language.code()),
+            get_translation("component", self.language.code()), get_translation("line", self.language.code()),
+            get_translation("message", self.language.code())));
+        section.push_str("|-----------|-----------|------|---------|\n");
+        for (project_key, issue) in occurrences {
+            let line = if self.options.line_ranges { line_range(issue) } else { issue.line.clone() };
+            section.push_str(&format!("| {} | {} | {} | {} |\n", table_cell(project_key),
+                self.component_cell(&issue.component, self.issue_link(Some(project_key), issue)), line,
+                table_cell(&issue.message)));
+        }
+        section.push('\n');
+        section.push_str(self.separator());
+        section
+    }
+
+    pub fn generate_issue_counts(&self, counts: &IssueCounts) -> String {
+        let open_issues_label = get_translation("open_issues", self.language.code());
+
+        let breakdown: Vec<String> = nonzero_by_severity(&counts.by_severity).into_iter()
+            .map(|(severity, count)| format!("{}: {}", severity, count))
+            .collect();
+
+        if breakdown.is_empty() {
+            format!("**{}:** {}", open_issues_label, counts.total)
+        } else {
+            format!("**{}:** {} ({})", open_issues_label, counts.total, breakdown.join(", "))
+        }
+    }
+
+    pub fn generate_new_vs_overall(&self, project: &ProjectData) -> String {
+        let new_code_label = get_translation("new_code", self.language.code());
+        let overall_label = get_translation("overall", self.language.code());
+        let overall = project.issue_counts.as_ref().map(|c| c.total.to_string())
+            .unwrap_or_else(|| "N/A".to_string());
+
+        match &project.new_code_counts {
+            Some(new_code) => format!("**{}:** {} / **{}:** {}\n\n",
+                new_code_label, new_code.total, overall_label, overall),
+            None => format!("**{}:** {} _({})_\n\n",
+                overall_label, overall, get_translation("new_code_unsupported", self.language.code())),
+        }
+    }
+
+    pub fn generate_trend(&self, trend: &[TrendPoint]) -> String {
+        let trend_label = get_translation("issue_trend", self.language.code());
+
+        if trend.len() < 2 {
+            return format!("**{}:** _{}_\n\n", trend_label, get_translation("trend_too_short", self.language.code()));
+        }
+
+        let counts: Vec<u64> = trend.iter().map(|point| point.count).collect();
+        let mut table = format!("**{}:** {}\n\n", trend_label, sparkline(&counts));
+        table.push_str(&format!("| {} | {} |\n",
+            get_translation("date", self.language.code()), get_translation("issues", self.language.code())));
+        table.push_str("|--------|--------|\n");
+        for point in trend {
+            table.push_str(&format!("| {} | {} |\n", point.date, point.count));
+        }
+        table.push('\n');
+        table
+    }
+
+    pub fn generate_worst_directories(&self, directories: &[DirectoryCount]) -> String {
+        let mut table = format!("**{}:**\n\n", get_translation("worst_directories", self.language.code()));
+
+        if directories.is_empty() {
+            table.push_str(&get_translation("no_open_issues", self.language.code()));
+            table.push_str("\n\n");
+            return table;
+        }
+
+        table.push_str(&format!("| {} | {} |\n",
+            get_translation("directory", self.language.code()), get_translation("issues", self.language.code())));
+        table.push_str("|--------|--------|\n");
+        for directory in directories.iter().take(WORST_DIRECTORIES_SHOWN) {
+            table.push_str(&format!("| {} | {} |\n", table_cell(&directory.path), directory.count));
+        }
+        table.push('\n');
+        table
+    }
+
+    pub fn generate_effort_summary(&self, effort: Option<&EffortSummary>) -> String {
+        let effort_label = get_translation("remediation_effort", self.language.code());
+
+        let Some(effort) = effort else {
+            return format!("**{}:** _{}_\n\n", effort_label, get_translation("effort_unsupported", self.language.code()));
+        };
+
+        let mut table = format!("**{}:** {}\n\n", effort_label, format_effort(effort.total_minutes));
+        let rows = nonzero_by_severity(&effort.by_severity);
+        if rows.is_empty() {
+            return table;
+        }
+
+        table.push_str(&format!("| {} | {} |\n",
+            get_translation("severity", self.language.code()), get_translation("effort", self.language.code())));
+        table.push_str("|--------|--------|\n");
+        for (severity, minutes) in rows {
+            table.push_str(&format!("| {} | {} |\n", severity, format_effort(minutes)));
+        }
+        table.push('\n');
+        table
+    }
+
+    /// `**Quality profiles:** Java: Sonar way, Python: Strict` on one line.
+    pub fn generate_quality_profiles(&self, profiles: &[QualityProfile]) -> String {
+        let label = get_translation("quality_profiles", self.language.code());
+        if profiles.is_empty() {
+            return format!("**{}:** _{}_\n\n", label, get_translation("no_quality_profiles", self.language.code()));
+        }
+
+        let profiles: Vec<String> = profiles.iter()
+            .map(|profile| format!("{}: {}", profile.language_name.as_deref().unwrap_or(&profile.language), profile.name))
+            .collect();
+        format!("**{}:** {}\n\n", label, profiles.join(", "))
+    }
+
+    /// `**Ratings:** Security: 🟢 A, Security review: 🟠 C, Reliability: _n/a_` on one line.
+    pub fn generate_ratings(&self, ratings: &Ratings) -> String {
+        let grades: Vec<String> = rating_grades(ratings, self.language).into_iter()
+            .map(|(label, grade)| format!("{}: {}", label,
+                grade.unwrap_or_else(|| format!("_{}_", get_translation("no_rating", self.language.code())))))
+            .collect();
+        format!("**{}:** {}\n\n", get_translation("ratings", self.language.code()), grades.join(", "))
+    }
+
+    /// `**Measures:** Coverage: 81.3%, Duplication: N/A` on one line.
+    pub fn generate_measures(&self, measures: &BTreeMap<String, String>) -> String {
+        format!("**{}:** {}\n\n", get_translation("measures", self.language.code()), measure_values(measures, self.language).join(", "))
+    }
+
+    /// `**Quality Gate:** ✅ Passed` (or Failed / Warning / No quality gate).
+    pub fn generate_gate_status(&self, gate: &QualityGateStatus) -> String {
+        format!("**{}:** {}\n\n", get_translation("quality_gate", self.language.code()),
+            get_translation(gate_status_key(&gate.status), self.language.code()))
+    }
+
+    /// Lists the failing conditions of a failed gate; empty when the gate didn't fail.
+    pub fn generate_gate_failures(&self, gate: &QualityGateStatus) -> String {
+        let failures = gate_failures(gate, self.language);
+        if failures.is_empty() {
+            return String::new();
+        }
+        let lines: String = failures.iter()
+            .map(|(metric, failure)| format!("- `{}`: {}\n", metric, failure))
+            .collect();
+        format!("**{}:**\n\n{}\n", get_translation("gate_failed_reasons", self.language.code()), lines)
+    }
+
+    pub fn generate_legend(&self) -> String {
+        let mut legend = format!("**{}:**\n\n", get_translation("legend_title", self.language.code()));
+
+        for (severity, marker) in SEVERITIES.iter().zip(["⛔", "🔴", "🟠", "🟡", "🔵"]) {
+            let description_key = format!("legend_{}", severity.to_lowercase());
+            legend.push_str(&format!("- {} **{}** — {}\n", marker, severity,
+                get_translation(&description_key, self.language.code())));
+        }
+
+        legend.push('\n');
+        legend
+    }
+
+    fn author_name(&self, issue: &IssueData) -> String {
+        issue.author.clone().unwrap_or_else(|| get_translation("unknown_author", self.language.code()))
+    }
+
+    pub fn generate_author_summary(&self, projects_data: &[ProjectData]) -> String {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for issue in projects_data.iter().flat_map(|project| project.issues.iter()) {
+            *counts.entry(self.author_name(issue)).or_default() += 1;
+        }
+
+        let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let mut summary = format!("**{}:**\n\n", get_translation("issues_by_author", self.language.code()));
+        summary.push_str(&format!("| {} | {} |\n",
+            get_translation("author", self.language.code()), get_translation("issues", self.language.code())));
+        summary.push_str("|--------|--------|\n");
+        for (author, count) in counts {
+            summary.push_str(&format!("| {} | {} |\n", table_cell(&author), count));
+        }
+        summary.push('\n');
+        summary
+    }
+
+    /// Table of open issues per severity, one row per project and a grand total row.
+    pub fn generate_summary(&self, projects_data: &[ProjectData]) -> String {
+        let total_label = get_translation("total", self.language.code());
+        let mut summary = format!("**{}:**\n\n", get_translation("summary", self.language.code()));
+        summary.push_str(&format!("| {} | {} | {} |\n",
+            get_translation("project", self.language.code()), SEVERITIES.join(" | "), total_label));
+        summary.push_str(&format!("|{}|\n", vec!["--------"; SEVERITIES.len() + 2].join("|")));
+
+        let mut totals = [0u64; SEVERITIES.len()];
         for project in projects_data {
-            report.push_str(&format!("## {}: {}\n\n", project_label, project.project_key));
+            let counts = severity_counts(project);
+            let row: Vec<u64> = SEVERITIES.iter().map(|severity| counts.get(*severity).copied().unwrap_or(0)).collect();
+            for (total, count) in totals.iter_mut().zip(&row) {
+                *total += count;
+            }
+            let cells: Vec<String> = row.iter().map(u64::to_string).collect();
+            summary.push_str(&format!("| {} | {} | {} |\n",
+                table_cell(&project.project_key), cells.join(" | "), row.iter().sum::<u64>()));
+        }
+
+        let cells: Vec<String> = totals.iter().map(|total| format!("**{}**", total)).collect();
+        summary.push_str(&format!("| **{}** | {} | **{}** |\n\n", total_label, cells.join(" | "), totals.iter().sum::<u64>()));
+        summary
+    }
 
-            let formatted_date = self.format_analysis_date(project.last_analysis.as_deref());
-            report.push_str(&format!("**{}:** {}\n\n", last_analysis_label, formatted_date));
+    pub fn generate_extension_summary(&self, projects_data: &[ProjectData]) -> String {
+        let issues: Vec<IssueData> = projects_data.iter()
+            .flat_map(|project| project.issues.iter().cloned())
+            .collect();
 
-            report.push_str(&format!("**{}:**\n\n", latest_issues_label));
-            report.push_str(&self.generate_issues_table(&project.issues));
-            report.push_str("\n\n---\n\n");
+        let mut summary = format!("**{}:**\n\n", get_translation("issues_by_extension", self.language.code()));
+        summary.push_str(&format!("| {} | {} |\n",
+            get_translation("extension", self.language.code()), get_translation("issues", self.language.code())));
+        summary.push_str("|--------|--------|\n");
+        for (extension, count) in count_by_extension(&issues) {
+            let extension = match extension {
+                Some(extension) => format!(".{}", extension),
+                None => get_translation("no_extension", self.language.code()),
+            };
+            summary.push_str(&format!("| {} | {} |\n", table_cell(&extension), count));
+        }
+        summary.push('\n');
+        summary
+    }
+
+    pub fn generate_footer(&self) -> String {
+        format!("{}\n", footer_text(self.language, self.options.server_url.as_deref()))
+    }
+
+    pub fn generate_project_section(&self, project: &ProjectData) -> String {
+        let project_label = get_translation("project", self.language.code());
+        let last_analysis_label = get_translation("last_analysis", self.language.code());
+        let latest_issues_label = get_translation("latest_issues", self.language.code());
+
+        let mut section = format!("## {}: {}\n\n", project_label, project.project_key);
+
+        let formatted_date = self.format_analysis_date(project.last_analysis.as_deref());
+        section.push_str(&format!("**{}:** {}\n\n", last_analysis_label, formatted_date));
+
+        if self.options.new_vs_overall {
+            section.push_str(&self.generate_new_vs_overall(project));
+        }
+
+        if let Some(profiles) = &project.quality_profiles {
+            section.push_str(&self.generate_quality_profiles(profiles));
+        }
+
+        if let Some(ratings) = &project.ratings {
+            section.push_str(&self.generate_ratings(ratings));
+        }
+
+        if let Some(measures) = &project.measures {
+            section.push_str(&self.generate_measures(measures));
+        }
+
+        for denied in &project.denied_sections {
+            section.push_str(&format!("**{}:** _{}_\n\n",
+                get_translation(denied, self.language.code()), get_translation("insufficient_permissions", self.language.code())));
+        }
+
+        if let Some(trend) = &project.trend {
+            section.push_str(&self.generate_trend(trend));
+        }
+
+        if let Some(gate) = &project.quality_gate {
+            section.push_str(&self.generate_gate_status(gate));
+            section.push_str(&self.generate_gate_failures(gate));
+        }
+
+        if self.options.effort_summary {
+            section.push_str(&self.generate_effort_summary(project.effort.as_ref()));
+        }
+
+        if let Some(directories) = &project.worst_directories {
+            section.push_str(&self.generate_worst_directories(directories));
+        }
+
+        match project.counts_instead_of_issues() {
+            Some(counts) => {
+                section.push_str(&self.generate_issue_counts(counts));
+            }
+            None => {
+                let issues_label = match &project.compared_to {
+                    Some(base) => get_translation("issues_not_on_branch", self.language.code()).replace("{branch}", base),
+                    None if self.options.by_author => get_translation("new_code_by_author", self.language.code()),
+                    None => latest_issues_label,
+                };
+                section.push_str(&format!("**{}:**\n\n", issues_label));
+                if self.options.by_author {
+                    section.push_str(&self.author_groups(&project.issues, Some(&project.project_key)));
+                } else if self.options.severity_first {
+                    section.push_str(&self.severity_groups(&project.issues, Some(&project.project_key)));
+                } else {
+                    section.push_str(&self.generate_project_issues_table(&project.project_key, &project.issues));
+                }
+            }
+        }
+        section.push_str("\n\n");
+        if let Some(elapsed) = project.fetch_time {
+            section.push_str(&format!("_{}_\n\n", get_translation("fetch_time", self.language.code())
+                .replace("{duration}", &format_duration(elapsed))));
+        }
+        section.push_str(self.separator());
+        section
+    }
+
+    pub fn generate_clean_line(&self, project: &ProjectData) -> String {
+        format!("{}\n\n", get_translation("project_clean", self.language.code()).replace("{project}", &project.project_key))
+    }
+
+    fn separator(&self) -> &'static str {
+        if self.options.no_separators { "" } else { "---\n\n" }
+    }
+
+    pub fn generate_truncation_note(&self, omitted_sections: usize, group_by: GroupBy) -> String {
+        let key = match group_by {
+            GroupBy::Project => "report_truncated",
+            GroupBy::Severity => "report_truncated_severities",
+            GroupBy::Rule => "report_truncated_rules",
+        };
+        format!("{}\n\n", get_translation(key, self.language.code())
+            .replace("{count}", &omitted_sections.to_string()))
+    }
+
+    pub fn generate_report(&self, projects_data: &[ProjectData]) -> String {
+        let report_title = get_translation("report_title", self.language.code());
+
+        let mut header = format!("# {}\n\n", report_title);
+        if !self.options.no_timestamp {
+            let now: DateTime<Utc> = Utc::now();
+            header.push_str(&format!("{}: {}\n\n",
+                get_translation("generated", self.language.code()), now.format("%Y-%m-%d %H:%M:%S")));
+        }
+        if let Some(server_version) = &self.options.server_version {
+            header.push_str(&format!("{}: {}\n\n", get_translation("server_version", self.language.code()), server_version));
+        }
+        if let Some(window) = created_window(self.options.created_after, self.options.created_before) {
+            header.push_str(&format!("{}: {}\n\n", get_translation("issues_created", self.language.code()), window));
+        }
+        header.push_str(self.separator());
+
+        if self.options.summary {
+            header.push_str(&self.generate_summary(projects_data));
+            header.push_str(self.separator());
+        }
+
+        if self.options.author_summary {
+            header.push_str(&self.generate_author_summary(projects_data));
+            header.push_str(self.separator());
+        }
+
+        if self.options.by_extension {
+            header.push_str(&self.generate_extension_summary(projects_data));
+            header.push_str(self.separator());
+        }
+
+        let sections: Vec<String> = match self.options.group_by {
+            GroupBy::Severity => group_projects_by_severity(projects_data).iter()
+                .map(|(severity, occurrences)| self.generate_severity_section(severity, occurrences))
+                .collect(),
+            GroupBy::Rule => group_by_rule(projects_data).iter()
+                .map(|group| self.generate_rule_section(group))
+                .collect(),
+            GroupBy::Project => projects_data.iter()
+                .map(|project| if self.options.compact && is_clean(project) {
+                    self.generate_clean_line(project)
+                } else {
+                    self.generate_project_section(project)
+                })
+                .collect(),
+        };
+
+        let mut trailer = String::new();
+        if self.options.legend {
+            trailer.push_str(&self.generate_legend());
+        }
+        if self.options.footer {
+            trailer.push_str(&self.generate_footer());
+        }
+
+        let mut report = header;
+        let full_size = report.len() + sections.iter().map(String::len).sum::<usize>() + trailer.len();
+
+        match self.options.max_report_bytes {
+            Some(max_bytes) if full_size > max_bytes => {
+                // Cut only between whole project sections so no table or UTF-8 sequence is split.
+                let mut included = 0;
+                let mut size = report.len() + trailer.len();
+                for section in &sections {
+                    let note_size = self.generate_truncation_note(sections.len() - included - 1, self.options.group_by).len();
+                    if size + section.len() + note_size > max_bytes {
+                        break;
+                    }
+                    size += section.len();
+                    included += 1;
+                }
+
+                for section in &sections[..included] {
+                    report.push_str(section);
+                }
+                report.push_str(&self.generate_truncation_note(sections.len() - included, self.options.group_by));
+                report.push_str(&trailer);
+                // Header and note alone may not fit; cut at the last char boundary within the limit.
+                if report.len() > max_bytes {
+                    let mut end = max_bytes;
+                    while !report.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    report.truncate(end);
+                }
+                return report;
+            }
+            _ => {
+                for section in &sections {
+                    report.push_str(section);
+                }
+            }
         }
 
+        report.push_str(&trailer);
         report
     }
 }
\ No newline at end of file
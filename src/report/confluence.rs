@@ -0,0 +1,130 @@
+use crate::client::{IssueCounts, IssueData};
+use crate::i18n::{get_translation, Language};
+use crate::report::{footer_text, nonzero_by_severity, MarkdownReportGenerator, ProjectData};
+use chrono::{DateTime, Utc};
+
+/// Renders the report in Confluence wiki markup (`h1.` headings, `||header||` tables).
+pub struct ConfluenceReportGenerator {
+    language: Language,
+    timestamp: bool,
+    new_vs_overall: bool,
+    footer: bool,
+    footer_url: Option<String>,
+}
+
+impl ConfluenceReportGenerator {
+    pub fn new(language: Language) -> Self {
+        Self { language, timestamp: true, new_vs_overall: false, footer: false, footer_url: None }
+    }
+
+    /// Whether to include the "Generated" time (on by default).
+    pub fn with_timestamp(mut self, timestamp: bool) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Add a "New code / Overall" issue count line per project (off by default).
+    pub fn with_new_vs_overall(mut self, new_vs_overall: bool) -> Self {
+        self.new_vs_overall = new_vs_overall;
+        self
+    }
+
+    /// Append the "Generated by" line, naming `server_url` with credentials redacted.
+    pub fn with_footer(mut self, footer: bool, server_url: Option<String>) -> Self {
+        self.footer = footer;
+        self.footer_url = server_url;
+        self
+    }
+
+    /// Escapes characters that Confluence treats as table delimiters or macro/markup starts.
+    pub fn escape(value: &str) -> String {
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            if matches!(c, '|' | '{' | '}' | '[' | ']' | '\\') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    pub fn generate_issues_table(&self, issues: &[IssueData]) -> String {
+        if issues.is_empty() {
+            return format!("{}\n", get_translation("no_open_issues", self.language.code()));
+        }
+
+        let mut table = format!("||{}||{}||{}||{}||\n",
+            get_translation("severity", self.language.code()),
+            get_translation("message", self.language.code()),
+            get_translation("component", self.language.code()),
+            get_translation("line", self.language.code()));
+
+        for issue in issues {
+            table.push_str(&format!("|{}|{}|{}|{}|\n",
+                Self::escape(&issue.severity),
+                Self::escape(&issue.message.replace(['\r', '\n'], " ")),
+                Self::escape(&issue.component),
+                Self::escape(&issue.line)));
+        }
+
+        table
+    }
+
+    pub fn generate_issue_counts(&self, counts: &IssueCounts) -> String {
+        let breakdown: Vec<String> = nonzero_by_severity(&counts.by_severity).into_iter()
+            .map(|(severity, count)| format!("{}: {}", severity, count))
+            .collect();
+        let mut line = format!("*{}:* {}", get_translation("open_issues", self.language.code()), counts.total);
+        if !breakdown.is_empty() {
+            line.push_str(&format!(" ({})", breakdown.join(", ")));
+        }
+        line.push('\n');
+        line
+    }
+
+    pub fn generate_new_vs_overall(&self, project: &ProjectData) -> String {
+        let overall = project.issue_counts.as_ref().map_or_else(|| "N/A".to_string(), |counts| counts.total.to_string());
+        match &project.new_code_counts {
+            Some(new_code) => format!("*{}:* {} / *{}:* {}\n\n",
+                get_translation("new_code", self.language.code()), new_code.total, get_translation("overall", self.language.code()), overall),
+            None => format!("*{}:* {} _({})_\n\n",
+                get_translation("overall", self.language.code()), overall, get_translation("new_code_unsupported", self.language.code())),
+        }
+    }
+
+    pub fn generate_report(&self, projects_data: &[ProjectData]) -> String {
+        let dates = MarkdownReportGenerator::new(self.language);
+        let mut report = format!("h1. {}\n\n", get_translation("report_title", self.language.code()));
+        if self.timestamp {
+            let now: DateTime<Utc> = Utc::now();
+            report.push_str(&format!("{}: {}\n\n", get_translation("generated", self.language.code()), now.format("%Y-%m-%d %H:%M:%S")));
+        }
+
+        for project in projects_data {
+            report.push_str(&format!("h2. {}: {}\n\n", get_translation("project", self.language.code()), Self::escape(&project.project_key)));
+            report.push_str(&format!("*{}:* {}\n\n", get_translation("last_analysis", self.language.code()),
+                dates.format_analysis_date(project.last_analysis.as_deref())));
+            if self.new_vs_overall {
+                report.push_str(&self.generate_new_vs_overall(project));
+            }
+            match project.counts_instead_of_issues() {
+                Some(counts) => report.push_str(&self.generate_issue_counts(counts)),
+                None => {
+                    let issues_label = match &project.compared_to {
+                        Some(base) => get_translation("issues_not_on_branch", self.language.code()).replace("{branch}", base),
+                        None => get_translation("latest_issues", self.language.code()),
+                    };
+                    report.push_str(&format!("*{}:*\n\n", Self::escape(&issues_label)));
+                    report.push_str(&self.generate_issues_table(&project.issues));
+                }
+            }
+            report.push_str("\n----\n\n");
+        }
+
+        if self.footer {
+            report.push_str(&format!("{}\n", Self::escape(&footer_text(self.language, self.footer_url.as_deref()))));
+        }
+
+        report
+    }
+}
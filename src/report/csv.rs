@@ -0,0 +1,52 @@
+use crate::report::ProjectData;
+
+/// Columns of the CSV export, in order.
+pub const CSV_HEADER: [&str; 5] = ["project_key", "severity", "message", "component", "line"];
+
+/// Renders one RFC 4180 CSV row per listed issue, after a header row, for spreadsheet
+/// pipelines. Projects without issues add no rows.
+pub struct CsvReportGenerator;
+
+impl CsvReportGenerator {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Quotes `value` when it contains a comma, quote or line break, doubling inner quotes.
+    pub fn escape(value: &str) -> String {
+        if value.contains([',', '"', '\r', '\n']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+
+    fn row<'a>(fields: impl IntoIterator<Item = &'a str>) -> String {
+        let fields: Vec<String> = fields.into_iter().map(Self::escape).collect();
+        format!("{}\r\n", fields.join(","))
+    }
+
+    pub fn generate_report(&self, projects_data: &[ProjectData]) -> String {
+        let mut report = Self::row(CSV_HEADER);
+        for project in projects_data {
+            for issue in &project.issues {
+                // An empty cell keeps the column numeric for spreadsheets.
+                let line = if issue.line == "N/A" { "" } else { issue.line.as_str() };
+                report.push_str(&Self::row([
+                    project.project_key.as_str(),
+                    issue.severity.as_str(),
+                    issue.message.as_str(),
+                    issue.component.as_str(),
+                    line,
+                ]));
+            }
+        }
+        report
+    }
+}
+
+impl Default for CsvReportGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
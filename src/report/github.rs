@@ -0,0 +1,59 @@
+use crate::client::IssueData;
+use crate::report::{strip_repo_root, ProjectData};
+
+/// Renders issues as GitHub Actions workflow commands so they show up as inline annotations.
+pub struct GithubAnnotationGenerator {
+    repo_root: Option<String>,
+}
+
+impl GithubAnnotationGenerator {
+    /// `repo_root` is stripped from the start of component keys (e.g. `my-project:`) so
+    /// annotation paths are relative to the repository root.
+    pub fn new(repo_root: Option<String>) -> Self {
+        Self { repo_root }
+    }
+
+    pub fn annotation_level(severity: &str) -> &'static str {
+        match severity {
+            "BLOCKER" | "CRITICAL" => "error",
+            "MAJOR" => "warning",
+            _ => "notice",
+        }
+    }
+
+    pub fn relative_path<'a>(&self, component: &'a str) -> &'a str {
+        strip_repo_root(component, self.repo_root.as_deref())
+    }
+
+    pub fn generate_annotation(&self, issue: &IssueData) -> String {
+        let mut properties = Vec::new();
+        if issue.component != "N/A" {
+            properties.push(format!("file={}", escape_property(self.relative_path(&issue.component))));
+        }
+        if issue.line != "N/A" {
+            properties.push(format!("line={}", escape_property(&issue.line)));
+        }
+
+        format!("::{} {}::{}\n",
+            Self::annotation_level(&issue.severity),
+            properties.join(","),
+            escape_data(&issue.message))
+    }
+
+    pub fn generate_report(&self, projects_data: &[ProjectData]) -> String {
+        projects_data.iter()
+            .flat_map(|project| project.issues.iter())
+            .map(|issue| self.generate_annotation(issue))
+            .collect()
+    }
+}
+
+/// Escapes a workflow command message.
+fn escape_data(value: &str) -> String {
+    value.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escapes a workflow command property value, which additionally can't contain `:` or `,`.
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
@@ -0,0 +1,109 @@
+use crate::i18n::{get_translation, Language};
+use crate::report::{nonzero_by_severity, MarkdownReportGenerator, ProjectData, ReportOptions};
+use crate::thresholds::severity_totals;
+
+/// Hidden first line a bot searches for to update its previous comment instead of adding one.
+pub const GITHUB_COMMENT_MARKER: &str = "<!-- sonarqube-checker -->";
+
+/// GitHub rejects comment bodies longer than this many characters.
+pub const GITHUB_COMMENT_MAX_CHARS: usize = 65536;
+
+/// Renders a single pull request comment body: marker, headline and one collapsible section
+/// per project.
+pub struct GithubCommentGenerator {
+    language: Language,
+    markdown: MarkdownReportGenerator,
+    max_chars: usize,
+}
+
+impl GithubCommentGenerator {
+    pub fn new(language: Language, options: ReportOptions) -> Self {
+        Self {
+            language,
+            markdown: MarkdownReportGenerator::with_options(language, options),
+            max_chars: GITHUB_COMMENT_MAX_CHARS,
+        }
+    }
+
+    /// Overrides the comment size limit (in characters).
+    pub fn with_max_chars(mut self, max_chars: usize) -> Self {
+        self.max_chars = max_chars;
+        self
+    }
+
+    /// `### SonarQube: 5 open issues in 2 projects (1 BLOCKER, 4 MAJOR)`, counting every open
+    /// issue where the counts facet was fetched and the listed ones otherwise.
+    pub fn generate_headline(&self, projects_data: &[ProjectData]) -> String {
+        let totals = severity_totals(projects_data);
+        let total: u64 = totals.values().sum();
+        let projects = projects_data.len().to_string();
+
+        if total == 0 {
+            return format!("### {}\n\n", get_translation("comment_headline_clean", self.language.code())
+                .replace("{projects}", &projects));
+        }
+
+        let breakdown: Vec<String> = nonzero_by_severity(&totals).into_iter()
+            .map(|(severity, count)| format!("{} {}", count, severity))
+            .collect();
+        format!("### {} ({})\n\n", get_translation("comment_headline", self.language.code())
+            .replace("{issues}", &total.to_string())
+            .replace("{projects}", &projects), breakdown.join(", "))
+    }
+
+    pub fn generate_project_section(&self, project: &ProjectData) -> String {
+        let count = project.issue_counts.as_ref()
+            .map_or(project.issues.len() as u64, |counts| counts.total);
+
+        let mut body = String::new();
+        if let Some(gate) = &project.quality_gate {
+            body.push_str(&self.markdown.generate_gate_failures(gate));
+        }
+        match project.counts_instead_of_issues() {
+            Some(counts) => {
+                body.push_str(&self.markdown.generate_issue_counts(counts));
+            }
+            None => body.push_str(&self.markdown.generate_project_issues_table(&project.project_key, &project.issues)),
+        }
+
+        // The blank lines let GitHub render the Markdown inside the HTML block.
+        format!("<details><summary><b>{}</b> — {}</summary>\n\n{}\n\n</details>\n\n",
+            escape_html(&project.project_key),
+            get_translation(if count == 1 { "total_issue" } else { "total_issues" }, self.language.code())
+                .replace("{count}", &count.to_string()),
+            body.trim_end())
+    }
+
+    pub fn generate_truncation_note(&self, omitted_projects: usize) -> String {
+        format!("_{}_\n", get_translation("comment_truncated", self.language.code())
+            .replace("{count}", &omitted_projects.to_string()))
+    }
+
+    /// Keeps whole project sections while the body fits [`GITHUB_COMMENT_MAX_CHARS`], then
+    /// notes how many were left out.
+    pub fn generate_report(&self, projects_data: &[ProjectData]) -> String {
+        let mut comment = format!("{}\n{}", GITHUB_COMMENT_MARKER, self.generate_headline(projects_data));
+        let mut size = comment.chars().count();
+
+        for (index, project) in projects_data.iter().enumerate() {
+            let section = self.generate_project_section(project);
+            let remaining = projects_data.len() - index - 1;
+            let note_size = if remaining > 0 { self.generate_truncation_note(remaining).chars().count() } else { 0 };
+            let section_size = section.chars().count();
+
+            if size + section_size + note_size > self.max_chars {
+                comment.push_str(&self.generate_truncation_note(projects_data.len() - index));
+                return comment;
+            }
+            comment.push_str(&section);
+            size += section_size;
+        }
+
+        comment
+    }
+}
+
+/// Escapes text placed inside the `<summary>` HTML element.
+fn escape_html(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
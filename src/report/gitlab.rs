@@ -0,0 +1,87 @@
+use crate::client::IssueData;
+use crate::report::{strip_repo_root, ProjectData};
+use serde::Serialize;
+
+/// One entry of a GitLab Code Quality report.
+#[derive(Debug, Serialize)]
+pub struct CodeQualityIssue {
+    pub description: String,
+    pub check_name: String,
+    pub fingerprint: String,
+    pub severity: &'static str,
+    pub location: CodeQualityLocation,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CodeQualityLocation {
+    pub path: String,
+    pub lines: CodeQualityLines,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CodeQualityLines {
+    pub begin: u32,
+}
+
+/// Renders issues as a GitLab CI Code Quality artifact (`artifacts:reports:codequality`).
+pub struct GitlabCodeQualityGenerator {
+    repo_root: Option<String>,
+}
+
+impl GitlabCodeQualityGenerator {
+    /// `repo_root` is stripped from component keys, as for `--format github`.
+    pub fn new(repo_root: Option<String>) -> Self {
+        Self { repo_root }
+    }
+
+    /// Maps a SonarQube severity to GitLab's; unknown severities become `info`.
+    pub fn gitlab_severity(severity: &str) -> &'static str {
+        match severity.to_uppercase().as_str() {
+            "BLOCKER" => "blocker",
+            "CRITICAL" | "HIGH" => "critical",
+            "MAJOR" | "MEDIUM" => "major",
+            "MINOR" | "LOW" => "minor",
+            _ => "info",
+        }
+    }
+
+    /// Identifies an issue across pipelines so GitLab can tell new issues from fixed ones:
+    /// FNV-1a over rule, component, message and line, which (unlike `DefaultHasher`) is the
+    /// same on every build.
+    pub fn fingerprint(issue: &IssueData) -> String {
+        let rule = issue.rule.as_deref().unwrap_or("");
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for part in [rule, &issue.component, &issue.message, &issue.line] {
+            // A separator byte that can't occur in UTF-8 keeps ("ab", "c") apart from ("a", "bc").
+            for byte in part.bytes().chain([0xff]) {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+        format!("{:016x}", hash)
+    }
+
+    pub fn code_quality_issue(&self, issue: &IssueData) -> CodeQualityIssue {
+        CodeQualityIssue {
+            description: issue.message.clone(),
+            check_name: issue.rule.clone().unwrap_or_else(|| "sonarqube".to_string()),
+            fingerprint: Self::fingerprint(issue),
+            severity: Self::gitlab_severity(&issue.severity),
+            location: CodeQualityLocation {
+                path: strip_repo_root(&issue.component, self.repo_root.as_deref()).to_string(),
+                // GitLab requires a line; file-level issues point at the first one.
+                lines: CodeQualityLines { begin: issue.line.parse().unwrap_or(1) },
+            },
+        }
+    }
+
+    pub fn generate_report(&self, projects_data: &[ProjectData]) -> String {
+        let issues: Vec<CodeQualityIssue> = projects_data.iter()
+            .flat_map(|project| project.issues.iter())
+            .map(|issue| self.code_quality_issue(issue))
+            .collect();
+        let mut report = serde_json::to_string_pretty(&issues).expect("code quality issues always serialize");
+        report.push('\n');
+        report
+    }
+}
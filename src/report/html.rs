@@ -0,0 +1,244 @@
+use crate::client::IssueData;
+use crate::i18n::{get_translation, Language};
+use crate::report::{footer_text, gate_failures, gate_status_key, issue_url, measure_values, nonzero_by_severity, rating_grades, shorten_component, MarkdownReportGenerator, ProjectData};
+use crate::theme::Theme;
+use chrono::{DateTime, Utc};
+
+/// Inline stylesheet; the document loads no external assets so it can be mailed as is.
+const STYLE: &str = "body{font-family:-apple-system,'Segoe UI',Roboto,Helvetica,Arial,sans-serif;margin:2em;color:#212121}\
+h1{border-bottom:2px solid #e0e0e0;padding-bottom:.3em}\
+h2{margin-top:1.5em}\
+table{border-collapse:collapse;width:100%;margin:.5em 0 1.5em}\
+th,td{border:1px solid #e0e0e0;padding:.4em .6em;text-align:left;vertical-align:top}\
+th{background:#f5f5f5}\
+tr:nth-child(even) td{background:#fafafa}\
+.severity{display:inline-block;padding:.1em .5em;border-radius:.3em;font-size:.85em;font-weight:bold;white-space:nowrap}\
+.component{font-family:ui-monospace,Consolas,monospace;word-break:break-all}\
+.muted{color:#757575}";
+
+/// Renders the report as a standalone HTML document: one table per project, with severities
+/// as badges in the theme's colors.
+pub struct HtmlReportGenerator {
+    language: Language,
+    timestamp: bool,
+    theme: Theme,
+    shorten_components: Option<usize>,
+    server_url: Option<String>,
+    footer: bool,
+    footer_url: Option<String>,
+}
+
+impl HtmlReportGenerator {
+    pub fn new(language: Language) -> Self {
+        Self { language, timestamp: true, theme: Theme::default(), shorten_components: None, server_url: None, footer: false, footer_url: None }
+    }
+
+    /// Whether to include the "Generated" time (on by default).
+    pub fn with_timestamp(mut self, timestamp: bool) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Colors of the severity badges.
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Show only this many trailing component path segments, with the full key as a tooltip.
+    pub fn with_shortened_components(mut self, segments: Option<usize>) -> Self {
+        self.shorten_components = segments;
+        self
+    }
+
+    /// Link components to the issues on the server at `server_url`.
+    pub fn with_issue_links(mut self, server_url: Option<String>) -> Self {
+        self.server_url = server_url;
+        self
+    }
+
+    /// Whether to end with a "Generated by" line naming the tool version and, with credentials
+    /// redacted, `server_url`.
+    pub fn with_footer(mut self, footer: bool, server_url: Option<String>) -> Self {
+        self.footer = footer;
+        self.footer_url = server_url;
+        self
+    }
+
+    /// Escapes the characters HTML treats as markup in text and attribute values.
+    pub fn escape(value: &str) -> String {
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&#39;"),
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    fn text(&self, key: &str) -> String {
+        Self::escape(&get_translation(key, self.language.code()))
+    }
+
+    /// Severity badge in the theme's color; severities the theme doesn't know stay grey.
+    pub fn severity_badge(&self, severity: &str) -> String {
+        let background = self.theme.color(severity).map_or("#9e9e9e", |color| color.html.as_str());
+        format!(
+            "<span class=\"severity\" style=\"background:{};color:{}\">{}</span>",
+            background,
+            badge_text_color(background),
+            Self::escape(severity)
+        )
+    }
+
+    fn component_cell(&self, component: &str, link: Option<String>) -> String {
+        let (title, text) = match self.shorten_components.map(|segments| shorten_component(component, segments)) {
+            Some(short) if short != component => (format!(" title=\"{}\"", Self::escape(component)), Self::escape(&short)),
+            _ => (String::new(), Self::escape(component)),
+        };
+        match link {
+            Some(url) => format!("<td class=\"component\"{}><a href=\"{}\">{}</a></td>", title, Self::escape(&url), text),
+            None => format!("<td class=\"component\"{}>{}</td>", title, text),
+        }
+    }
+
+    pub fn generate_issues_table(&self, issues: &[IssueData]) -> String {
+        self.issues_table(issues, None)
+    }
+
+    /// Issues table of `project_key`, whose components link to the issues when a server
+    /// was given to [`Self::with_issue_links`].
+    pub fn generate_project_issues_table(&self, project_key: &str, issues: &[IssueData]) -> String {
+        self.issues_table(issues, Some(project_key))
+    }
+
+    fn issues_table(&self, issues: &[IssueData], project_key: Option<&str>) -> String {
+        if issues.is_empty() {
+            return format!("<p>{}</p>\n", self.text("no_open_issues"));
+        }
+
+        let mut table = format!(
+            "<table>\n<thead><tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr></thead>\n<tbody>\n",
+            self.text("severity"),
+            self.text("issue_type"),
+            self.text("message"),
+            self.text("component"),
+            self.text("line")
+        );
+        for issue in issues {
+            table.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td>{}<td>{}</td></tr>\n",
+                self.severity_badge(&issue.severity),
+                Self::escape(issue.issue_type.as_deref().unwrap_or("N/A")),
+                Self::escape(&issue.message),
+                self.component_cell(&issue.component, self.issue_link(project_key, issue)),
+                Self::escape(&issue.line)
+            ));
+        }
+        table.push_str("</tbody>\n</table>\n");
+        table
+    }
+
+    fn issue_link(&self, project_key: Option<&str>, issue: &IssueData) -> Option<String> {
+        issue_url(self.server_url.as_deref()?, project_key?, issue.key.as_deref()?)
+    }
+
+    pub fn generate_project_section(&self, project: &ProjectData) -> String {
+        let mut section = format!("<section>\n<h2>{}: {}</h2>\n", self.text("project"), Self::escape(&project.project_key));
+
+        let last_analysis = MarkdownReportGenerator::new(self.language).format_analysis_date(project.last_analysis.as_deref());
+        section.push_str(&format!("<p><strong>{}:</strong> {}</p>\n", self.text("last_analysis"), Self::escape(&last_analysis)));
+
+        if let Some(ratings) = &project.ratings {
+            let grades: Vec<String> = rating_grades(ratings, self.language).into_iter()
+                .map(|(label, grade)| format!("{}: {}", Self::escape(&label),
+                    grade.map_or_else(|| format!("<em>{}</em>", self.text("no_rating")), |grade| Self::escape(&grade))))
+                .collect();
+            section.push_str(&format!("<p><strong>{}:</strong> {}</p>\n", self.text("ratings"), grades.join(", ")));
+        }
+
+        if let Some(measures) = &project.measures {
+            section.push_str(&format!("<p><strong>{}:</strong> {}</p>\n", self.text("measures"),
+                Self::escape(&measure_values(measures, self.language).join(", "))));
+        }
+
+        if let Some(gate) = &project.quality_gate {
+            section.push_str(&format!("<p><strong>{}:</strong> {}</p>\n", self.text("quality_gate"), self.text(gate_status_key(&gate.status))));
+            let failures = gate_failures(gate, self.language);
+            if !failures.is_empty() {
+                section.push_str(&format!("<p><strong>{}:</strong></p>\n<ul>\n", self.text("gate_failed_reasons")));
+                for (metric, failure) in failures {
+                    section.push_str(&format!("<li><code>{}</code>: {}</li>\n", Self::escape(metric), Self::escape(&failure)));
+                }
+                section.push_str("</ul>\n");
+            }
+        }
+
+        match project.counts_instead_of_issues() {
+            Some(counts) => {
+                let badges: Vec<String> = nonzero_by_severity(&counts.by_severity).into_iter()
+                    .map(|(severity, count)| format!("{} {}", self.severity_badge(severity), count))
+                    .collect();
+                section.push_str(&format!("<p><strong>{}:</strong> {} {}</p>\n", self.text("open_issues"), counts.total, badges.join(" ")));
+            }
+            None => {
+                let issues_label = match &project.compared_to {
+                    Some(base) => get_translation("issues_not_on_branch", self.language.code()).replace("{branch}", base),
+                    None => get_translation("latest_issues", self.language.code()),
+                };
+                section.push_str(&format!("<h3>{}</h3>\n", Self::escape(&issues_label)));
+                section.push_str(&self.generate_project_issues_table(&project.project_key, &project.issues));
+            }
+        }
+
+        section.push_str("</section>\n");
+        section
+    }
+
+    pub fn generate_report(&self, projects_data: &[ProjectData]) -> String {
+        let title = self.text("report_title");
+        let mut report = format!(
+            "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+            self.language.code(),
+            title,
+            STYLE,
+            title
+        );
+        if self.timestamp {
+            let now: DateTime<Utc> = Utc::now();
+            report.push_str(&format!("<p class=\"muted\">{}: {}</p>\n", self.text("generated"), now.format("%Y-%m-%d %H:%M:%S")));
+        }
+
+        for project in projects_data {
+            report.push_str(&self.generate_project_section(project));
+        }
+
+        if self.footer {
+            let footer = footer_text(self.language, self.footer_url.as_deref());
+            report.push_str(&format!("<footer class=\"muted\">{}</footer>\n", Self::escape(&footer)));
+        }
+
+        report.push_str("</body>\n</html>\n");
+        report
+    }
+}
+
+/// Black or white, whichever reads better on `background` (a `#rgb` or `#rrggbb` color).
+fn badge_text_color(background: &str) -> &'static str {
+    let hex = background.trim_start_matches('#');
+    let channel = |index: usize| -> f64 {
+        let value = if hex.len() == 3 {
+            u8::from_str_radix(&hex[index..index + 1].repeat(2), 16)
+        } else {
+            u8::from_str_radix(hex.get(index * 2..index * 2 + 2).unwrap_or("00"), 16)
+        };
+        value.unwrap_or(0) as f64
+    };
+    let luminance = 0.299 * channel(0) + 0.587 * channel(1) + 0.114 * channel(2);
+    if luminance > 150.0 { "#212121" } else { "#ffffff" }
+}
@@ -0,0 +1,56 @@
+use crate::report::ProjectData;
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+
+/// Root object of the JSON report.
+#[derive(Debug, Serialize)]
+pub struct JsonReport<'a> {
+    /// RFC 3339 generation time; left out with `--no-timestamp`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generated: Option<String>,
+    pub projects: &'a [ProjectData],
+}
+
+/// Serializes the fetched project data as JSON, indented by default or on a single line.
+pub struct JsonReportGenerator {
+    compact: bool,
+    timestamp: bool,
+}
+
+impl JsonReportGenerator {
+    pub fn new() -> Self {
+        Self { compact: false, timestamp: true }
+    }
+
+    /// Whether to write everything on one line, without indentation (`--format json-compact`).
+    pub fn with_compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    /// Whether to include the `generated` time (on by default).
+    pub fn with_timestamp(mut self, timestamp: bool) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub fn generate_report(&self, projects_data: &[ProjectData]) -> String {
+        let report = JsonReport {
+            generated: self.timestamp.then(|| Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)),
+            projects: projects_data,
+        };
+        let mut json = if self.compact {
+            serde_json::to_string(&report)
+        } else {
+            serde_json::to_string_pretty(&report)
+        }.expect("project data always serializes");
+        json.push('\n');
+        json
+    }
+}
+
+impl Default for JsonReportGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
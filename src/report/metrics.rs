@@ -0,0 +1,66 @@
+use crate::client::IssueCounts;
+use crate::report::ProjectData;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// One sample in the `ndjson-metrics` output.
+#[derive(Debug, Serialize)]
+struct MetricLine<'a> {
+    project: &'a str,
+    metric: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    severity: Option<&'a str>,
+    value: u64,
+    /// Unix timestamp in seconds.
+    ts: i64,
+}
+
+/// Renders issue counts as newline-delimited JSON, one line per metric per project, for
+/// time-series ingestion (InfluxDB, Prometheus exporters and the like).
+pub struct MetricsReportGenerator;
+
+impl MetricsReportGenerator {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn push_counts(lines: &mut String, project: &str, metric: &str, counts: &IssueCounts, ts: i64) {
+        let mut push = |severity: Option<&str>, value: u64| {
+            let line = MetricLine { project, metric, severity, value, ts };
+            lines.push_str(&serde_json::to_string(&line).expect("metric lines always serialize"));
+            lines.push('\n');
+        };
+
+        push(None, counts.total);
+        for (severity, count) in &counts.by_severity {
+            push(Some(severity), *count);
+        }
+    }
+
+    /// Like [`generate_report`](Self::generate_report) with a fixed timestamp.
+    pub fn generate_report_at(&self, projects_data: &[ProjectData], timestamp: DateTime<Utc>) -> String {
+        let ts = timestamp.timestamp();
+        let mut lines = String::new();
+
+        for project in projects_data {
+            if let Some(counts) = &project.issue_counts {
+                Self::push_counts(&mut lines, &project.project_key, "issues", counts, ts);
+            }
+            if let Some(counts) = &project.new_code_counts {
+                Self::push_counts(&mut lines, &project.project_key, "new_code_issues", counts, ts);
+            }
+        }
+
+        lines
+    }
+
+    pub fn generate_report(&self, projects_data: &[ProjectData]) -> String {
+        self.generate_report_at(projects_data, Utc::now())
+    }
+}
+
+impl Default for MetricsReportGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,116 @@
+use crate::client::IssueData;
+use crate::i18n::{get_translation, Language};
+use crate::report::{nonzero_by_severity, MarkdownReportGenerator, ProjectData};
+use crate::theme::Theme;
+use chrono::{DateTime, Utc};
+
+/// Renders the report as plain text for log sinks that mangle Markdown: `=`-underlined
+/// headings and indented issue lines, no tables, emphasis or box-drawing characters.
+pub struct PlainReportGenerator {
+    language: Language,
+    timestamp: bool,
+    colors: Option<Theme>,
+}
+
+impl PlainReportGenerator {
+    pub fn new(language: Language) -> Self {
+        Self { language, timestamp: true, colors: None }
+    }
+
+    /// Whether to include the "Generated" time (on by default).
+    pub fn with_timestamp(mut self, timestamp: bool) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Color severities with the theme's ANSI codes, for a terminal (off by default).
+    pub fn with_colors(mut self, theme: Option<Theme>) -> Self {
+        self.colors = theme;
+        self
+    }
+
+    /// Translated label without its leading emoji, e.g. `Project` for `📁 Project`.
+    fn label(&self, key: &str) -> String {
+        get_translation(key, self.language.code())
+            .trim_start_matches(|c: char| !c.is_alphanumeric())
+            .to_string()
+    }
+
+    pub fn heading(title: &str) -> String {
+        format!("{}\n{}\n\n", title, "=".repeat(title.chars().count()))
+    }
+
+    /// One `  - SEVERITY  component:line  message` line per issue, severities padded to the
+    /// widest one so components line up.
+    pub fn generate_issue_lines(&self, issues: &[IssueData]) -> String {
+        if issues.is_empty() {
+            return format!("  {}\n", self.label("no_open_issues"));
+        }
+
+        let width = issues.iter().map(|issue| issue.severity.chars().count()).max().unwrap_or(0);
+        issues.iter()
+            .map(|issue| {
+                let location = if issue.line == "N/A" {
+                    issue.component.clone()
+                } else {
+                    format!("{}:{}", issue.component, issue.line)
+                };
+                let message = issue.message.split_whitespace().collect::<Vec<_>>().join(" ");
+                let severity = format!("{:<width$}", issue.severity, width = width);
+                let severity = match &self.colors {
+                    Some(theme) => theme.paint(&issue.severity, &severity),
+                    None => severity,
+                };
+                format!("  - {}  {}  {}\n", severity, location, message)
+            })
+            .collect()
+    }
+
+    pub fn generate_project_section(&self, project: &ProjectData) -> String {
+        let mut section = Self::heading(&format!("{}: {}", self.label("project"), project.project_key));
+
+        let last_analysis = match project.last_analysis.as_deref() {
+            Some(date) => MarkdownReportGenerator::new(self.language).format_analysis_date(Some(date)),
+            None => self.label("no_analysis_available"),
+        };
+        section.push_str(&format!("{}: {}\n\n", self.label("last_analysis"), last_analysis));
+
+        match project.counts_instead_of_issues() {
+            Some(counts) => {
+                let breakdown: Vec<String> = nonzero_by_severity(&counts.by_severity).into_iter()
+                    .map(|(severity, count)| format!("{}: {}", severity, count))
+                    .collect();
+                section.push_str(&format!("{}: {}", self.label("open_issues"), counts.total));
+                if !breakdown.is_empty() {
+                    section.push_str(&format!(" ({})", breakdown.join(", ")));
+                }
+                section.push('\n');
+            }
+            None => {
+                let issues_label = match &project.compared_to {
+                    Some(base) => self.label("issues_not_on_branch").replace("{branch}", base),
+                    None => self.label("latest_issues"),
+                };
+                section.push_str(&format!("{}:\n", issues_label));
+                section.push_str(&self.generate_issue_lines(&project.issues));
+            }
+        }
+
+        section.push('\n');
+        section
+    }
+
+    pub fn generate_report(&self, projects_data: &[ProjectData]) -> String {
+        let mut report = Self::heading(&self.label("report_title"));
+        if self.timestamp {
+            let now: DateTime<Utc> = Utc::now();
+            report.push_str(&format!("{}: {}\n\n", self.label("generated"), now.format("%Y-%m-%d %H:%M:%S")));
+        }
+
+        for project in projects_data {
+            report.push_str(&self.generate_project_section(project));
+        }
+
+        report
+    }
+}
@@ -0,0 +1,32 @@
+//! Canonical severity ordering shared by every feature that compares severities.
+
+/// Legacy severities from worst to least severe.
+pub const SEVERITIES: [&str; 5] = ["BLOCKER", "CRITICAL", "MAJOR", "MINOR", "INFO"];
+
+/// Rank returned for severities this tool doesn't know. Callers comparing ranks must check
+/// [`is_known_severity`] first, since the sentinel is numerically above every real rank.
+pub const UNKNOWN_SEVERITY_RANK: u8 = u8::MAX;
+
+/// Maps a severity to its rank, `INFO` = 0 through `BLOCKER` = 4, case-insensitively.
+/// The clean-code impact severities `HIGH`, `MEDIUM` and `LOW` rank with `CRITICAL`, `MAJOR`
+/// and `MINOR` respectively.
+pub fn severity_rank(severity: &str) -> u8 {
+    match severity.to_uppercase().as_str() {
+        "BLOCKER" => 4,
+        "CRITICAL" | "HIGH" => 3,
+        "MAJOR" | "MEDIUM" => 2,
+        "MINOR" | "LOW" => 1,
+        "INFO" => 0,
+        _ => UNKNOWN_SEVERITY_RANK,
+    }
+}
+
+pub fn is_known_severity(severity: &str) -> bool {
+    severity_rank(severity) != UNKNOWN_SEVERITY_RANK
+}
+
+/// Whether `severity` is at least as severe as `threshold`. Unknown severities never qualify.
+pub fn meets_threshold(severity: &str, threshold: &str) -> bool {
+    is_known_severity(severity) && is_known_severity(threshold)
+        && severity_rank(severity) >= severity_rank(threshold)
+}
@@ -0,0 +1,191 @@
+use anyhow::{bail, Context, Result};
+use encoding_rs::Encoding;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Destination for a rendered report, chosen from the `--output` value.
+pub trait OutputSink {
+    /// Writes an already encoded report, e.g. from [`encode_report`].
+    fn write_bytes(&self, contents: &[u8]) -> Result<()>;
+
+    fn write(&self, contents: &str) -> Result<()> {
+        self.write_bytes(contents.as_bytes())
+    }
+
+    /// Where the report went, for log messages.
+    fn location(&self) -> String;
+}
+
+/// Writes to a local file; used for every `--output` that isn't a cloud URL.
+pub struct FileSink {
+    path: PathBuf,
+}
+
+impl FileSink {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl OutputSink for FileSink {
+    fn write_bytes(&self, contents: &[u8]) -> Result<()> {
+        let is_special = fs::metadata(&self.path).is_ok_and(|metadata| !metadata.is_file() && !metadata.is_dir());
+        if !is_special {
+            return fs::write(&self.path, contents)
+                .context(format!("Error writing to file: {}", self.path.display()));
+        }
+
+        // FIFOs and devices (e.g. /dev/stdout) can't be truncated; stream into them instead.
+        OpenOptions::new()
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(contents))
+            .context(format!("Error writing to file: {}", self.path.display()))
+    }
+
+    fn location(&self) -> String {
+        self.path.display().to_string()
+    }
+}
+
+/// Environment variable GitHub Actions points at the job summary file.
+pub const GITHUB_STEP_SUMMARY: &str = "GITHUB_STEP_SUMMARY";
+
+/// Appends to the GitHub Actions job summary (`--github-summary`); steps may write to it
+/// several times, so earlier content is kept.
+pub struct GithubSummarySink {
+    path: PathBuf,
+}
+
+impl GithubSummarySink {
+    /// Uses the file named by `$GITHUB_STEP_SUMMARY`; an error when it's unset, i.e. when
+    /// not running in a GitHub Actions step.
+    pub fn from_env() -> Result<Self> {
+        match std::env::var_os(GITHUB_STEP_SUMMARY).filter(|path| !path.is_empty()) {
+            Some(path) => Ok(Self { path: path.into() }),
+            None => bail!("Error: --github-summary needs ${} (set by GitHub Actions in each step)", GITHUB_STEP_SUMMARY),
+        }
+    }
+}
+
+impl OutputSink for GithubSummarySink {
+    fn write_bytes(&self, contents: &[u8]) -> Result<()> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(contents))
+            .context(format!("Error writing to job summary: {}", self.path.display()))
+    }
+
+    fn location(&self) -> String {
+        format!("job summary ({})", self.path.display())
+    }
+}
+
+/// Uploads to an object store bucket. Credentials are resolved by `object_store` from the
+/// environment, the same way the AWS and Google Cloud SDKs do.
+#[cfg(any(feature = "s3", feature = "gcs"))]
+pub struct ObjectStoreSink {
+    store: Box<dyn object_store::ObjectStore>,
+    key: object_store::path::Path,
+    url: String,
+}
+
+#[cfg(any(feature = "s3", feature = "gcs"))]
+impl ObjectStoreSink {
+    #[cfg(feature = "s3")]
+    pub fn s3(url: &str, bucket: &str, key: &str) -> Result<Self> {
+        let store = object_store::aws::AmazonS3Builder::from_env()
+            .with_bucket_name(bucket)
+            .build()
+            .with_context(|| format!("Failed to configure S3 for {}", url))?;
+        Self::new(Box::new(store), url, key)
+    }
+
+    #[cfg(feature = "gcs")]
+    pub fn gcs(url: &str, bucket: &str, key: &str) -> Result<Self> {
+        let store = object_store::gcp::GoogleCloudStorageBuilder::from_env()
+            .with_bucket_name(bucket)
+            .build()
+            .with_context(|| format!("Failed to configure Google Cloud Storage for {}", url))?;
+        Self::new(Box::new(store), url, key)
+    }
+
+    fn new(store: Box<dyn object_store::ObjectStore>, url: &str, key: &str) -> Result<Self> {
+        let key = object_store::path::Path::parse(key)
+            .with_context(|| format!("Invalid object key in {}", url))?;
+        Ok(Self { store, key, url: url.to_string() })
+    }
+}
+
+#[cfg(any(feature = "s3", feature = "gcs"))]
+impl OutputSink for ObjectStoreSink {
+    fn write_bytes(&self, contents: &[u8]) -> Result<()> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("Failed to start upload runtime")?;
+        runtime.block_on(self.store.put(&self.key, contents.to_vec().into()))
+            .with_context(|| format!("Error uploading to {}", self.url))?;
+        Ok(())
+    }
+
+    fn location(&self) -> String {
+        self.url.clone()
+    }
+}
+
+/// Parses an `--output-encoding` label such as `windows-1251`, `cp1251` or `latin1` (WHATWG
+/// encoding labels). UTF-16 is rejected since encoders only produce ASCII-compatible output.
+pub fn parse_encoding(label: &str) -> Result<&'static Encoding, String> {
+    let encoding = Encoding::for_label(label.trim().as_bytes())
+        .ok_or_else(|| format!("unknown encoding '{}'", label))?;
+    if encoding.output_encoding() != encoding {
+        return Err(format!("cannot write reports in {}", encoding.name()));
+    }
+    Ok(encoding)
+}
+
+/// Encodes a report for `--output-encoding`. Characters the encoding can't represent become
+/// HTML numeric character references such as `&#128202;`; the flag tells whether any did.
+pub fn encode_report(report: &str, encoding: &'static Encoding) -> (Vec<u8>, bool) {
+    let (bytes, _, replaced) = encoding.encode(report);
+    (bytes.into_owned(), replaced)
+}
+
+/// Picks the sink for an `--output` value: `s3://bucket/key` and `gs://bucket/key` upload
+/// (when built with the `s3` / `gcs` feature), anything else is a local path.
+pub fn sink_for(output: &str) -> Result<Box<dyn OutputSink>> {
+    let Some((scheme, _)) = output.split_once("://") else {
+        return Ok(Box::new(FileSink::new(output)));
+    };
+
+    match scheme {
+        #[cfg(feature = "s3")]
+        "s3" => {
+            let (bucket, key) = bucket_and_key(output)?;
+            Ok(Box::new(ObjectStoreSink::s3(output, bucket, key)?))
+        }
+        #[cfg(feature = "gcs")]
+        "gs" => {
+            let (bucket, key) = bucket_and_key(output)?;
+            Ok(Box::new(ObjectStoreSink::gcs(output, bucket, key)?))
+        }
+        #[cfg(not(feature = "s3"))]
+        "s3" => bail!("Error: {} needs a build with the `s3` feature (cargo build --features s3)", output),
+        #[cfg(not(feature = "gcs"))]
+        "gs" => bail!("Error: {} needs a build with the `gcs` feature (cargo build --features gcs)", output),
+        _ => bail!("Error: unsupported output URL scheme '{}://' in {}", scheme, output),
+    }
+}
+
+/// Splits `scheme://bucket/key` into bucket and object key.
+pub fn bucket_and_key(url: &str) -> Result<(&str, &str)> {
+    let (scheme, rest) = url.split_once("://").unwrap_or(("", url));
+    match rest.split_once('/') {
+        Some((bucket, key)) if !bucket.is_empty() && !key.is_empty() => Ok((bucket, key)),
+        _ => bail!("Error: {} must name a bucket and an object key, e.g. {}://bucket/report.md", url, scheme),
+    }
+}
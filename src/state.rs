@@ -0,0 +1,68 @@
+//! Rolling record of the issues reported per project, so cron runs can report only what
+//! appeared since the previous run (`--state-file`).
+
+use crate::client::IssueData;
+use crate::report::ProjectData;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunState {
+    /// Keys of the issues listed by the last run, per project key.
+    pub projects: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl RunState {
+    /// Reads the state left by the previous run; a missing file is an empty state (first run).
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read state file {}", path.display()))?;
+        serde_json::from_str(&text)
+            .with_context(|| format!("Invalid state file {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize state")?;
+        fs::write(path, json + "\n")
+            .with_context(|| format!("Error writing state file: {}", path.display()))
+    }
+
+    /// Whether a previous run recorded `project_key`; until then every issue counts as new.
+    pub fn has_project(&self, project_key: &str) -> bool {
+        self.projects.contains_key(project_key)
+    }
+
+    /// Issues of `project_key` not listed by the previous run, in their original order. Issues
+    /// without a key can't be matched and are always kept.
+    pub fn new_since(&self, project_key: &str, issues: &[IssueData]) -> Vec<IssueData> {
+        let Some(seen) = self.projects.get(project_key) else {
+            return issues.to_vec();
+        };
+        issues.iter()
+            .filter(|issue| !issue.key.as_ref().is_some_and(|key| seen.contains(key)))
+            .cloned()
+            .collect()
+    }
+
+    /// Replaces the recorded issues of `project_key` with `issues`; other projects are kept.
+    pub fn record(&mut self, project_key: &str, issues: &[IssueData]) {
+        let keys = issues.iter().filter_map(|issue| issue.key.clone()).collect();
+        self.projects.insert(project_key.to_string(), keys);
+    }
+}
+
+/// Projects listing fewer issues than their counts report, i.e. cut by `--max-issues`.
+/// Recording such a list would forget the unlisted issues and report them as new next run.
+/// Projects fetched without counts can't be checked and are left out.
+pub fn truncated_projects(projects_data: &[ProjectData]) -> Vec<&str> {
+    projects_data.iter()
+        .filter(|project| project.issue_counts.as_ref().is_some_and(|counts| counts.total > project.issues.len() as u64))
+        .map(|project| project.project_key.as_str())
+        .collect()
+}
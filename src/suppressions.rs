@@ -0,0 +1,80 @@
+use crate::client::SonarQubeClient;
+use crate::i18n::{get_translation, Language};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Statuses of issues that are still waiting to be fixed.
+const UNRESOLVED_STATUSES: [&str; 3] = ["OPEN", "CONFIRMED", "REOPENED"];
+
+/// Reads a suppressions file: one issue key per line, optionally followed by whitespace and a
+/// note. Blank lines and `#` comments are skipped; duplicates are kept once, in file order.
+pub fn load_suppressions(path: &Path) -> Result<Vec<String>> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read suppressions file {}", path.display()))?;
+
+    let mut keys: Vec<String> = Vec::new();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        let key = line.split_whitespace().next().unwrap_or(line);
+        if !keys.iter().any(|existing| existing == key) {
+            keys.push(key.to_string());
+        }
+    }
+    Ok(keys)
+}
+
+/// Suppressed issue keys sorted by what should happen to their entries.
+#[derive(Debug, Default, PartialEq)]
+pub struct SuppressionCheck {
+    /// Issues still unresolved: the suppression is still needed.
+    pub still_open: Vec<String>,
+    /// Issues resolved or closed since, with their status: the entry can be removed.
+    pub resolved: Vec<(String, String)>,
+    /// Keys the server doesn't know (purged or mistyped): the entry can be removed.
+    pub missing: Vec<String>,
+}
+
+impl SuppressionCheck {
+    /// Classifies `keys` by the statuses the server returned for them.
+    pub fn classify(keys: &[String], statuses: &BTreeMap<String, String>) -> Self {
+        let mut check = Self::default();
+        for key in keys {
+            match statuses.get(key) {
+                Some(status) if UNRESOLVED_STATUSES.contains(&status.as_str()) => check.still_open.push(key.clone()),
+                Some(status) => check.resolved.push((key.clone(), status.clone())),
+                None => check.missing.push(key.clone()),
+            }
+        }
+        check
+    }
+
+    pub fn run(client: &SonarQubeClient, keys: &[String]) -> Result<Self> {
+        Ok(Self::classify(keys, &client.get_issue_statuses(keys)?))
+    }
+
+    /// Markdown maintenance report listing removable entries first.
+    pub fn render(&self, language: Language) -> String {
+        let mut report = format!("# {}\n\n", get_translation("suppressions_title", language.code()));
+
+        let removable: Vec<String> = self.resolved.iter()
+            .map(|(key, status)| format!("- `{}` ({})\n", key, status))
+            .chain(self.missing.iter().map(|key| format!("- `{}` ({})\n", key, get_translation("suppression_missing", language.code()))))
+            .collect();
+        report.push_str(&format!("## {} ({})\n\n", get_translation("suppressions_removable", language.code()), removable.len()));
+        if removable.is_empty() {
+            report.push_str(&format!("{}\n", get_translation("suppressions_none", language.code())));
+        }
+        report.push_str(&removable.concat());
+
+        report.push_str(&format!("\n## {} ({})\n\n", get_translation("suppressions_still_open", language.code()), self.still_open.len()));
+        if self.still_open.is_empty() {
+            report.push_str(&format!("{}\n", get_translation("suppressions_none", language.code())));
+        }
+        for key in &self.still_open {
+            report.push_str(&format!("- `{}`\n", key));
+        }
+
+        report
+    }
+}
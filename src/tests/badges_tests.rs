@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use crate::badges::{write_badges, EndpointBadge};
+    use crate::client::{IssueCounts, IssueData};
+    use crate::report::ProjectData;
+    use crate::theme::Theme;
+    use serde_json::{json, Value};
+    use std::fs;
+
+    fn create_counts_project(key: &str, by_severity: &[(&str, u64)]) -> ProjectData {
+        ProjectData {
+            project_key: key.to_string(),
+            issue_counts: Some(IssueCounts {
+                total: by_severity.iter().map(|(_, count)| count).sum(),
+                by_severity: by_severity.iter().map(|(severity, count)| (severity.to_string(), *count)).collect(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_badge_uses_worst_severity_color() {
+        let theme = Theme::parse("critical: {html: \"#123abc\"}").unwrap();
+        let project = create_counts_project("p", &[("BLOCKER", 0), ("CRITICAL", 2), ("MINOR", 3)]);
+
+        assert_eq!(EndpointBadge::for_project(&project, &theme), EndpointBadge {
+            schema_version: 1,
+            label: "sonarqube".to_string(),
+            message: "5 issues, worst critical".to_string(),
+            color: "123abc".to_string(),
+        });
+
+        let listed = ProjectData {
+            issues: vec![IssueData { severity: "INFO".to_string(), ..Default::default() }],
+            ..Default::default()
+        };
+        assert_eq!(EndpointBadge::for_project(&listed, &theme).message, "1 issue, worst info");
+    }
+
+    #[test]
+    fn test_write_badges_one_file_per_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let badges_dir = dir.path().join("badges");
+        let projects = vec![
+            create_counts_project("group:api", &[("BLOCKER", 1)]),
+            create_counts_project("web app", &[]),
+        ];
+
+        let paths = write_badges(&badges_dir, &projects, &Theme::default()).unwrap();
+
+        assert_eq!(paths, vec![badges_dir.join("group_api.json"), badges_dir.join("web_app.json")]);
+        assert_eq!(fs::read_dir(&badges_dir).unwrap().count(), 2);
+        let api: Value = serde_json::from_str(&fs::read_to_string(&paths[0]).unwrap()).unwrap();
+        assert_eq!(api, json!({"schemaVersion": 1, "label": "sonarqube", "message": "1 issue, worst blocker", "color": "b71c1c"}));
+        let web: Value = serde_json::from_str(&fs::read_to_string(&paths[1]).unwrap()).unwrap();
+        assert_eq!(web, json!({"schemaVersion": 1, "label": "sonarqube", "message": "no issues", "color": "brightgreen"}));
+    }
+
+    #[test]
+    fn test_write_badges_rejects_colliding_file_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let badges_dir = dir.path().join("badges");
+        let projects = vec![
+            create_counts_project("group:api", &[]),
+            create_counts_project("group_api", &[]),
+        ];
+
+        let error = write_badges(&badges_dir, &projects, &Theme::default()).unwrap_err();
+
+        assert!(error.to_string().contains("projects group:api and group_api would both write badge group_api.json"));
+        assert!(!badges_dir.exists());
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+    use crate::cache::{load_projects, save_projects};
+    use crate::client::{IssueCounts, IssueData, QualityGateStatus, Ratings, TrendPoint};
+    use crate::i18n::Language;
+    use crate::report::{render_report, OutputFormat, ProjectData, ReportOptions};
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    fn project() -> ProjectData {
+        ProjectData {
+            project_key: "my-project".to_string(),
+            last_analysis: Some("2024-01-15T10:30:00+0000".to_string()),
+            issues: vec![IssueData {
+                severity: "MAJOR".to_string(),
+                issue_type: Some("BUG".to_string()),
+                message: "Null check | missing".to_string(),
+                component: "my-project:src/Main.java".to_string(),
+                line: "42".to_string(),
+                key: Some("AX1".to_string()),
+                ..Default::default()
+            }],
+            issue_counts: Some(IssueCounts { total: 1, by_severity: [("MAJOR".to_string(), 1)].into_iter().collect() }),
+            trend: Some(vec![TrendPoint { date: "2024-01-15".to_string(), count: 1 }]),
+            quality_gate: Some(QualityGateStatus { status: "OK".to_string(), conditions: Vec::new() }),
+            ratings: Some(Ratings { security: Some(1.0), ..Default::default() }),
+            fetch_time: Some(Duration::from_millis(120)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cached_projects_render_the_same_report() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("cache.json");
+        let fetched = vec![project()];
+
+        save_projects(&path, &fetched).unwrap();
+        let cached = load_projects(&path).unwrap();
+
+        let options = ReportOptions { no_timestamp: true, ..Default::default() };
+        assert_eq!(
+            render_report(OutputFormat::Json, Language::EN, &options, &cached),
+            render_report(OutputFormat::Json, Language::EN, &options, &fetched)
+        );
+        // Timings describe the fetch, not the data, and aren't cached.
+        assert_eq!(cached[0].fetch_time, None);
+    }
+
+    #[test]
+    fn test_missing_cache_file_is_an_error() {
+        let dir = tempdir().unwrap();
+
+        let error = load_projects(&dir.path().join("cache.json")).unwrap_err();
+
+        assert!(error.to_string().contains("not found; run once without --offline"));
+    }
+
+    #[test]
+    fn test_malformed_cache_file_is_an_error() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("cache.json");
+        std::fs::write(&path, "{\"projects\": []}").unwrap();
+
+        let error = load_projects(&path).unwrap_err();
+
+        assert!(error.to_string().starts_with("Invalid cache file"));
+    }
+}
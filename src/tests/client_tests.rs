@@ -1,7 +1,10 @@
 #[cfg(test)]
 mod tests {
-    use crate::client::{SonarQubeClient, IssueData};
-    use mockito::{Mock, Server};
+    use crate::compat::ApiCompat;
+    use crate::client::{backoff_delay, batch_keys, parse_retry_after, MAX_RETRY_AFTER, AuthScheme, decode_project_key, is_path_key, last_days_start, parse_last_days, parse_directories, ClientOptions, IssueFilters, MinTlsVersion, SonarQubeClient, IssueData, TrendPoint, DirectoryCount, EffortSummary, PermissionDenied, Ratings, CODE_METRICS};
+    use chrono::NaiveDate;
+    use std::time::Duration;
+    use mockito::Server;
     use serde_json::json;
 
     #[test]
@@ -13,6 +16,35 @@ mod tests {
         assert!(client.is_ok());
     }
 
+    #[test]
+    fn test_sonarqube_client_creation_with_connection_tuning() {
+        let options = ClientOptions {
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            http2_prior_knowledge: true,
+            ..Default::default()
+        };
+        let client = SonarQubeClient::with_options(
+            "https://sonarqube.example.com".to_string(),
+            "test_token".to_string(),
+            options,
+        );
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn test_sonarqube_client_creation_with_min_tls_version() {
+        let options = ClientOptions {
+            min_tls_version: Some(MinTlsVersion::Tls1_2),
+            ..Default::default()
+        };
+        let client = SonarQubeClient::with_options(
+            "https://sonarqube.example.com".to_string(),
+            "test_token".to_string(),
+            options,
+        );
+        assert!(client.is_ok());
+    }
+
     #[test]
     fn test_base_url_trimming() {
         let client = SonarQubeClient::new(
@@ -21,7 +53,7 @@ mod tests {
         ).unwrap();
         
         // The base URL should have the trailing slash removed
-        // This is tested implicitly through successful API calls
+        assert_eq!(client.base_url(), "https://sonarqube.example.com");
     }
 
     #[test]
@@ -54,6 +86,7 @@ mod tests {
         let mut server = Server::new();
         let mock = server
             .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(json!({
@@ -73,6 +106,7 @@ mod tests {
         let mut server = Server::new();
         let mock = server
             .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
             .with_status(404)
             .create();
 
@@ -137,6 +171,7 @@ mod tests {
         let mut server = Server::new();
         let mock = server
             .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(json!({
@@ -176,6 +211,7 @@ mod tests {
         let mut server = Server::new();
         let mock = server
             .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(json!({
@@ -195,6 +231,7 @@ mod tests {
         let mut server = Server::new();
         let mock = server
             .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
             .with_status(500)
             .create();
 
@@ -212,6 +249,7 @@ mod tests {
             message: "Test message".to_string(),
             component: "test.java".to_string(),
             line: "42".to_string(),
+            ..Default::default()
         };
 
         let cloned = issue.clone();
@@ -220,4 +258,1631 @@ mod tests {
         assert_eq!(cloned.component, "test.java");
         assert_eq!(cloned.line, "42");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_get_issue_counts_new_code_period() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("componentKeys".to_string(), "test-project".to_string()),
+                mockito::Matcher::UrlEncoded("ps".to_string(), "1".to_string()),
+                mockito::Matcher::UrlEncoded("facets".to_string(), "severities".to_string()),
+                mockito::Matcher::UrlEncoded("inNewCodePeriod".to_string(), "true".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "paging": {"pageIndex": 1, "pageSize": 1, "total": 3},
+                "issues": [],
+                "facets": [{
+                    "property": "severities",
+                    "values": [
+                        {"val": "CRITICAL", "count": 1},
+                        {"val": "MAJOR", "count": 2}
+                    ]
+                }]
+            }).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let filters = IssueFilters { in_new_code_period: true, ..Default::default() };
+        let counts = client.get_issue_counts("test-project", &filters).unwrap().unwrap();
+
+        mock.assert();
+        assert_eq!(counts.total, 3);
+        assert_eq!(counts.by_severity.get("CRITICAL"), Some(&1));
+        assert_eq!(counts.by_severity.get("MAJOR"), Some(&2));
+    }
+
+    #[test]
+    fn test_get_issue_counts_unsupported_new_code_period() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(400)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let filters = IssueFilters { in_new_code_period: true, ..Default::default() };
+        let result = client.get_issue_counts("test-project", &filters).unwrap();
+
+        mock.assert();
+        assert_eq!(result, None);
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_search_issues_created_after_only() {
+        let mut server = Server::new();
+        let unexpected_mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Regex("createdBefore".to_string()))
+            .expect(0)
+            .create();
+        let mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("createdAfter".to_string(), "2024-01-01".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": []}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let filters = IssueFilters { created_after: Some(date("2024-01-01")), ..Default::default() };
+        client.search_issues("test-project", 10, &filters, false).unwrap();
+
+        mock.assert();
+        unexpected_mock.assert();
+    }
+
+    #[test]
+    fn test_search_issues_with_transitions() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("additionalFields".to_string(), "transitions,actions".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": [
+                {"key": "AX1", "severity": "MAJOR", "transitions": ["confirm", "resolve", "wontfix"], "actions": ["comment", "assign"]},
+                {"key": "AX2", "severity": "MINOR"}
+            ]}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let issues = client.search_issues("test-project", 10, &IssueFilters::default(), true).unwrap();
+
+        mock.assert();
+        assert_eq!(issues[0].transitions, ["confirm", "resolve", "wontfix"]);
+        assert!(issues[0].can_transition("wontfix"));
+        assert!(!issues[0].can_transition("reopen"));
+        assert!(issues[0].can_perform("assign"));
+        assert!(issues[1].transitions.is_empty() && !issues[1].can_perform("comment"));
+    }
+
+    #[test]
+    fn test_search_issues_without_transitions_by_default() {
+        let mut server = Server::new();
+        let unexpected_mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Regex("additionalFields".to_string()))
+            .expect(0)
+            .create();
+        let mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("componentKeys".to_string(), "test-project".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": [{"key": "AX1"}]}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let issues = client.search_issues("test-project", 10, &IssueFilters::default(), false).unwrap();
+
+        mock.assert();
+        unexpected_mock.assert();
+        assert!(issues[0].transitions.is_empty() && issues[0].actions.is_empty());
+    }
+
+    #[test]
+    fn test_search_issues_created_before_only() {
+        let mut server = Server::new();
+        let unexpected_mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Regex("createdAfter".to_string()))
+            .expect(0)
+            .create();
+        let mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("createdBefore".to_string(), "2024-02-01".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": []}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let filters = IssueFilters { created_before: Some(date("2024-02-01")), ..Default::default() };
+        client.search_issues("test-project", 10, &filters, false).unwrap();
+
+        mock.assert();
+        unexpected_mock.assert();
+    }
+
+    #[test]
+    fn test_search_issues_assigned_state() {
+        for (assigned, expected) in [(Some(true), "true"), (Some(false), "false")] {
+            let mut server = Server::new();
+            let mock = server
+                .mock("GET", "/api/issues/search")
+                .match_query(mockito::Matcher::UrlEncoded("assigned".to_string(), expected.to_string()))
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(json!({"issues": []}).to_string())
+                .create();
+
+            let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+            let filters = IssueFilters { assigned, ..Default::default() };
+            client.search_issues("test-project", 10, &filters, false).unwrap();
+
+            mock.assert();
+        }
+    }
+
+    #[test]
+    fn test_search_issues_without_assigned_state() {
+        let mut server = Server::new();
+        let unexpected_mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Regex("assigned=".to_string()))
+            .expect(0)
+            .create();
+        let mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("componentKeys".to_string(), "test-project".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": []}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        client.search_issues("test-project", 10, &IssueFilters::default(), false).unwrap();
+
+        mock.assert();
+        unexpected_mock.assert();
+    }
+
+    #[test]
+    fn test_last_days_start_relative_to_fixed_today() {
+        assert_eq!(last_days_start(date("2024-03-15"), 7), date("2024-03-08"));
+        assert_eq!(last_days_start(date("2024-03-01"), 1), date("2024-02-29"));
+        assert_eq!(last_days_start(date("2024-01-10"), 30), date("2023-12-11"));
+    }
+
+    #[test]
+    fn test_parse_last_days() {
+        assert_eq!(parse_last_days("14"), Ok(14));
+        assert!(parse_last_days("0").is_err());
+        assert!(parse_last_days("-3").is_err());
+        assert!(parse_last_days("week").is_err());
+    }
+
+    #[test]
+    fn test_search_issues_last_days_sends_created_after() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("createdAfter".to_string(), "2024-03-08".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": []}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let filters = IssueFilters { created_after: Some(last_days_start(date("2024-03-15"), 7)), ..Default::default() };
+        client.search_issues("test-project", 10, &filters, false).unwrap();
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_search_issues_created_window() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("createdAfter".to_string(), "2024-01-01".to_string()),
+                mockito::Matcher::UrlEncoded("createdBefore".to_string(), "2024-02-01".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": []}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let filters = IssueFilters {
+            created_after: Some(date("2024-01-01")),
+            created_before: Some(date("2024-02-01")),
+            ..Default::default()
+        };
+        client.search_issues("test-project", 10, &filters, false).unwrap();
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_issue_filters_validate_date_window() {
+        let valid = IssueFilters {
+            created_after: Some(date("2024-01-01")),
+            created_before: Some(date("2024-02-01")),
+            ..Default::default()
+        };
+        assert!(valid.validate("--since").is_ok());
+
+        let inverted = IssueFilters {
+            created_after: Some(date("2024-02-01")),
+            created_before: Some(date("2024-01-01")),
+            ..Default::default()
+        };
+        assert!(inverted.validate("--since").unwrap_err().to_string().contains("must be after --since (2024-02-01)"));
+        assert!(inverted.validate("--last-days").unwrap_err().to_string().contains("must be after --last-days (2024-02-01)"));
+    }
+
+    #[test]
+    fn test_get_latest_issues_captures_key_and_creation_date() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "issues": [{
+                    "key": "AYx1-abc",
+                    "creationDate": "2024-01-15T10:30:00+0000",
+                    "severity": "MAJOR"
+                }]
+            }).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let result = client.get_latest_issues("test-project", 10).unwrap();
+
+        assert_eq!(result[0].key, Some("AYx1-abc".to_string()));
+        assert_eq!(result[0].creation_date, Some("2024-01-15T10:30:00+0000".to_string()));
+    }
+
+    fn create_retrying_client(url: String, analysis_retries: u32, issues_retries: u32) -> SonarQubeClient {
+        let options = ClientOptions {
+            analysis_retries,
+            issues_retries,
+            retry_base_delay: Duration::from_millis(1),
+            retry_jitter: 0.0,
+            ..Default::default()
+        };
+        SonarQubeClient::with_options(url, "test_token".to_string(), options).unwrap()
+    }
+
+    #[test]
+    fn test_analysis_request_uses_analysis_retries() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(503)
+            .expect(4)
+            .create();
+
+        let client = create_retrying_client(server.url(), 3, 1);
+        let result = client.get_last_analysis_date("test-project").unwrap();
+
+        mock.assert();
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_issues_request_uses_issues_retries() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(502)
+            .expect(2)
+            .create();
+
+        let client = create_retrying_client(server.url(), 3, 1);
+        let result = client.get_latest_issues("test-project", 10).unwrap();
+
+        mock.assert();
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_retry_recovers_after_transient_failures() {
+        let mut server = Server::new();
+        let failing_mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(503)
+            .expect(2)
+            .create();
+        let success_mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": [{"severity": "MAJOR"}]}).to_string())
+            .expect(1)
+            .create();
+
+        let client = create_retrying_client(server.url(), 0, 2);
+        let result = client.get_latest_issues("test-project", 10).unwrap();
+
+        failing_mock.assert();
+        success_mock.assert();
+        assert_eq!(result.len(), 1);
+    }
+
+    #[test]
+    fn test_too_many_requests_waits_for_retry_after() {
+        let mut server = Server::new();
+        let throttled = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(429)
+            .with_header("retry-after", "0")
+            .expect(1)
+            .create();
+        let success = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": [{"severity": "MAJOR"}]}).to_string())
+            .expect(1)
+            .create();
+
+        // A base delay this long would time the test out if Retry-After were ignored.
+        let options = ClientOptions { issues_retries: 1, retry_base_delay: Duration::from_secs(600), ..Default::default() };
+        let client = SonarQubeClient::with_options(server.url(), "test_token".to_string(), options).unwrap();
+        let result = client.get_latest_issues("test-project", 10).unwrap();
+
+        throttled.assert();
+        success.assert();
+        assert_eq!(result.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = "2024-05-01T12:00:00Z".parse().unwrap();
+        assert_eq!(parse_retry_after("5", now), Some(Duration::from_secs(5)));
+        assert_eq!(parse_retry_after(" 0 ", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("Wed, 01 May 2024 12:00:30 GMT", now), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after("Wed, 01 May 2024 11:00:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("86400", now), Some(MAX_RETRY_AFTER));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_client_error_is_not_retried() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(400)
+            .expect(1)
+            .create();
+
+        let client = create_retrying_client(server.url(), 3, 3);
+        client.get_latest_issues("test-project", 10).unwrap();
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_get_server_version_plain_text() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/server/version")
+            .with_status(200)
+            .with_header("content-type", "text/plain")
+            .with_body("10.4.1.88267\n")
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let version = client.get_server_version().unwrap();
+
+        mock.assert();
+        assert_eq!(version, Some("10.4.1.88267".to_string()));
+    }
+
+    #[test]
+    fn test_get_server_version_unavailable() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/server/version")
+            .with_status(404)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        assert_eq!(client.get_server_version().unwrap(), None);
+    }
+
+    #[test]
+    fn test_backoff_delay_jitter_bounds() {
+        let base = Duration::from_millis(500);
+        for attempt in 0..4 {
+            let exponential = base * 2u32.pow(attempt);
+            let lowest = backoff_delay(base, attempt, 0.5, 0.0);
+            assert_eq!(lowest, exponential / 2);
+            for _ in 0..100 {
+                let delay = backoff_delay(base, attempt, 0.5, fastrand::f64());
+                assert!(delay >= exponential / 2 && delay < exponential * 3 / 2, "{:?} out of bounds", delay);
+            }
+        }
+    }
+
+    #[test]
+    fn test_backoff_delay_without_jitter() {
+        let base = Duration::from_millis(500);
+        assert_eq!(backoff_delay(base, 0, 0.0, 0.9), Duration::from_millis(500));
+        assert_eq!(backoff_delay(base, 2, 0.0, 0.1), Duration::from_millis(2000));
+    }
+
+    #[test]
+    fn test_get_current_user() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/users/current")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"isLoggedIn": true, "login": "jdoe", "name": "Jane Doe"}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+
+        assert_eq!(client.get_current_user().unwrap(), Some("jdoe".to_string()));
+        mock.assert();
+    }
+
+    #[test]
+    fn test_get_current_user_not_logged_in() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/users/current")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"isLoggedIn": false}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+
+        assert_eq!(client.get_current_user().unwrap(), None);
+    }
+
+    #[test]
+    fn test_search_issues_forwards_assignees() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("assignees".to_string(), "jdoe".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": []}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let filters = IssueFilters { assignees: Some("jdoe".to_string()), ..Default::default() };
+        client.search_issues("test-project", 10, &filters, false).unwrap();
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_same_host_redirect_keeps_authorization() {
+        let mut server = Server::new();
+        let redirect_mock = server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(301)
+            .with_header("location", "/sonar/api/project_analyses/search?project=test-project&ps=1")
+            .create();
+        let target_mock = server
+            .mock("GET", "/sonar/api/project_analyses/search")
+            .match_query(mockito::Matcher::UrlEncoded("project".to_string(), "test-project".to_string()))
+            .match_header("authorization", "Basic dGVzdF90b2tlbjo=")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"analyses": [{"date": "2024-01-15T10:30:00+0000"}]}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let result = client.get_last_analysis_date("test-project").unwrap();
+
+        redirect_mock.assert();
+        target_mock.assert();
+        assert_eq!(result, Some("2024-01-15T10:30:00+0000".to_string()));
+    }
+
+    #[test]
+    fn test_cross_host_redirect_is_reported() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(302)
+            .with_header("location", "https://other.example.com/api/project_analyses/search")
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let error = client.get_last_analysis_date("test-project").unwrap_err();
+
+        let message = format!("{:#}", error);
+        assert!(message.contains("redirected"), "{}", message);
+        assert!(message.contains("https://other.example.com/api/project_analyses/search"));
+        assert!(message.contains("--url"));
+    }
+
+    #[test]
+    fn test_get_portfolio_projects_uses_ref_keys_across_pages() {
+        let mut server = Server::new();
+        let first_page = server
+            .mock("GET", "/api/measures/component_tree")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("component".to_string(), "my-portfolio".to_string()),
+                mockito::Matcher::UrlEncoded("qualifiers".to_string(), "TRK".to_string()),
+                mockito::Matcher::UrlEncoded("p".to_string(), "1".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "paging": {"pageIndex": 1, "pageSize": 2, "total": 3},
+                "components": [
+                    {"key": "my-portfolio:backend", "refKey": "backend"},
+                    {"key": "frontend"}
+                ]
+            }).to_string())
+            .create();
+        let second_page = server
+            .mock("GET", "/api/measures/component_tree")
+            .match_query(mockito::Matcher::UrlEncoded("p".to_string(), "2".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "paging": {"pageIndex": 2, "pageSize": 2, "total": 3},
+                "components": [{"key": "my-portfolio:api", "refKey": "api"}]
+            }).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let keys = client.get_portfolio_projects("my-portfolio").unwrap();
+
+        first_page.assert();
+        second_page.assert();
+        assert_eq!(keys, vec!["api", "backend", "frontend"]);
+    }
+
+    #[test]
+    fn test_get_portfolio_projects_not_found_mentions_edition() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/measures/component_tree")
+            .match_query(mockito::Matcher::Any)
+            .with_status(404)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let error = client.get_portfolio_projects("my-portfolio").unwrap_err();
+
+        assert!(error.to_string().contains("Enterprise"));
+    }
+
+    #[test]
+    fn test_dump_raw_saves_unparsed_body_with_token_redacted() {
+        let mut server = Server::new();
+        let body = r#"{"analyses": [{"date": "2024-01-15T10:30:00+0000", "revision": "test_token"}], "extra": 1}"#;
+        server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .create();
+
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let options = ClientOptions {
+            dump_raw_dir: Some(temp_dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let client = SonarQubeClient::with_options(server.url(), "test_token".to_string(), options).unwrap();
+        let result = client.get_last_analysis_date("group:project").unwrap();
+
+        assert_eq!(result, Some("2024-01-15T10:30:00+0000".to_string()));
+        let dumped = std::fs::read_to_string(temp_dir.path().join("group_project.analysis.json")).unwrap();
+        assert_eq!(dumped, body.replace("test_token", "***"));
+    }
+
+    #[test]
+    fn test_get_issue_history_keeps_last_value_per_day() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/measures/search_history")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("component".to_string(), "test-project".to_string()),
+                mockito::Matcher::UrlEncoded("metrics".to_string(), "violations".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "paging": {"pageIndex": 1, "pageSize": 1000, "total": 4},
+                "measures": [{
+                    "metric": "violations",
+                    "history": [
+                        {"date": "2024-01-01T08:00:00+0000", "value": "12"},
+                        {"date": "2024-01-01T18:00:00+0000", "value": "10"},
+                        {"date": "2024-01-02T08:00:00+0000"},
+                        {"date": "2024-01-03T08:00:00+0000", "value": "7"}
+                    ]
+                }]
+            }).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let history = client.get_issue_history("test-project").unwrap();
+
+        assert_eq!(history, vec![
+            TrendPoint { date: "2024-01-01".to_string(), count: 10 },
+            TrendPoint { date: "2024-01-03".to_string(), count: 7 },
+        ]);
+    }
+
+    #[test]
+    fn test_missing_ca_bundle_fails_construction() {
+        let options = ClientOptions {
+            ca_bundle: Some("/nonexistent/ca-bundle.pem".into()),
+            ..Default::default()
+        };
+
+        let error = SonarQubeClient::with_options("https://sonarqube.example.com".to_string(), "test_token".to_string(), options)
+            .err()
+            .expect("construction should fail");
+
+        assert!(format!("{:#}", error).contains("Failed to read CA bundle /nonexistent/ca-bundle.pem"));
+    }
+
+    #[test]
+    fn test_get_directory_issue_counts_sorts_worst_first() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/measures/component_tree")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("component".to_string(), "test-project".to_string()),
+                mockito::Matcher::UrlEncoded("metricKeys".to_string(), "violations".to_string()),
+                mockito::Matcher::UrlEncoded("qualifiers".to_string(), "DIR".to_string()),
+                mockito::Matcher::UrlEncoded("p".to_string(), "1".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "paging": {"pageIndex": 1, "pageSize": 2, "total": 3},
+                "components": [
+                    {"key": "test-project:src/util", "path": "src/util", "measures": [{"metric": "violations", "value": "4"}]},
+                    {"key": "test-project:src/clean", "path": "src/clean", "measures": [{"metric": "violations", "value": "0"}]}
+                ]
+            }).to_string())
+            .create();
+        server
+            .mock("GET", "/api/measures/component_tree")
+            .match_query(mockito::Matcher::UrlEncoded("p".to_string(), "2".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "paging": {"pageIndex": 2, "pageSize": 2, "total": 3},
+                "components": [
+                    {"key": "test-project:src/core", "path": "src/core", "measures": [{"metric": "violations", "value": "9"}]}
+                ]
+            }).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let directories = client.get_directory_issue_counts("test-project").unwrap();
+
+        assert_eq!(directories, vec![
+            DirectoryCount { path: "src/core".to_string(), count: 9 },
+            DirectoryCount { path: "src/util".to_string(), count: 4 },
+        ]);
+    }
+
+    #[test]
+    fn test_get_effort_summary_reads_effort_facet() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("facetMode".to_string(), "effort".to_string()),
+                mockito::Matcher::UrlEncoded("facets".to_string(), "severities".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "total": 12,
+                "effortTotal": 630,
+                "issues": [],
+                "facets": [{"property": "severities", "values": [
+                    {"val": "CRITICAL", "count": 510},
+                    {"val": "MINOR", "count": 120}
+                ]}]
+            }).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let effort = client.get_effort_summary("test-project", &IssueFilters::default()).unwrap();
+
+        mock.assert();
+        assert_eq!(effort, Some(EffortSummary {
+            total_minutes: 630,
+            by_severity: [("CRITICAL".to_string(), 510), ("MINOR".to_string(), 120)].into_iter().collect(),
+        }));
+    }
+
+    #[test]
+    fn test_get_effort_summary_without_effort_total_is_unsupported() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"total": 12, "issues": [], "facets": []}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let effort = client.get_effort_summary("test-project", &IssueFilters::default()).unwrap();
+
+        assert_eq!(effort, None);
+    }
+
+    #[test]
+    fn test_search_issues_forwards_on_component_only_only_when_set() {
+        let mut server = Server::new();
+        let without_flag = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Regex("onComponentOnly".to_string()))
+            .expect(0)
+            .create();
+        let with_flag = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("onComponentOnly".to_string(), "true".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": []}).to_string())
+            .expect(1)
+            .create();
+        let any = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": []}).to_string())
+            .expect(1)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        client.search_issues("project:src/Main.java", 10, &IssueFilters::default(), false).unwrap();
+        let exact = IssueFilters { on_component_only: true, ..Default::default() };
+        client.search_issues("project:src/Main.java", 10, &exact, false).unwrap();
+
+        any.assert();
+        with_flag.assert();
+        without_flag.assert();
+    }
+
+    #[test]
+    fn test_search_issues_without_paging_returns_single_page() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "issues": [
+                    {"key": "AX1", "severity": "MAJOR", "message": "First", "component": "p:a.rs", "line": 1},
+                    {"key": "AX2", "severity": "MINOR", "message": "Second", "component": "p:b.rs"}
+                ]
+            }).to_string())
+            .expect(1)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let issues = client.search_issues("p", 10, &IssueFilters::default(), false).unwrap();
+
+        mock.assert();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[1].line, "N/A");
+    }
+
+    fn issues_page(first: usize, count: usize, total: usize) -> String {
+        let issues: Vec<serde_json::Value> = (first..first + count)
+            .map(|n| json!({"key": format!("AX{}", n), "severity": "MAJOR", "message": "Issue", "component": "p:a.rs"}))
+            .collect();
+        json!({"paging": {"pageIndex": first / 500 + 1, "pageSize": 500, "total": total}, "issues": issues}).to_string()
+    }
+
+    #[test]
+    fn test_search_issues_reads_pages_until_max_issues() {
+        let mut server = Server::new();
+        let pages: Vec<_> = (1..=4)
+            .map(|page| {
+                server
+                    .mock("GET", "/api/issues/search")
+                    .match_query(mockito::Matcher::AllOf(vec![
+                        mockito::Matcher::UrlEncoded("ps".to_string(), "500".to_string()),
+                        mockito::Matcher::UrlEncoded("p".to_string(), page.to_string()),
+                    ]))
+                    .with_status(200)
+                    .with_header("content-type", "application/json")
+                    .with_body(issues_page((page - 1) * 500, 500, 3000))
+                    .expect(1)
+                    .create()
+            })
+            .collect();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let issues = client.search_issues("p", 2000, &IssueFilters::default(), false).unwrap();
+
+        for page in pages {
+            page.assert();
+        }
+        assert_eq!(issues.len(), 2000);
+        assert_eq!(issues[0].key.as_deref(), Some("AX0"));
+        assert_eq!(issues[1999].key.as_deref(), Some("AX1999"));
+    }
+
+    #[test]
+    fn test_search_issues_stops_at_total() {
+        let mut server = Server::new();
+        let first = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("p".to_string(), "1".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(issues_page(0, 500, 700))
+            .expect(1)
+            .create();
+        let second = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("p".to_string(), "2".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(issues_page(500, 200, 700))
+            .expect(1)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let issues = client.search_issues("p", 2000, &IssueFilters::default(), false).unwrap();
+
+        first.assert();
+        second.assert();
+        assert_eq!(issues.len(), 700);
+    }
+
+    #[test]
+    fn test_search_issues_small_limit_makes_one_request() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("ps".to_string(), "50".to_string()),
+                mockito::Matcher::UrlEncoded("p".to_string(), "1".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(issues_page(0, 50, 3000))
+            .expect(1)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let issues = client.search_issues("p", 50, &IssueFilters::default(), false).unwrap();
+
+        mock.assert();
+        assert_eq!(issues.len(), 50);
+    }
+
+    #[test]
+    fn test_issue_counts_use_parameter_names_of_api_compat() {
+        let mut server = Server::new();
+        let legacy = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("statuses".to_string(), "OPEN,CONFIRMED".to_string()),
+                mockito::Matcher::UrlEncoded("sinceLeakPeriod".to_string(), "true".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"total": 2, "issues": [], "facets": []}).to_string())
+            .expect(1)
+            .create();
+        let current = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("issueStatuses".to_string(), "OPEN,CONFIRMED".to_string()),
+                mockito::Matcher::UrlEncoded("inNewCodePeriod".to_string(), "true".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"total": 3, "issues": [], "facets": []}).to_string())
+            .expect(1)
+            .create();
+
+        let filters = IssueFilters { in_new_code_period: true, ..Default::default() };
+        let mut client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        client.set_api_compat(ApiCompat::V10);
+        assert_eq!(client.get_issue_counts("p", &filters).unwrap().unwrap().total, 3);
+        client.set_api_compat(ApiCompat::V8);
+        assert_eq!(client.get_issue_counts("p", &filters).unwrap().unwrap().total, 2);
+
+        legacy.assert();
+        current.assert();
+    }
+
+    #[test]
+    fn test_accept_language_header_is_sent() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .match_header("accept-language", "ru")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"analyses": []}).to_string())
+            .create();
+
+        let options = ClientOptions { accept_language: Some("ru".to_string()), ..Default::default() };
+        let client = SonarQubeClient::with_options(server.url(), "test_token".to_string(), options).unwrap();
+        client.get_last_analysis_date("test-project").unwrap();
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_get_quality_gate_not_found_is_none() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/qualitygates/project_status")
+            .match_query(mockito::Matcher::UrlEncoded("projectKey".to_string(), "never-analyzed".to_string()))
+            .with_status(404)
+            .with_body(json!({"errors": [{"msg": "No analysis found"}]}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        assert_eq!(client.get_quality_gate_status("never-analyzed", None).unwrap(), None);
+        mock.assert();
+    }
+
+    #[test]
+    fn test_get_quality_gate_with_two_failing_conditions() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/qualitygates/project_status")
+            .match_query(mockito::Matcher::UrlEncoded("projectKey".to_string(), "test-project".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "projectStatus": {
+                    "status": "ERROR",
+                    "conditions": [
+                        {"status": "ERROR", "metricKey": "new_violations", "comparator": "GT", "errorThreshold": "0", "actualValue": "3"},
+                        {"status": "OK", "metricKey": "new_duplicated_lines_density", "comparator": "GT", "errorThreshold": "3", "actualValue": "0.5"},
+                        {"status": "ERROR", "metricKey": "new_coverage", "comparator": "LT", "errorThreshold": "80", "actualValue": "61.2"}
+                    ]
+                }
+            }).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let gate = client.get_quality_gate_status("test-project", None).unwrap().unwrap();
+
+        mock.assert();
+        assert_eq!(gate.status, "ERROR");
+        let failed: Vec<&str> = gate.failed_conditions().map(|c| c.metric_key.as_str()).collect();
+        assert_eq!(failed, vec!["new_violations", "new_coverage"]);
+        assert_eq!(gate.conditions[2].error_threshold.as_deref(), Some("80"));
+        assert_eq!(gate.conditions[2].actual_value.as_deref(), Some("61.2"));
+    }
+
+    #[test]
+    fn test_max_api_calls_halts_further_requests() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"analyses": []}).to_string())
+            .expect(2)
+            .create();
+
+        let options = ClientOptions { max_api_calls: Some(2), ..Default::default() };
+        let client = SonarQubeClient::with_options(server.url(), "test_token".to_string(), options).unwrap();
+        client.get_last_analysis_date("a").unwrap();
+        client.get_last_analysis_date("b").unwrap();
+        let error = client.get_last_analysis_date("c").unwrap_err();
+
+        mock.assert();
+        assert!(format!("{:#}", error).contains("Stopped after 2 API calls (--max-api-calls)"));
+        assert_eq!(client.api_calls(), 2);
+    }
+
+    #[test]
+    fn test_search_issues_forwards_directories_only_when_set() {
+        let mut server = Server::new();
+        let without_directories = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Regex("directories".to_string()))
+            .expect(0)
+            .create();
+        let with_directories = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("directories".to_string(), "src/main,src/lib".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": []}).to_string())
+            .expect(1)
+            .create();
+        let any = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": []}).to_string())
+            .expect(1)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        client.search_issues("test-project", 10, &IssueFilters::default(), false).unwrap();
+        let scoped = IssueFilters { directories: Some("src/main,src/lib".to_string()), ..Default::default() };
+        client.search_issues("test-project", 10, &scoped, false).unwrap();
+
+        any.assert();
+        with_directories.assert();
+        without_directories.assert();
+    }
+
+    #[test]
+    fn test_parse_directories_rejects_empty_entries() {
+        assert_eq!(parse_directories(" src/main , src/lib ").unwrap(), "src/main,src/lib");
+        assert!(parse_directories("src/main,,src/lib").is_err());
+        assert!(parse_directories("").is_err());
+    }
+
+    #[test]
+    fn test_keys_with_colons_and_slashes_are_encoded_once() {
+        let mut server = Server::new();
+        let analyses = server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Regex("^project=group%3Asubgroup%3Aproject%2Fsrc&".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"analyses": [{"date": "2024-01-15T10:30:00+0000"}]}).to_string())
+            .expect(1)
+            .create();
+        let issues = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex("componentKeys=group%3Asubgroup%3Aproject%2Fsrc(&|$)".to_string()),
+                mockito::Matcher::UrlEncoded("componentKeys".to_string(), "group:subgroup:project/src".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": []}).to_string())
+            .expect(1)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let key = decode_project_key("group%3Asubgroup%3Aproject%2Fsrc");
+        client.get_last_analysis_date(&key).unwrap();
+        client.search_issues(&key, 10, &IssueFilters::default(), false).unwrap();
+
+        analyses.assert();
+        issues.assert();
+    }
+
+    #[test]
+    fn test_decode_project_key() {
+        assert_eq!(decode_project_key("group:subgroup:project"), "group:subgroup:project");
+        assert_eq!(decode_project_key("group%3Aproject%2fsrc"), "group:project/src");
+        assert_eq!(decode_project_key("100%"), "100%");
+        assert_eq!(decode_project_key("bad%zzkey"), "bad%zzkey");
+        assert_eq!(decode_project_key("key%+1"), "key%+1");
+        assert_eq!(decode_project_key("%FF"), "%FF");
+    }
+
+    #[test]
+    fn test_is_path_key() {
+        assert!(is_path_key("my-project:src/main"));
+        assert!(is_path_key("group:project:src/lib.rs"));
+        assert!(!is_path_key("group:subgroup:project"));
+        assert!(!is_path_key("my-project"));
+        assert!(!is_path_key("team/project"));
+    }
+
+    #[test]
+    fn test_search_issues_parses_text_range() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": [
+                {"severity": "MAJOR", "message": "m", "component": "p:A.java", "line": 42,
+                 "textRange": {"startLine": 42, "endLine": 47, "startOffset": 4, "endOffset": 5}},
+                {"severity": "MINOR", "message": "m", "component": "p:B.java", "line": 3}
+            ]}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let issues = client.get_latest_issues("p", 10).unwrap();
+
+        let range = issues[0].text_range.as_ref().unwrap();
+        assert_eq!((range.start_line, range.end_line, range.start_offset, range.end_offset), (42, 47, Some(4), Some(5)));
+        assert!(issues[1].text_range.is_none());
+    }
+
+    #[test]
+    fn test_search_issues_parses_issue_type() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": [
+                {"severity": "MAJOR", "type": "VULNERABILITY", "message": "m", "component": "p:A.java"},
+                {"severity": "MINOR", "message": "m", "component": "p:B.java"}
+            ]}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let issues = client.get_latest_issues("p", 10).unwrap();
+
+        assert_eq!(issues[0].issue_type.as_deref(), Some("VULNERABILITY"));
+        assert_eq!(issues[1].issue_type, None);
+    }
+
+    #[test]
+    fn test_detect_api_prefix_at_root() {
+        let mut server = Server::new();
+        let status = server.mock("GET", "/api/system/status").with_status(200).with_body(r#"{"status":"UP"}"#).create();
+        let context_path = server.mock("GET", "/sonar/api/system/status").expect(0).create();
+
+        let mut client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+
+        assert_eq!(client.detect_api_prefix().unwrap(), Some(String::new()));
+        assert_eq!(client.api_prefix(), "");
+        status.assert();
+        context_path.assert();
+    }
+
+    #[test]
+    fn test_detect_api_prefix_below_context_path() {
+        let mut server = Server::new();
+        server.mock("GET", "/api/system/status").with_status(404).create();
+        server.mock("GET", "/sonar/api/system/status").with_status(200).with_body(r#"{"status":"UP"}"#).create();
+        let issues = server
+            .mock("GET", "/sonar/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": []}).to_string())
+            .create();
+
+        let mut client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+
+        assert_eq!(client.detect_api_prefix().unwrap(), Some("/sonar".to_string()));
+        assert!(client.get_latest_issues("p", 10).unwrap().is_empty());
+        issues.assert();
+    }
+
+    #[test]
+    fn test_detect_api_prefix_not_found() {
+        let mut server = Server::new();
+        server.mock("GET", "/api/system/status").with_status(404).create();
+        server.mock("GET", "/sonar/api/system/status").with_status(404).create();
+
+        let mut client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+
+        assert_eq!(client.detect_api_prefix().unwrap(), None);
+        assert_eq!(client.api_prefix(), "");
+    }
+
+    #[test]
+    fn test_get_quality_profiles_multiple_languages() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/qualityprofiles/search")
+            .match_query(mockito::Matcher::UrlEncoded("project".to_string(), "test-project".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"profiles": [
+                {"key": "p1", "name": "Strict", "language": "py", "languageName": "Python", "isDefault": false},
+                {"key": "p2", "name": "Sonar way", "language": "java", "languageName": "Java", "isDefault": true},
+                {"key": "p3", "name": "Sonar way", "language": "xml"}
+            ]}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let profiles = client.get_quality_profiles("test-project").unwrap().unwrap();
+
+        mock.assert();
+        let summary: Vec<(&str, &str, Option<&str>)> = profiles.iter()
+            .map(|profile| (profile.language.as_str(), profile.name.as_str(), profile.language_name.as_deref()))
+            .collect();
+        assert_eq!(summary, vec![
+            ("java", "Sonar way", Some("Java")),
+            ("py", "Strict", Some("Python")),
+            ("xml", "Sonar way", None),
+        ]);
+    }
+
+    #[test]
+    fn test_get_quality_profiles_forbidden() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/qualityprofiles/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(403)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let error = client.get_quality_profiles("test-project").unwrap_err();
+        assert_eq!(error.downcast_ref::<PermissionDenied>().unwrap().status, 403);
+    }
+
+    #[test]
+    fn test_get_ratings() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/measures/component")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("component".to_string(), "test-project".to_string()),
+                mockito::Matcher::UrlEncoded("metricKeys".to_string(), "security_rating,security_review_rating,reliability_rating".to_string()),
+                mockito::Matcher::UrlEncoded("branch".to_string(), "develop".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"component": {"key": "test-project", "measures": [
+                {"metric": "security_rating", "value": "1.0"},
+                {"metric": "reliability_rating", "value": "4.0"}
+            ]}}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let ratings = client.get_ratings("test-project", Some("develop")).unwrap().unwrap();
+
+        mock.assert();
+        assert_eq!(ratings, Ratings { security: Some(1.0), security_review: None, reliability: Some(4.0) });
+    }
+
+    #[test]
+    fn test_get_ratings_forbidden() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/measures/component")
+            .match_query(mockito::Matcher::Any)
+            .with_status(403)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let error = client.get_ratings("test-project", None).unwrap_err();
+        assert_eq!(error.downcast_ref::<PermissionDenied>().unwrap().status, 403);
+    }
+
+    #[test]
+    fn test_get_measures_leaves_out_missing_metrics() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/measures/component")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("component".to_string(), "test-project".to_string()),
+                mockito::Matcher::UrlEncoded("metricKeys".to_string(), "coverage,duplicated_lines_density".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"component": {"key": "test-project", "measures": [
+                {"metric": "duplicated_lines_density", "value": "2.1"}
+            ]}}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let measures = client.get_measures("test-project", None, &CODE_METRICS).unwrap();
+
+        mock.assert();
+        assert_eq!(measures, [("duplicated_lines_density".to_string(), "2.1".to_string())].into_iter().collect());
+    }
+
+    #[test]
+    fn test_get_measures_server_error_is_empty() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/measures/component")
+            .match_query(mockito::Matcher::Any)
+            .with_status(404)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        assert!(client.get_measures("missing-project", None, &CODE_METRICS).unwrap().is_empty());
+    }
+
+        #[test]
+    fn test_builder_sets_auth_organization_and_language() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/project_analyses/search")
+            .match_header("authorization", "Bearer squ_token")
+            .match_header("accept-language", "ru")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("project".to_string(), "test-project".to_string()),
+                mockito::Matcher::UrlEncoded("organization".to_string(), "my-org".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"analyses": [{"date": "2024-01-15T10:30:00+0000"}]}).to_string())
+            .create();
+
+        let client = SonarQubeClient::builder()
+            .url(server.url())
+            .token("squ_token")
+            .auth_scheme(AuthScheme::Bearer)
+            .organization("my-org")
+            .accept_language("ru")
+            .timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        let date = client.get_last_analysis_date("test-project").unwrap();
+
+        mock.assert();
+        assert_eq!(date.as_deref(), Some("2024-01-15T10:30:00+0000"));
+    }
+
+    #[test]
+    fn test_authorization_header_per_auth_scheme() {
+        // base64("squ_token:")
+        for (auth_scheme, header) in [(AuthScheme::Basic, "Basic c3F1X3Rva2VuOg=="), (AuthScheme::Bearer, "Bearer squ_token")] {
+            let mut server = Server::new();
+            let mock = server
+                .mock("GET", "/api/project_analyses/search")
+                .match_header("authorization", header)
+                .match_query(mockito::Matcher::Any)
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(json!({"analyses": []}).to_string())
+                .expect(1)
+                .create();
+
+            let options = ClientOptions { auth_scheme, ..Default::default() };
+            let client = SonarQubeClient::with_options(server.url(), "squ_token".to_string(), options).unwrap();
+            client.get_last_analysis_date("test-project").unwrap();
+
+            mock.assert();
+        }
+    }
+
+    #[test]
+    fn test_basic_auth_header_matches_standard_base64() {
+        // Values produced by the former base64::encode: standard alphabet with `+` and `/`,
+        // padded with `=`.
+        for (token, header) in [
+            ("squ_??>~", "Basic c3F1Xz8/Pn46"),
+            ("squ_a?>~?", "Basic c3F1X2E/Pn4/Og=="),
+            ("sq~~", "Basic c3F+fjo="),
+        ] {
+            let mut server = Server::new();
+            let mock = server
+                .mock("GET", "/api/project_analyses/search")
+                .match_header("authorization", header)
+                .match_query(mockito::Matcher::Any)
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(json!({"analyses": []}).to_string())
+                .expect(1)
+                .create();
+
+            let client = SonarQubeClient::new(server.url(), token.to_string()).unwrap();
+            client.get_last_analysis_date("test-project").unwrap();
+
+            mock.assert();
+        }
+    }
+
+    #[test]
+    fn test_timeout_is_configurable_and_zero_disables_it() {
+        let default = SonarQubeClient::new("http://localhost".to_string(), "t".to_string()).unwrap();
+        assert_eq!(default.timeout(), Some(Duration::from_secs(30)));
+
+        let options = ClientOptions { timeout: Duration::from_secs(120), ..Default::default() };
+        let configured = SonarQubeClient::with_options("http://localhost".to_string(), "t".to_string(), options).unwrap();
+        assert_eq!(configured.timeout(), Some(Duration::from_secs(120)));
+
+        let disabled = SonarQubeClient::builder()
+            .url("http://localhost")
+            .token("t")
+            .timeout(Duration::ZERO)
+            .build()
+            .unwrap();
+        assert_eq!(disabled.timeout(), None);
+    }
+
+    #[test]
+    fn test_request_fails_after_timeout() {
+        // Accepts connections but never answers.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let client = SonarQubeClient::builder()
+            .url(url)
+            .token("t")
+            .timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+        let error = client.get_last_analysis_date("p").unwrap_err();
+
+        let reqwest_error = error.chain().find_map(|cause| cause.downcast_ref::<reqwest::Error>()).unwrap();
+        assert!(reqwest_error.is_timeout());
+        drop(listener);
+    }
+
+    #[test]
+    fn test_builder_keeps_options_and_basic_auth() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/project_analyses/search")
+            // base64("squ_token:")
+            .match_header("authorization", "Basic c3F1X3Rva2VuOg==")
+            .match_query(mockito::Matcher::Any)
+            .with_status(503)
+            .expect(3)
+            .create();
+
+        let options = ClientOptions { analysis_retries: 2, retry_base_delay: Duration::from_millis(1), ..Default::default() };
+        let client = SonarQubeClient::builder()
+            .options(options)
+            .url(server.url())
+            .token("squ_token")
+            .build()
+            .unwrap();
+        client.get_last_analysis_date("test-project").unwrap();
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_builder_requires_url_and_token() {
+        let error = SonarQubeClient::builder().token("t").build().err().unwrap();
+        assert!(error.to_string().contains("needs a url"));
+        let error = SonarQubeClient::builder().url("http://localhost").build().err().unwrap();
+        assert!(error.to_string().contains("needs a token"));
+    }
+
+    fn numbered_keys(count: usize, prefix: &str) -> Vec<String> {
+        (0..count).map(|index| format!("{}{:03}", prefix, index)).collect()
+    }
+
+    #[test]
+    fn test_batch_keys_by_count() {
+        let keys = numbered_keys(250, "AX");
+        let sizes: Vec<usize> = batch_keys(&keys, 100, 10_000).iter().map(|batch| batch.len()).collect();
+        assert_eq!(sizes, [100, 100, 50]);
+        assert!(batch_keys(&[], 100, 10_000).is_empty());
+    }
+
+    #[test]
+    fn test_batch_keys_by_encoded_length() {
+        // `p:` encodes to `p%3A`: 4 + 3 digits = 7 characters, plus `%2C` between keys.
+        let keys = numbered_keys(10, "p:");
+        let batches = batch_keys(&keys, 100, 7 * 3 + 3 * 2);
+        let sizes: Vec<usize> = batches.iter().map(|batch| batch.len()).collect();
+        assert_eq!(sizes, [3, 3, 3, 1]);
+        assert_eq!(batches.concat(), keys);
+    }
+
+    #[test]
+    fn test_batch_keys_oversized_key_gets_own_batch() {
+        let keys = vec!["a".to_string(), "x".repeat(50), "b".to_string()];
+        let batches = batch_keys(&keys, 100, 10);
+        assert_eq!(batches, [&keys[0..1], &keys[1..2], &keys[2..3]]);
+    }
+
+    #[test]
+    fn test_get_issue_statuses_in_several_batches() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Regex("issues=".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": [{"key": "AX000", "status": "OPEN"}, {"key": "AX249", "status": "CLOSED"}]}).to_string())
+            .expect(3)
+            .create();
+
+        let options = ClientOptions { batch_size: 100, ..Default::default() };
+        let client = SonarQubeClient::with_options(server.url(), "test_token".to_string(), options).unwrap();
+        let statuses = client.get_issue_statuses(&numbered_keys(250, "AX")).unwrap();
+
+        mock.assert();
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(statuses["AX249"], "CLOSED");
+    }
+
+    /// A client for `target` reached as `localhost`, with `proxy` as its proxy.
+    fn client_via_proxy(target: &Server, proxy: &Server, no_proxy: &str) -> SonarQubeClient {
+        let options = ClientOptions {
+            proxy: Some(proxy.url()),
+            no_proxy: Some(no_proxy.to_string()),
+            ..Default::default()
+        };
+        let url = format!("http://localhost:{}", target.socket_address().port());
+        SonarQubeClient::with_options(url, "test_token".to_string(), options).unwrap()
+    }
+
+    #[test]
+    fn test_no_proxy_host_bypasses_proxy() {
+        let mut target = Server::new();
+        let mut proxy = Server::new();
+        let direct = target.mock("GET", "/api/server/version").with_body("10.4").create();
+        let proxied = proxy.mock("GET", "/api/server/version").with_body("9.9").expect(0).create();
+
+        let client = client_via_proxy(&target, &proxy, "other.example, localhost");
+
+        assert_eq!(client.get_server_version().unwrap(), Some("10.4".to_string()));
+        direct.assert();
+        proxied.assert();
+    }
+
+    #[test]
+    fn test_proxy_used_for_hosts_not_in_no_proxy() {
+        let mut target = Server::new();
+        let mut proxy = Server::new();
+        let direct = target.mock("GET", "/api/server/version").with_body("10.4").expect(0).create();
+        let proxied = proxy.mock("GET", "/api/server/version").with_body("9.9").create();
+
+        let client = client_via_proxy(&target, &proxy, "other.example");
+
+        assert_eq!(client.get_server_version().unwrap(), Some("9.9".to_string()));
+        direct.assert();
+        proxied.assert();
+    }
+
+    #[test]
+    fn test_retry_budget_shared_across_requests() {
+        let mut server = Server::new();
+        // 1 + 2 retries for the first request, then no retries are left for the next two.
+        let mock = server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(503)
+            .expect(5)
+            .create();
+
+        let options = ClientOptions {
+            analysis_retries: 3,
+            retry_base_delay: Duration::from_millis(1),
+            retry_jitter: 0.0,
+            retry_budget: Some(2),
+            ..Default::default()
+        };
+        let client = SonarQubeClient::with_options(server.url(), "test_token".to_string(), options).unwrap();
+        for project in ["a", "b", "c"] {
+            assert_eq!(client.get_last_analysis_date(project).unwrap(), None);
+        }
+
+        mock.assert();
+        assert_eq!(client.retries_used(), 2);
+        assert_eq!(client.api_calls(), 5);
+    }
+}
@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::{IssueData, SonarQubeClient};
+    use crate::comments::{comment_targets, post_comments, CommentOutcome, CommentTarget};
+    use crate::report::ProjectData;
+    use mockito::Server;
+
+    fn issue(key: Option<&str>, severity: &str, rule: &str) -> IssueData {
+        IssueData {
+            key: key.map(str::to_string),
+            severity: severity.to_string(),
+            rule: Some(rule.to_string()),
+            component: "p:src/Main.java".to_string(),
+            line: "4".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn projects() -> Vec<ProjectData> {
+        vec![ProjectData {
+            project_key: "p".to_string(),
+            issues: vec![
+                issue(Some("AX1"), "CRITICAL", "java:S2095"),
+                issue(Some("AX2"), "MINOR", "java:S2095"),
+                issue(Some("AX3"), "BLOCKER", "java:S1481"),
+                issue(None, "BLOCKER", "java:S2095"),
+            ],
+            ..Default::default()
+        }]
+    }
+
+    fn keys(targets: &[CommentTarget]) -> Vec<&str> {
+        targets.iter().map(|target| target.issue_key.as_str()).collect()
+    }
+
+    #[test]
+    fn test_comment_targets_filters_by_severity_and_rule() {
+        assert_eq!(keys(&comment_targets(&projects(), "INFO", &[])), ["AX1", "AX2", "AX3"]);
+        assert_eq!(keys(&comment_targets(&projects(), "MAJOR", &[])), ["AX1", "AX3"]);
+
+        let targets = comment_targets(&projects(), "MAJOR", &["java:S2095".to_string()]);
+        assert_eq!(keys(&targets), ["AX1"]);
+        assert_eq!(targets[0].description, "p (p:src/Main.java:4) CRITICAL java:S2095");
+    }
+
+    #[test]
+    fn test_post_comments_dry_run_posts_nothing() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("POST", "/api/issues/add_comment")
+            .expect(0)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let targets = comment_targets(&projects(), "INFO", &[]);
+
+        assert_eq!(post_comments(&client, &targets, "Tracked in JIRA-1", false), CommentOutcome::DryRun);
+        mock.assert();
+    }
+
+    #[test]
+    fn test_post_comments_sends_form_and_keeps_going_after_failures() {
+        let mut server = Server::new();
+        let ok_mock = server
+            .mock("POST", "/api/issues/add_comment")
+            .match_header("content-type", "application/x-www-form-urlencoded")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex("issue=AX(1|3)".to_string()),
+                mockito::Matcher::UrlEncoded("text".to_string(), "Tracked in JIRA-1 & co".to_string()),
+            ]))
+            .with_status(204)
+            .expect(2)
+            .create();
+        let failing_mock = server
+            .mock("POST", "/api/issues/add_comment")
+            .match_body(mockito::Matcher::UrlEncoded("issue".to_string(), "AX2".to_string()))
+            .with_status(403)
+            .expect(1)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let targets = comment_targets(&projects(), "INFO", &[]);
+        let outcome = post_comments(&client, &targets, "Tracked in JIRA-1 & co", true);
+
+        ok_mock.assert();
+        failing_mock.assert();
+        assert_eq!(outcome, CommentOutcome::Posted {
+            failures: vec!["p (p:src/Main.java:4) MINOR java:S2095 [AX2]: HTTP 403 Forbidden".to_string()],
+        });
+    }
+}
@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod tests {
+    use crate::compat::ApiCompat;
+
+    #[test]
+    fn test_for_server_version() {
+        assert_eq!(ApiCompat::for_server_version("8.9.10.61524"), Some(ApiCompat::V8));
+        assert_eq!(ApiCompat::for_server_version("9.3.0.51899"), Some(ApiCompat::V8));
+        assert_eq!(ApiCompat::for_server_version("9.9.4.87374"), Some(ApiCompat::V9));
+        assert_eq!(ApiCompat::for_server_version("10.3.0.82913"), Some(ApiCompat::V9));
+        assert_eq!(ApiCompat::for_server_version("10.4.1.88267"), Some(ApiCompat::V10));
+        assert_eq!(ApiCompat::for_server_version("2025.1.0.102418\n"), Some(ApiCompat::V10));
+        assert_eq!(ApiCompat::for_server_version("<html>"), None);
+    }
+
+    #[test]
+    fn test_parameter_names_per_level() {
+        assert_eq!(ApiCompat::V8.new_code_param(), "sinceLeakPeriod");
+        assert_eq!(ApiCompat::V9.new_code_param(), "inNewCodePeriod");
+        assert_eq!(ApiCompat::V10.new_code_param(), "inNewCodePeriod");
+        assert_eq!(ApiCompat::V8.status_param(), "statuses");
+        assert_eq!(ApiCompat::V9.status_param(), "statuses");
+        assert_eq!(ApiCompat::V10.status_param(), "issueStatuses");
+        assert_eq!(ApiCompat::default(), ApiCompat::V9);
+    }
+}
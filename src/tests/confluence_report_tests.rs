@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::{IssueCounts, IssueData};
+    use crate::i18n::Language;
+    use crate::report::{ConfluenceReportGenerator, ProjectData};
+
+    fn create_test_issue(severity: &str, message: &str, component: &str, line: &str) -> IssueData {
+        IssueData {
+            severity: severity.to_string(),
+            message: message.to_string(),
+            component: component.to_string(),
+            line: line.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_generate_issues_table_markup() {
+        let generator = ConfluenceReportGenerator::new(Language::EN);
+        let issues = vec![create_test_issue("CRITICAL", "NullPointer", "Main.java", "42")];
+
+        let table = generator.generate_issues_table(&issues);
+        assert_eq!(table, "||🔥 Severity||💬 Message||🧩 Component||📍 Line||\n|CRITICAL|NullPointer|Main.java|42|\n");
+    }
+
+    #[test]
+    fn test_escape_confluence_markup() {
+        assert_eq!(ConfluenceReportGenerator::escape("a | b {code} [x]"), "a \\| b \\{code\\} \\[x\\]");
+        assert_eq!(ConfluenceReportGenerator::escape("plain"), "plain");
+    }
+
+    #[test]
+    fn test_generate_issues_table_escapes_cells() {
+        let generator = ConfluenceReportGenerator::new(Language::EN);
+        let issues = vec![create_test_issue("MAJOR", "Use || or {noformat}\nhere", "Logic.java", "10")];
+
+        let table = generator.generate_issues_table(&issues);
+        assert!(table.contains("|MAJOR|Use \\|\\| or \\{noformat\\} here|Logic.java|10|\n"));
+    }
+
+    #[test]
+    fn test_generate_report_headings() {
+        let generator = ConfluenceReportGenerator::new(Language::EN);
+        let projects = vec![
+            ProjectData {
+                project_key: "project1".to_string(),
+                last_analysis: Some("2024-01-15T10:30:00+00:00".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        let report = generator.generate_report(&projects);
+        assert!(report.starts_with("h1. 📊 SonarQube Analysis Report\n"));
+        assert!(report.contains("h2. 📁 Project: project1\n"));
+        assert!(report.contains("*📅 Last Analysis:* 2024-01-15 10:30:00 UTC"));
+        assert!(report.contains("✅ No open issues found."));
+        assert!(!report.contains("## "));
+    }
+
+    #[test]
+    fn test_generate_report_counts_only_and_new_vs_overall() {
+        let generator = ConfluenceReportGenerator::new(Language::EN).with_new_vs_overall(true);
+        let projects = vec![ProjectData {
+            project_key: "counted".to_string(),
+            issue_counts: Some(IssueCounts {
+                total: 120,
+                by_severity: [("BLOCKER".to_string(), 20), ("MAJOR".to_string(), 100)].into_iter().collect(),
+            }),
+            new_code_counts: Some(IssueCounts { total: 4, ..Default::default() }),
+            counts_only: true,
+            ..Default::default()
+        }];
+
+        let report = generator.generate_report(&projects);
+        assert!(report.contains("*🆕 New code:* 4 / *📦 Overall:* 120\n"));
+        assert!(report.contains("*🔢 Open issues:* 120 (BLOCKER: 20, MAJOR: 100)\n"));
+        assert!(!report.contains("No open issues found"));
+    }
+
+    #[test]
+    fn test_footer_redacts_token() {
+        let generator = ConfluenceReportGenerator::new(Language::EN)
+            .with_footer(true, Some("https://secret_token@sonar.example.com/".to_string()));
+
+        let report = generator.generate_report(&[]);
+        assert!(report.ends_with(&format!("Generated by sonarqube_checker v{} against https://***@sonar.example.com\n", env!("CARGO_PKG_VERSION"))));
+        assert!(!report.contains("secret_token"));
+        assert!(!ConfluenceReportGenerator::new(Language::EN).generate_report(&[]).contains("Generated by"));
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::IssueData;
+    use crate::report::{render_report, CsvReportGenerator, OutputFormat, ProjectData, ReportOptions};
+    use crate::i18n::Language;
+
+    fn create_test_issue(severity: &str, message: &str, component: &str, line: &str) -> IssueData {
+        IssueData {
+            severity: severity.to_string(),
+            message: message.to_string(),
+            component: component.to_string(),
+            line: line.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_escape_quotes_only_when_needed() {
+        assert_eq!(CsvReportGenerator::escape("plain text"), "plain text");
+        assert_eq!(CsvReportGenerator::escape("a, b"), "\"a, b\"");
+        assert_eq!(CsvReportGenerator::escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(CsvReportGenerator::escape("two\nlines"), "\"two\nlines\"");
+        assert_eq!(CsvReportGenerator::escape(""), "");
+    }
+
+    #[test]
+    fn test_generate_report_one_row_per_issue() {
+        let projects = vec![
+            ProjectData {
+                project_key: "p1".to_string(),
+                issues: vec![
+                    create_test_issue("CRITICAL", "Remove \"this\", then\nthat", "p1:src/a.rs", "42"),
+                    create_test_issue("MINOR", "File-level", "p1:src/b.rs", "N/A"),
+                ],
+                ..Default::default()
+            },
+            ProjectData { project_key: "clean".to_string(), ..Default::default() },
+            ProjectData {
+                project_key: "p2".to_string(),
+                issues: vec![create_test_issue("MAJOR", "Simple", "p2:lib.rs", "7")],
+                ..Default::default()
+            },
+        ];
+
+        let report = CsvReportGenerator::new().generate_report(&projects);
+
+        assert_eq!(report, "project_key,severity,message,component,line\r\n\
+            p1,CRITICAL,\"Remove \"\"this\"\", then\nthat\",p1:src/a.rs,42\r\n\
+            p1,MINOR,File-level,p1:src/b.rs,\r\n\
+            p2,MAJOR,Simple,p2:lib.rs,7\r\n");
+    }
+
+    #[test]
+    fn test_csv_format_without_projects_is_header_only() {
+        assert_eq!(OutputFormat::from_extension("issues.CSV"), Some(OutputFormat::Csv));
+
+        let report = render_report(OutputFormat::Csv, Language::EN, &ReportOptions::default(), &[]);
+        assert_eq!(report, "project_key,severity,message,component,line\r\n");
+    }
+}
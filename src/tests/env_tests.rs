@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod tests {
+    use crate::env::{env_file_args, load_env_files};
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_env_file_args() {
+        let args = ["sonarqube_checker", "--env-file", ".env.shared", "--projects", "a", "--env-file=.env.local"]
+            .map(String::from);
+
+        assert_eq!(env_file_args(args), vec![PathBuf::from(".env.shared"), PathBuf::from(".env.local")]);
+    }
+
+    #[test]
+    fn test_load_env_files_later_file_overrides_earlier() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let shared = temp_dir.path().join(".env");
+        let local = temp_dir.path().join(".env.local");
+        fs::write(&shared, "ENV_TESTS_URL=https://shared.example.com\nENV_TESTS_PROJECTS=shared\nENV_TESTS_TOKEN=from_file\n").unwrap();
+        fs::write(&local, "ENV_TESTS_PROJECTS=local\nENV_TESTS_TOKEN=from_file\n").unwrap();
+        env::set_var("ENV_TESTS_TOKEN", "from_environment");
+
+        load_env_files(&[shared, local]).unwrap();
+
+        assert_eq!(env::var("ENV_TESTS_URL").unwrap(), "https://shared.example.com");
+        assert_eq!(env::var("ENV_TESTS_PROJECTS").unwrap(), "local");
+        assert_eq!(env::var("ENV_TESTS_TOKEN").unwrap(), "from_environment");
+    }
+
+    #[test]
+    fn test_load_env_files_missing_file_is_an_error() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let missing = temp_dir.path().join(".env.missing");
+
+        let err = load_env_files(&[missing]).unwrap_err();
+
+        assert!(err.to_string().contains("Error reading env file"));
+    }
+}
@@ -0,0 +1,419 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::{AuthenticationFailed, IssueFilters, SonarQubeClient};
+    use crate::fetch::{fetch_project, fetch_projects, FetchOptions};
+    use crate::i18n::Language;
+    use crate::report::MarkdownReportGenerator;
+    use crate::thresholds::Thresholds;
+    use mockito::Server;
+    use serde_json::json;
+
+    #[test]
+    fn test_fetch_project_with_issues() {
+        let mut server = Server::new();
+        let analyses_mock = server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"analyses": [{"date": "2024-01-15T10:30:00+0000"}]}).to_string())
+            .create();
+        let issues_mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("ps".to_string(), "10".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": [{"severity": "MAJOR", "message": "Fix me"}]}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let project = fetch_project(&client, "test-project", &FetchOptions::default()).unwrap();
+
+        analyses_mock.assert();
+        issues_mock.assert();
+        assert_eq!(project.project_key, "test-project");
+        assert_eq!(project.last_analysis, Some("2024-01-15T10:30:00+0000".to_string()));
+        assert_eq!(project.issues.len(), 1);
+        assert!(project.issue_counts.is_none() && !project.counts_only);
+    }
+
+    #[test]
+    fn test_fetch_project_with_transitions() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"analyses": []}).to_string())
+            .create();
+        let issues_mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("additionalFields".to_string(), "transitions,actions".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": [{"key": "AX1", "severity": "MAJOR", "transitions": ["confirm"]}]}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let options = FetchOptions { with_transitions: true, ..Default::default() };
+        let project = fetch_project(&client, "test-project", &options).unwrap();
+
+        issues_mock.assert();
+        assert!(project.issues[0].can_transition("confirm"));
+    }
+
+    #[test]
+    fn test_fetch_project_compares_with_the_whole_base_branch() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"analyses": [{"date": "2024-01-15T10:30:00+0000"}]}).to_string())
+            .create();
+        let issue = |message: String| json!({"rule": "r", "component": "c", "message": message, "severity": "MAJOR"});
+        server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("branch".to_string(), "feature".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "paging": {"pageIndex": 1, "pageSize": 500, "total": 2},
+                "issues": [issue("old 500".to_string()), issue("brand new".to_string())]
+            }).to_string())
+            .create();
+        let mut base_page = |page: &str, issues: Vec<serde_json::Value>| server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("branch".to_string(), "main".to_string()),
+                mockito::Matcher::UrlEncoded("p".to_string(), page.to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"paging": {"pageIndex": 1, "pageSize": 500, "total": 501}, "issues": issues}).to_string())
+            .expect(1)
+            .create();
+        // "old 500" is on the base branch's second page.
+        let first_page = base_page("1", (0..500).map(|i| issue(format!("old {}", i))).collect());
+        let second_page = base_page("2", vec![issue("old 500".to_string())]);
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let options = FetchOptions {
+            compare_branch: Some("main".to_string()),
+            filters: IssueFilters { branch: Some("feature".to_string()), ..Default::default() },
+            ..Default::default()
+        };
+        let project = fetch_project(&client, "test-project", &options).unwrap();
+
+        first_page.assert();
+        second_page.assert();
+        assert_eq!(project.compared_to.as_deref(), Some("main"));
+        assert_eq!(project.issues.len(), 1);
+        assert_eq!(project.issues[0].message, "brand new");
+    }
+
+    #[test]
+    fn test_fetch_project_zero_max_issues_uses_facets() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"analyses": []}).to_string())
+            .create();
+        let facets_mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("ps".to_string(), "1".to_string()),
+                mockito::Matcher::UrlEncoded("facets".to_string(), "severities".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "paging": {"pageIndex": 1, "pageSize": 1, "total": 4},
+                "issues": [{"severity": "BLOCKER", "message": "Not requested"}],
+                "facets": [{
+                    "property": "severities",
+                    "values": [{"val": "BLOCKER", "count": 1}, {"val": "MINOR", "count": 3}]
+                }]
+            }).to_string())
+            .expect(1)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let options = FetchOptions { max_issues: 0, ..Default::default() };
+        let project = fetch_project(&client, "test-project", &options).unwrap();
+
+        facets_mock.assert();
+        assert!(project.counts_only && project.issues.is_empty());
+        let counts = project.issue_counts.unwrap();
+        assert_eq!(counts.total, 4);
+        assert_eq!(counts.by_severity.get("MINOR"), Some(&3));
+    }
+
+    #[test]
+    fn test_fetch_project_ignore_rules_filters_issues_and_counts() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"analyses": []}).to_string())
+            .create();
+        server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("ps".to_string(), "500".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": [
+                {"key": "1", "severity": "BLOCKER", "message": "Noisy", "rule": "java:S1481"},
+                {"key": "2", "severity": "MAJOR", "message": "Real", "rule": "java:S2259"},
+                {"key": "3", "severity": "MINOR", "message": "Also noisy", "rule": "python:S125"}
+            ]}).to_string())
+            .create();
+        let counts = |total: u64, blocker: u64, major: u64, minor: u64| json!({
+            "paging": {"pageIndex": 1, "pageSize": 1, "total": total},
+            "issues": [],
+            "facets": [{"property": "severities", "values": [
+                {"val": "BLOCKER", "count": blocker}, {"val": "MAJOR", "count": major}, {"val": "MINOR", "count": minor}
+            ]}]
+        }).to_string();
+        // Registered first so the more specific mock below takes precedence (mockito matches newest first).
+        server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("facets".to_string(), "severities".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(counts(6, 1, 2, 3))
+            .create();
+        let ignored_mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("facets".to_string(), "severities".to_string()),
+                mockito::Matcher::UrlEncoded("rules".to_string(), "java:S1481,python:S125".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(counts(3, 1, 0, 2))
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let options = FetchOptions {
+            issue_counts: true,
+            ignore_rules: vec!["java:S1481".to_string(), "python:S125".to_string()],
+            ..Default::default()
+        };
+        let project = fetch_project(&client, "test-project", &options).unwrap();
+
+        ignored_mock.assert();
+        let messages: Vec<&str> = project.issues.iter().map(|issue| issue.message.as_str()).collect();
+        assert_eq!(messages, vec!["Real"]);
+        let counts = project.issue_counts.as_ref().unwrap();
+        assert_eq!(counts.total, 3);
+        assert_eq!(counts.by_severity.get("BLOCKER"), None);
+        assert_eq!(counts.by_severity.get("MINOR"), Some(&1));
+
+        let thresholds = Thresholds::parse("blocker=0").unwrap();
+        assert!(thresholds.breaches(&[project]).is_empty());
+    }
+
+    #[test]
+    fn test_fetch_project_ignore_rules_looks_past_the_first_page() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"analyses": []}).to_string())
+            .create();
+        // The two newest issues, all --max-issues would fetch, are of the ignored rule.
+        let issues_mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("ps".to_string(), "500".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "paging": {"pageIndex": 1, "pageSize": 500, "total": 5},
+                "issues": [
+                    {"key": "1", "message": "Noisy", "rule": "java:S1481", "creationDate": "2024-01-05T00:00:00+0000"},
+                    {"key": "2", "message": "Noisy", "rule": "java:S1481", "creationDate": "2024-01-04T00:00:00+0000"},
+                    {"key": "3", "message": "Real", "rule": "java:S2259", "creationDate": "2024-01-03T00:00:00+0000"},
+                    {"key": "4", "message": "Real", "rule": "java:S2259", "creationDate": "2024-01-02T00:00:00+0000"},
+                    {"key": "5", "message": "Real", "rule": "java:S2259", "creationDate": "2024-01-01T00:00:00+0000"}
+                ]
+            }).to_string())
+            .expect(1)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let options = FetchOptions { max_issues: 2, ignore_rules: vec!["java:S1481".to_string()], ..Default::default() };
+        let project = fetch_project(&client, "test-project", &options).unwrap();
+
+        issues_mock.assert();
+        let keys: Vec<&str> = project.issues.iter().filter_map(|issue| issue.key.as_deref()).collect();
+        assert_eq!(keys, ["3", "4"]);
+    }
+
+    /// A server whose quality gate endpoint answers 403 while the rest of the project works.
+    fn server_with_forbidden_gate() -> mockito::ServerGuard {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"analyses": [{"date": "2024-01-15T10:30:00+0000"}]}).to_string())
+            .create();
+        server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": [{"severity": "MAJOR", "message": "Fix me"}]}).to_string())
+            .create();
+        server
+            .mock("GET", "/api/qualitygates/project_status")
+            .match_query(mockito::Matcher::Any)
+            .with_status(403)
+            .create();
+        server
+    }
+
+    #[test]
+    fn test_fetch_project_forbidden_gate_is_noted() {
+        let server = server_with_forbidden_gate();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let options = FetchOptions { quality_gate: true, ..Default::default() };
+        let project = fetch_project(&client, "test-project", &options).unwrap();
+
+        assert!(project.quality_gate.is_none());
+        assert_eq!(project.denied_sections, ["quality_gate"]);
+        assert_eq!(project.issues.len(), 1);
+        let section = MarkdownReportGenerator::new(Language::EN).generate_project_section(&project);
+        assert!(section.contains("**🚦 Quality Gate:** _🔒 insufficient permissions_\n\n"));
+        assert!(section.contains("Fix me"));
+    }
+
+    #[test]
+    fn test_fetch_project_forbidden_gate_strict_fails() {
+        let server = server_with_forbidden_gate();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let options = FetchOptions { quality_gate: true, strict_permissions: true, ..Default::default() };
+        let error = fetch_project(&client, "test-project", &options).unwrap_err();
+
+        assert!(error.to_string().contains("Insufficient permissions to fetch quality gate for test-project: HTTP 403"));
+    }
+
+    #[test]
+    fn test_fetch_projects_keeps_order_and_isolates_failures() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::UrlEncoded("project".to_string(), "broken".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("{not json")
+            .create();
+        server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"analyses": [{"date": "2024-01-15T10:30:00+0000"}]}).to_string())
+            .create();
+        let issues_mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": [{"severity": "MAJOR", "message": "Fix me"}]}).to_string())
+            .expect(7)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let keys: Vec<String> = ["p1", "p2", "broken", "p3", "p4", "p5", "p6", "p7"].iter().map(|key| key.to_string()).collect();
+        let options = FetchOptions { timings: true, ..Default::default() };
+        let results = fetch_projects(&client, &keys, &options, 3);
+
+        issues_mock.assert();
+        assert_eq!(results.len(), keys.len());
+        for (key, result) in keys.iter().zip(&results) {
+            match result {
+                Ok(project) => {
+                    assert_eq!(&project.project_key, key);
+                    assert!(project.fetch_time.is_some());
+                }
+                Err(_) => assert_eq!(key, "broken"),
+            }
+        }
+        assert!(results[2].is_err());
+    }
+
+    /// A server that answers the analysis request normally and every issue request with `status`.
+    fn server_with_issue_status(status: usize) -> mockito::ServerGuard {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"analyses": [{"date": "2024-01-15T10:30:00+0000"}]}).to_string())
+            .create();
+        server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(status)
+            .create();
+        server
+    }
+
+    #[test]
+    fn test_fetch_project_rejected_token_fails() {
+        let server = server_with_issue_status(401);
+
+        let client = SonarQubeClient::new(server.url(), "expired".to_string()).unwrap();
+        let error = fetch_project(&client, "test-project", &FetchOptions::default()).unwrap_err();
+
+        let failure = error.downcast_ref::<AuthenticationFailed>().unwrap();
+        assert!(failure.is_invalid_token());
+        assert_eq!(
+            error.to_string(),
+            "Authentication failed for test-project: HTTP 401 Unauthorized; check that the token is valid and hasn't expired"
+        );
+    }
+
+    #[test]
+    fn test_fetch_project_forbidden_issues_fail_the_project() {
+        let server = server_with_issue_status(403);
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let error = fetch_project(&client, "test-project", &FetchOptions::default()).unwrap_err();
+
+        assert!(!error.downcast_ref::<AuthenticationFailed>().unwrap().is_invalid_token());
+        assert!(error.to_string().contains("HTTP 403 Forbidden; check that the token has Browse permission on the project"));
+    }
+
+    #[test]
+    fn test_fetch_project_other_client_errors_stay_soft() {
+        let server = server_with_issue_status(404);
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let project = fetch_project(&client, "test-project", &FetchOptions::default()).unwrap();
+
+        assert!(project.issues.is_empty());
+        assert_eq!(project.last_analysis.as_deref(), Some("2024-01-15T10:30:00+0000"));
+    }
+
+    #[test]
+    fn test_fetch_projects_empty_list() {
+        let client = SonarQubeClient::new("http://localhost:1".to_string(), "test_token".to_string()).unwrap();
+        assert!(fetch_projects(&client, &[], &FetchOptions::default(), 4).is_empty());
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::{IssueCounts, IssueData};
+    use crate::i18n::Language;
+    use crate::report::{GithubCommentGenerator, ProjectData, ReportOptions, GITHUB_COMMENT_MARKER};
+
+    fn create_test_issue(severity: &str, message: &str) -> IssueData {
+        IssueData {
+            severity: severity.to_string(),
+            message: message.to_string(),
+            component: "my-project:src/Main.java".to_string(),
+            line: "42".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn create_project(key: &str, issues: Vec<IssueData>) -> ProjectData {
+        ProjectData { project_key: key.to_string(), issues, ..Default::default() }
+    }
+
+    #[test]
+    fn test_comment_starts_with_marker_and_headline() {
+        let generator = GithubCommentGenerator::new(Language::EN, ReportOptions::default());
+        let projects = vec![
+            create_project("api", vec![create_test_issue("BLOCKER", "Leak"), create_test_issue("MAJOR", "Complex")]),
+            create_project("web", vec![create_test_issue("MAJOR", "Unused")]),
+        ];
+
+        let comment = generator.generate_report(&projects);
+        let mut lines = comment.lines();
+
+        assert_eq!(lines.next(), Some(GITHUB_COMMENT_MARKER));
+        assert_eq!(lines.next(), Some("### SonarQube: 3 open issues in 2 projects (1 BLOCKER, 2 MAJOR)"));
+        assert!(comment.contains("<details><summary><b>api</b> — 2 issues</summary>\n\n| "));
+        assert!(comment.contains("<details><summary><b>web</b> — 1 issue</summary>"));
+        assert_eq!(comment.matches("</details>").count(), 2);
+    }
+
+    #[test]
+    fn test_headline_prefers_issue_counts_and_reports_clean_runs() {
+        let generator = GithubCommentGenerator::new(Language::EN, ReportOptions::default());
+        let counted = ProjectData {
+            issue_counts: Some(IssueCounts {
+                total: 12,
+                by_severity: [("CRITICAL".to_string(), 2), ("MINOR".to_string(), 10)].into_iter().collect(),
+            }),
+            ..create_project("api", vec![create_test_issue("CRITICAL", "Leak")])
+        };
+
+        assert_eq!(generator.generate_headline(&[counted]), "### SonarQube: 12 open issues in 1 projects (2 CRITICAL, 10 MINOR)\n\n");
+        assert_eq!(generator.generate_headline(&[create_project("web", vec![])]), "### ✅ SonarQube: no open issues in 1 projects\n\n");
+    }
+
+    #[test]
+    fn test_comment_truncates_at_project_boundary() {
+        let projects: Vec<ProjectData> = (0..20)
+            .map(|i| create_project(&format!("project-{}", i), vec![create_test_issue("MAJOR", &"x".repeat(200))]))
+            .collect();
+        let generator = GithubCommentGenerator::new(Language::EN, ReportOptions::default()).with_max_chars(2000);
+
+        let comment = generator.generate_report(&projects);
+
+        assert!(comment.chars().count() <= 2000);
+        assert!(comment.starts_with(GITHUB_COMMENT_MARKER));
+        let shown = comment.matches("</details>").count();
+        assert!(shown > 0 && shown < 20);
+        assert!(comment.ends_with(&format!("_{} more projects not shown (comment size limit)_\n", 20 - shown)));
+    }
+}
@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::IssueData;
+    use crate::report::{GithubAnnotationGenerator, ProjectData};
+
+    fn create_test_issue(severity: &str, message: &str, component: &str, line: &str) -> IssueData {
+        IssueData {
+            severity: severity.to_string(),
+            message: message.to_string(),
+            component: component.to_string(),
+            line: line.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_annotation_level_mapping() {
+        assert_eq!(GithubAnnotationGenerator::annotation_level("BLOCKER"), "error");
+        assert_eq!(GithubAnnotationGenerator::annotation_level("CRITICAL"), "error");
+        assert_eq!(GithubAnnotationGenerator::annotation_level("MAJOR"), "warning");
+        assert_eq!(GithubAnnotationGenerator::annotation_level("MINOR"), "notice");
+        assert_eq!(GithubAnnotationGenerator::annotation_level("INFO"), "notice");
+        assert_eq!(GithubAnnotationGenerator::annotation_level("N/A"), "notice");
+    }
+
+    #[test]
+    fn test_generate_annotation_strips_repo_root() {
+        let generator = GithubAnnotationGenerator::new(Some("my-project:".to_string()));
+        let issue = create_test_issue("CRITICAL", "Null dereference", "my-project:src/Main.java", "42");
+
+        assert_eq!(
+            generator.generate_annotation(&issue),
+            "::error file=src/Main.java,line=42::Null dereference\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_annotation_omits_missing_line() {
+        let generator = GithubAnnotationGenerator::new(None);
+        let issue = create_test_issue("MAJOR", "Module too complex", "src/lib.rs", "N/A");
+
+        assert_eq!(generator.generate_annotation(&issue), "::warning file=src/lib.rs::Module too complex\n");
+    }
+
+    #[test]
+    fn test_generate_annotation_escapes_command_syntax() {
+        let generator = GithubAnnotationGenerator::new(None);
+        let issue = create_test_issue("MINOR", "100% wrong\nsecond line", "proj:a,b.rs", "7");
+
+        assert_eq!(
+            generator.generate_annotation(&issue),
+            "::notice file=proj%3Aa%2Cb.rs,line=7::100%25 wrong%0Asecond line\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_report_all_projects() {
+        let generator = GithubAnnotationGenerator::new(None);
+        let projects = vec![
+            ProjectData {
+                project_key: "project1".to_string(),
+                issues: vec![create_test_issue("BLOCKER", "SQL injection", "Db.java", "87")],
+                ..Default::default()
+            },
+            ProjectData {
+                project_key: "project2".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(generator.generate_report(&projects), "::error file=Db.java,line=87::SQL injection\n");
+    }
+}
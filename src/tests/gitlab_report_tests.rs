@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::IssueData;
+    use crate::report::{GitlabCodeQualityGenerator, ProjectData};
+    use serde_json::{json, Value};
+
+    fn create_test_issue(severity: &str, message: &str, component: &str, line: &str) -> IssueData {
+        IssueData {
+            severity: severity.to_string(),
+            message: message.to_string(),
+            component: component.to_string(),
+            line: line.to_string(),
+            rule: Some("java:S2259".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_gitlab_severity_mapping() {
+        assert_eq!(GitlabCodeQualityGenerator::gitlab_severity("BLOCKER"), "blocker");
+        assert_eq!(GitlabCodeQualityGenerator::gitlab_severity("CRITICAL"), "critical");
+        assert_eq!(GitlabCodeQualityGenerator::gitlab_severity("MAJOR"), "major");
+        assert_eq!(GitlabCodeQualityGenerator::gitlab_severity("minor"), "minor");
+        assert_eq!(GitlabCodeQualityGenerator::gitlab_severity("INFO"), "info");
+        assert_eq!(GitlabCodeQualityGenerator::gitlab_severity("HIGH"), "critical");
+        assert_eq!(GitlabCodeQualityGenerator::gitlab_severity("N/A"), "info");
+    }
+
+    #[test]
+    fn test_fingerprint_is_stable_and_distinguishes_issues() {
+        let issue = create_test_issue("MAJOR", "Null dereference", "p:src/Main.java", "42");
+
+        // Pinned so a change of algorithm, which would make GitLab see every issue as new, fails here.
+        assert_eq!(GitlabCodeQualityGenerator::fingerprint(&issue), "a99da37d46719aa7");
+        assert_eq!(GitlabCodeQualityGenerator::fingerprint(&issue), GitlabCodeQualityGenerator::fingerprint(&issue.clone()));
+
+        let moved = create_test_issue("MAJOR", "Null dereference", "p:src/Main.java", "43");
+        let shifted = create_test_issue("MAJOR", "ull dereference", "p:src/Main.javaN", "42");
+        let reprioritized = IssueData { severity: "BLOCKER".to_string(), ..issue.clone() };
+        assert_ne!(GitlabCodeQualityGenerator::fingerprint(&issue), GitlabCodeQualityGenerator::fingerprint(&moved));
+        assert_ne!(GitlabCodeQualityGenerator::fingerprint(&issue), GitlabCodeQualityGenerator::fingerprint(&shifted));
+        assert_eq!(GitlabCodeQualityGenerator::fingerprint(&issue), GitlabCodeQualityGenerator::fingerprint(&reprioritized));
+    }
+
+    #[test]
+    fn test_generate_report_code_quality_schema() {
+        let generator = GitlabCodeQualityGenerator::new(Some("p:".to_string()));
+        let projects = vec![ProjectData {
+            project_key: "p".to_string(),
+            issues: vec![
+                create_test_issue("CRITICAL", "Null dereference", "p:src/Main.java", "42"),
+                IssueData { rule: None, ..create_test_issue("INFO", "Module too complex", "p:src/lib.rs", "N/A") },
+            ],
+            ..Default::default()
+        }];
+
+        let report: Value = serde_json::from_str(&generator.generate_report(&projects)).unwrap();
+
+        assert_eq!(report[0], json!({
+            "description": "Null dereference",
+            "check_name": "java:S2259",
+            "fingerprint": GitlabCodeQualityGenerator::fingerprint(&projects[0].issues[0]),
+            "severity": "critical",
+            "location": {"path": "src/Main.java", "lines": {"begin": 42}}
+        }));
+        assert_eq!(report[1]["check_name"], "sonarqube");
+        assert_eq!(report[1]["location"], json!({"path": "src/lib.rs", "lines": {"begin": 1}}));
+        assert_eq!(generator.generate_report(&[]), "[]\n");
+    }
+}
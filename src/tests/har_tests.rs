@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::{ClientOptions, SonarQubeClient};
+    use crate::har::HarRecorder;
+    use reqwest::header::HeaderMap;
+    use mockito::Server;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_har_records_exchanges_with_authorization_redacted() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(301)
+            .with_header("location", "/sonar/api/project_analyses/search?project=test-project&ps=1")
+            .create();
+        server
+            .mock("GET", "/sonar/api/project_analyses/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"analyses": [{"date": "2024-01-15T10:30:00+0000", "revision": "test_token"}]}).to_string())
+            .create();
+
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let har_path = temp_dir.path().join("run.har");
+        let options = ClientOptions { har: Some(har_path.clone()), ..Default::default() };
+        let client = SonarQubeClient::with_options(server.url(), "test_token".to_string(), options).unwrap();
+        let result = client.get_last_analysis_date("test-project").unwrap();
+        // The file is written when the client (and its recorder) goes away.
+        assert!(!har_path.exists());
+        drop(client);
+
+        assert_eq!(result, Some("2024-01-15T10:30:00+0000".to_string()));
+        let har_text = std::fs::read_to_string(&har_path).unwrap();
+        assert!(!har_text.contains("test_token"));
+        assert!(!har_text.contains("dGVzdF90b2tlbjo="));
+
+        let har: Value = serde_json::from_str(&har_text).unwrap();
+        assert_eq!(har["log"]["version"], "1.2");
+        let entries = har["log"]["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+
+        let redirect = &entries[0];
+        assert_eq!(redirect["request"]["method"], "GET");
+        assert!(redirect["request"]["url"].as_str().unwrap().contains("/api/project_analyses/search?project=test-project"));
+        assert!(redirect["request"]["queryString"].as_array().unwrap().contains(&json!({"name": "project", "value": "test-project"})));
+        let authorization: Vec<&Value> = redirect["request"]["headers"].as_array().unwrap().iter()
+            .filter(|header| header["name"] == "authorization")
+            .collect();
+        assert_eq!(authorization, vec![&json!({"name": "authorization", "value": "***"})]);
+        assert_eq!(redirect["response"]["status"], 301);
+        assert_eq!(redirect["response"]["redirectURL"], "/sonar/api/project_analyses/search?project=test-project&ps=1");
+
+        let target = &entries[1];
+        assert_eq!(target["response"]["status"], 200);
+        assert_eq!(target["response"]["content"]["mimeType"], "application/json");
+        assert!(target["response"]["content"]["text"].as_str().unwrap().contains(r#""revision":"***""#));
+        assert!(target["time"].as_f64().unwrap() >= 0.0);
+        assert!(target["startedDateTime"].as_str().unwrap().ends_with('Z'));
+    }
+
+    #[test]
+    fn test_har_records_request_bodies_with_token_redacted() {
+        let mut server = Server::new();
+        server
+            .mock("POST", "/api/issues/add_comment")
+            .with_status(200)
+            .create();
+
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let har_path = temp_dir.path().join("run.har");
+        let options = ClientOptions { har: Some(har_path.clone()), ..Default::default() };
+        let client = SonarQubeClient::with_options(server.url(), "test_token".to_string(), options).unwrap();
+        client.add_issue_comment("AX1", "Seen by test_token's owner").unwrap();
+        drop(client);
+
+        let har: Value = serde_json::from_str(&std::fs::read_to_string(&har_path).unwrap()).unwrap();
+        let request = &har["log"]["entries"][0]["request"];
+        assert_eq!(request["method"], "POST");
+        assert_eq!(request["postData"]["mimeType"], "application/x-www-form-urlencoded");
+        assert_eq!(request["postData"]["text"], "issue=AX1&text=Seen+by+***%27s+owner");
+        assert_eq!(request["bodySize"], "issue=AX1&text=Seen+by+test_token%27s+owner".len());
+    }
+
+    #[test]
+    fn test_har_keeps_the_response_url() {
+        let mut server = Server::new();
+        server.mock("GET", "/api/system/status").with_status(200).create();
+
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let recorder = HarRecorder::new(temp_dir.path().join("run.har"), &HeaderMap::new(), "");
+        let url = format!("{}/api/system/status", server.url());
+        let client = reqwest::blocking::Client::new();
+        let response = recorder.execute(&client, client.get(&url).build().unwrap()).unwrap();
+
+        assert_eq!(response.url().as_str(), url);
+        recorder.finish().unwrap();
+        assert!(temp_dir.path().join("run.har").exists());
+    }
+}
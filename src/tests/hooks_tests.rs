@@ -0,0 +1,54 @@
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+    use crate::client::IssueData;
+    use crate::hooks::run_issue_hook;
+    use crate::report::ProjectData;
+    use std::fs;
+
+    fn create_test_issue(severity: &str, message: &str, component: &str, line: &str) -> IssueData {
+        IssueData {
+            severity: severity.to_string(),
+            message: message.to_string(),
+            component: component.to_string(),
+            line: line.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn create_test_project() -> ProjectData {
+        ProjectData {
+            project_key: "project1".to_string(),
+            issues: vec![
+                create_test_issue("CRITICAL", "Don't \"quote\" me; $HOME", "src/Main.java", "42"),
+                create_test_issue("MINOR", "Rename this", "src/Utils.java", "N/A"),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_run_issue_hook_passes_env_vars_above_threshold() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let log_path = temp_dir.path().join("hook.log");
+        let command = format!(
+            "printf '%s|%s|%s|%s|%s\\n' \"$SQ_PROJECT\" \"$SQ_SEVERITY\" \"$SQ_MESSAGE\" \"$SQ_COMPONENT\" \"$SQ_LINE\" >> '{}'",
+            log_path.display()
+        );
+
+        let failures = run_issue_hook(&command, "MAJOR", &[create_test_project()]);
+
+        assert!(failures.is_empty());
+        let log = fs::read_to_string(&log_path).unwrap();
+        assert_eq!(log, "project1|CRITICAL|Don't \"quote\" me; $HOME|src/Main.java|42\n");
+    }
+
+    #[test]
+    fn test_run_issue_hook_collects_failures() {
+        let failures = run_issue_hook("exit 3", "INFO", &[create_test_project()]);
+
+        assert_eq!(failures.len(), 2);
+        assert!(failures[0].starts_with("project1 (src/Main.java:42)"));
+        assert!(failures[1].starts_with("project1 (src/Utils.java:N/A)"));
+    }
+}
@@ -0,0 +1,167 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::{GateCondition, IssueCounts, IssueData, QualityGateStatus, Ratings};
+    use crate::i18n::Language;
+    use crate::report::{render_report, HtmlReportGenerator, OutputFormat, ProjectData, ReportOptions};
+    use crate::theme::Theme;
+
+    fn create_test_issue(severity: &str, message: &str, component: &str, line: &str) -> IssueData {
+        IssueData {
+            severity: severity.to_string(),
+            message: message.to_string(),
+            component: component.to_string(),
+            line: line.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_escape_html_special_characters() {
+        assert_eq!(
+            HtmlReportGenerator::escape(r#"<script>alert("x" & 'y')</script> | ok"#),
+            "&lt;script&gt;alert(&quot;x&quot; &amp; &#39;y&#39;)&lt;/script&gt; | ok"
+        );
+    }
+
+    #[test]
+    fn test_generate_report_is_standalone_document() {
+        let generator = HtmlReportGenerator::new(Language::EN).with_timestamp(false);
+        let projects = vec![ProjectData {
+            project_key: "my-project".to_string(),
+            last_analysis: Some("2024-01-15T10:30:00+0000".to_string()),
+            issues: vec![create_test_issue("CRITICAL", "Use <b> & | pipes", "my-project:src/<gen>.rs", "42")],
+            ..Default::default()
+        }];
+
+        let report = generator.generate_report(&projects);
+
+        assert!(report.starts_with("<!DOCTYPE html>\n<html lang=\"en\">\n"));
+        assert!(report.ends_with("</body>\n</html>\n"));
+        assert!(report.contains("<title>📊 SonarQube Analysis Report</title>"));
+        assert!(report.contains("<h2>📁 Project: my-project</h2>"));
+        assert!(report.contains("<td>N/A</td><td>Use &lt;b&gt; &amp; | pipes</td><td class=\"component\">my-project:src/&lt;gen&gt;.rs</td><td>42</td>"));
+        assert!(!report.contains("Generated"));
+        for external in ["<link", "<script", "src=", "url("] {
+            assert!(!report.contains(external), "found {:?}", external);
+        }
+    }
+
+    #[test]
+    fn test_generate_project_section_type_gate_ratings_and_measures() {
+        let generator = HtmlReportGenerator::new(Language::EN);
+        let project = ProjectData {
+            project_key: "p".to_string(),
+            issues: vec![IssueData { issue_type: Some("BUG".to_string()), ..create_test_issue("MAJOR", "m", "c", "1") }],
+            quality_gate: Some(QualityGateStatus {
+                status: "ERROR".to_string(),
+                conditions: vec![GateCondition {
+                    status: "ERROR".to_string(),
+                    metric_key: "new_coverage".to_string(),
+                    comparator: Some("LT".to_string()),
+                    error_threshold: Some("80".to_string()),
+                    actual_value: Some("61.5".to_string()),
+                }],
+            }),
+            ratings: Some(Ratings { security: Some(1.0), security_review: None, reliability: Some(3.0) }),
+            measures: Some([("coverage".to_string(), "81.3".to_string())].into_iter().collect()),
+            ..Default::default()
+        };
+
+        let section = generator.generate_project_section(&project);
+        assert!(section.contains("<th>🔥 Severity</th><th>"));
+        assert!(section.contains("</span></td><td>BUG</td><td>m</td>"));
+        assert!(section.contains("<p><strong>🚦 Quality Gate:</strong> ❌ Failed</p>"));
+        assert!(section.contains("<li><code>new_coverage</code>: 61.5 (fails when &lt; 80)</li>"));
+        assert!(section.contains("<strong>🏅 Ratings:</strong> "));
+        assert!(section.contains(": 🟢 A, "));
+        assert!(section.contains("<em>"));
+        assert!(section.contains("<p><strong>📐 Measures:</strong> Coverage: 81.3%, Duplication: N/A</p>"));
+    }
+
+    #[test]
+    fn test_severity_badges_use_theme_colors() {
+        let theme = Theme::parse("critical: {html: \"#000000\"}\nminor: {html: \"#ffffff\"}").unwrap();
+        let generator = HtmlReportGenerator::new(Language::EN).with_theme(theme);
+
+        assert_eq!(
+            generator.severity_badge("CRITICAL"),
+            "<span class=\"severity\" style=\"background:#000000;color:#ffffff\">CRITICAL</span>"
+        );
+        assert!(generator.severity_badge("MINOR").contains("background:#ffffff;color:#212121"));
+        assert!(generator.severity_badge("<weird>").contains("background:#9e9e9e;color:#212121\">&lt;weird&gt;</span>"));
+    }
+
+    #[test]
+    fn test_generate_project_section_counts_and_empty() {
+        let generator = HtmlReportGenerator::new(Language::RU);
+        let counted = ProjectData {
+            project_key: "counted".to_string(),
+            issue_counts: Some(IssueCounts {
+                total: 3,
+                by_severity: [("BLOCKER".to_string(), 1), ("MINOR".to_string(), 2)].into_iter().collect(),
+            }),
+            counts_only: true,
+            ..Default::default()
+        };
+        let clean = ProjectData { project_key: "clean".to_string(), ..Default::default() };
+
+        let section = generator.generate_project_section(&counted);
+        assert!(section.contains(":</strong> 3 <span class=\"severity\""));
+        assert!(section.contains(">BLOCKER</span> 1"));
+        assert!(!section.contains("<table>"));
+
+        let section = generator.generate_project_section(&clean);
+        assert!(!section.contains("<table>"));
+        assert!(section.contains("<p>"));
+    }
+
+    #[test]
+    fn test_shortened_components_keep_full_path_in_title() {
+        let generator = HtmlReportGenerator::new(Language::EN).with_shortened_components(Some(2));
+
+        let table = generator.generate_issues_table(&[
+            create_test_issue("MAJOR", "Long", "p:src/main/java/<foo>/Bar.java", "3"),
+            create_test_issue("MINOR", "Short", "p:Bar.java", "4"),
+        ]);
+
+        assert!(table.contains("<td class=\"component\" title=\"p:src/main/java/&lt;foo&gt;/Bar.java\">…/&lt;foo&gt;/Bar.java</td>"));
+        assert!(table.contains("<td class=\"component\">p:Bar.java</td>"));
+    }
+
+    #[test]
+    fn test_issue_links_wrap_components() {
+        let generator = HtmlReportGenerator::new(Language::EN)
+            .with_issue_links(Some("https://sonar.example.com".to_string()));
+        let issue = IssueData { key: Some("AX-1".to_string()), ..create_test_issue("MAJOR", "Linked", "app:Main.java", "3") };
+
+        let table = generator.generate_project_issues_table("app", &[issue]);
+
+        assert!(table.contains("<td class=\"component\"><a href=\"https://sonar.example.com/project/issues?id=app&amp;open=AX-1\">app:Main.java</a></td>"));
+    }
+
+    #[test]
+    fn test_footer_redacts_token() {
+        let generator = HtmlReportGenerator::new(Language::EN)
+            .with_footer(true, Some("https://secret_token@sonar.example.com/".to_string()));
+
+        let report = generator.generate_report(&[]);
+
+        let expected = format!(
+            "<footer class=\"muted\">Generated by sonarqube_checker v{} against https://***@sonar.example.com</footer>\n</body>",
+            env!("CARGO_PKG_VERSION")
+        );
+        assert!(report.contains(&expected));
+        assert!(!report.contains("secret_token"));
+        assert!(!HtmlReportGenerator::new(Language::EN).generate_report(&[]).contains("<footer"));
+    }
+
+    #[test]
+    fn test_html_format_from_extension_and_render() {
+        assert_eq!(OutputFormat::from_extension("report.html"), Some(OutputFormat::Html));
+        assert_eq!(OutputFormat::from_extension("REPORT.HTM"), Some(OutputFormat::Html));
+
+        let options = ReportOptions { no_timestamp: true, ..Default::default() };
+        let report = render_report(OutputFormat::Html, Language::EN, &options, &[]);
+        assert!(report.starts_with("<!DOCTYPE html>"));
+    }
+}
@@ -1,80 +1,80 @@
 #[cfg(test)]
 mod tests {
-    use crate::i18n::{get_translation, Language, TRANSLATIONS};
+    use crate::i18n::{get_translation, translate_with, Language, Translations, TRANSLATIONS};
 
     #[test]
     fn test_language_from_str() {
-        assert_eq!(Language::from_str("en"), Language::En);
-        assert_eq!(Language::from_str("EN"), Language::En);
-        assert_eq!(Language::from_str("ru"), Language::Ru);
-        assert_eq!(Language::from_str("RU"), Language::Ru);
-        assert_eq!(Language::from_str("unknown"), Language::En);
-        assert_eq!(Language::from_str(""), Language::En);
+        assert_eq!("en".parse(), Ok(Language::EN));
+        assert_eq!("EN".parse(), Ok(Language::EN));
+        assert_eq!("ru".parse(), Ok(Language::RU));
+        assert_eq!("RU".parse(), Ok(Language::RU));
+        assert_eq!("unknown".parse(), Ok(Language::EN));
+        assert_eq!("".parse(), Ok(Language::EN));
     }
 
     #[test]
     fn test_get_translation_english() {
-        assert_eq!(get_translation("report_title", &Language::En), "📊 SonarQube Analysis Report");
-        assert_eq!(get_translation("generated", &Language::En), "🕒 Generated");
-        assert_eq!(get_translation("project", &Language::En), "📁 Project");
-        assert_eq!(get_translation("last_analysis", &Language::En), "📅 Last Analysis");
-        assert_eq!(get_translation("latest_issues", &Language::En), "🚨 Latest Issues");
-        assert_eq!(get_translation("no_analysis_available", &Language::En), "⚠️ No analysis available");
-        assert_eq!(get_translation("no_open_issues", &Language::En), "✅ No open issues found.");
-        assert_eq!(get_translation("severity", &Language::En), "🔥 Severity");
-        assert_eq!(get_translation("message", &Language::En), "💬 Message");
-        assert_eq!(get_translation("component", &Language::En), "🧩 Component");
-        assert_eq!(get_translation("line", &Language::En), "📍 Line");
+        assert_eq!(get_translation("report_title", Language::EN.code()), "📊 SonarQube Analysis Report");
+        assert_eq!(get_translation("generated", Language::EN.code()), "🕒 Generated");
+        assert_eq!(get_translation("project", Language::EN.code()), "📁 Project");
+        assert_eq!(get_translation("last_analysis", Language::EN.code()), "📅 Last Analysis");
+        assert_eq!(get_translation("latest_issues", Language::EN.code()), "🚨 Latest Issues");
+        assert_eq!(get_translation("no_analysis_available", Language::EN.code()), "⚠️ No analysis available");
+        assert_eq!(get_translation("no_open_issues", Language::EN.code()), "✅ No open issues found.");
+        assert_eq!(get_translation("severity", Language::EN.code()), "🔥 Severity");
+        assert_eq!(get_translation("message", Language::EN.code()), "💬 Message");
+        assert_eq!(get_translation("component", Language::EN.code()), "🧩 Component");
+        assert_eq!(get_translation("line", Language::EN.code()), "📍 Line");
     }
 
     #[test]
     fn test_get_translation_russian() {
-        assert_eq!(get_translation("report_title", &Language::Ru), "📊 Отчёт анализа SonarQube");
-        assert_eq!(get_translation("generated", &Language::Ru), "🕒 Создано");
-        assert_eq!(get_translation("project", &Language::Ru), "📁 Проект");
-        assert_eq!(get_translation("last_analysis", &Language::Ru), "📅 Последний анализ");
-        assert_eq!(get_translation("latest_issues", &Language::Ru), "🚨 Последние проблемы");
-        assert_eq!(get_translation("no_analysis_available", &Language::Ru), "⚠️ Анализ недоступен");
-        assert_eq!(get_translation("no_open_issues", &Language::Ru), "✅ Открытых проблем не найдено.");
-        assert_eq!(get_translation("severity", &Language::Ru), "🔥 Важность");
-        assert_eq!(get_translation("message", &Language::Ru), "💬 Сообщение");
-        assert_eq!(get_translation("component", &Language::Ru), "🧩 Компонент");
-        assert_eq!(get_translation("line", &Language::Ru), "📍 Строка");
+        assert_eq!(get_translation("report_title", Language::RU.code()), "📊 Отчёт анализа SonarQube");
+        assert_eq!(get_translation("generated", Language::RU.code()), "🕒 Создано");
+        assert_eq!(get_translation("project", Language::RU.code()), "📁 Проект");
+        assert_eq!(get_translation("last_analysis", Language::RU.code()), "📅 Последний анализ");
+        assert_eq!(get_translation("latest_issues", Language::RU.code()), "🚨 Последние проблемы");
+        assert_eq!(get_translation("no_analysis_available", Language::RU.code()), "⚠️ Анализ недоступен");
+        assert_eq!(get_translation("no_open_issues", Language::RU.code()), "✅ Открытых проблем не найдено.");
+        assert_eq!(get_translation("severity", Language::RU.code()), "🔥 Важность");
+        assert_eq!(get_translation("message", Language::RU.code()), "💬 Сообщение");
+        assert_eq!(get_translation("component", Language::RU.code()), "🧩 Компонент");
+        assert_eq!(get_translation("line", Language::RU.code()), "📍 Строка");
     }
 
     #[test]
     fn test_get_translation_missing_key() {
         // When a key doesn't exist, it should return the key itself
-        assert_eq!(get_translation("non_existent_key", &Language::En), "non_existent_key");
-        assert_eq!(get_translation("non_existent_key", &Language::Ru), "non_existent_key");
+        assert_eq!(get_translation("non_existent_key", Language::EN.code()), "non_existent_key");
+        assert_eq!(get_translation("non_existent_key", Language::RU.code()), "non_existent_key");
     }
 
     #[test]
     fn test_translations_loaded() {
         // Verify that translations are loaded correctly
-        assert!(!TRANSLATIONS.en.is_empty());
-        assert!(!TRANSLATIONS.ru.is_empty());
+        assert!(!TRANSLATIONS.languages["en"].is_empty());
+        assert!(!TRANSLATIONS.languages["ru"].is_empty());
         
         // Verify essential keys exist
-        assert!(TRANSLATIONS.en.contains_key("report_title"));
-        assert!(TRANSLATIONS.ru.contains_key("report_title"));
+        assert!(TRANSLATIONS.languages["en"].contains_key("report_title"));
+        assert!(TRANSLATIONS.languages["ru"].contains_key("report_title"));
     }
 
     #[test]
     fn test_all_keys_in_both_languages() {
         // Ensure all keys in English also exist in Russian
-        for key in TRANSLATIONS.en.keys() {
+        for key in TRANSLATIONS.languages["en"].keys() {
             assert!(
-                TRANSLATIONS.ru.contains_key(key),
+                TRANSLATIONS.languages["ru"].contains_key(key),
                 "Key '{}' missing in Russian translations", 
                 key
             );
         }
 
         // Ensure all keys in Russian also exist in English
-        for key in TRANSLATIONS.ru.keys() {
+        for key in TRANSLATIONS.languages["ru"].keys() {
             assert!(
-                TRANSLATIONS.en.contains_key(key),
+                TRANSLATIONS.languages["en"].contains_key(key),
                 "Key '{}' missing in English translations", 
                 key
             );
@@ -83,15 +83,81 @@ mod tests {
 
     #[test]
     fn test_language_equality() {
-        assert_eq!(Language::En, Language::En);
-        assert_eq!(Language::Ru, Language::Ru);
-        assert_ne!(Language::En, Language::Ru);
+        assert_eq!(Language::EN, Language::EN);
+        assert_eq!(Language::RU, Language::RU);
+        assert_ne!(Language::EN, Language::RU);
     }
 
     #[test]
     fn test_language_copy() {
-        let lang1 = Language::En;
+        let lang1 = Language::EN;
         let lang2 = lang1;  // Copy
         assert_eq!(lang1, lang2);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_custom_translations_override_built_in() {
+        let custom = Translations::parse("en:\n  report_title: Weekly code health\nru:\n  project: Репозиторий\n").unwrap();
+        let layers = [&custom, &*TRANSLATIONS];
+
+        assert_eq!(translate_with(&layers, "report_title", Language::EN.code()), "Weekly code health");
+        assert_eq!(translate_with(&layers, "project", Language::RU.code()), "Репозиторий");
+        // Keys left out keep the built-in text of the same language before falling back to English.
+        assert_eq!(translate_with(&layers, "report_title", Language::RU.code()), "📊 Отчёт анализа SonarQube");
+        assert_eq!(translate_with(&layers, "line", Language::EN.code()), "📍 Line");
+        assert_eq!(translate_with(&layers, "no_such_key", Language::RU.code()), "no_such_key");
+    }
+
+    #[test]
+    fn test_custom_translations_may_leave_out_languages() {
+        let custom = Translations::parse("ru:\n  line: Стр.\n").unwrap();
+        assert!(!custom.languages.contains_key("en"));
+        assert_eq!(custom.translate("line", Language::RU.code()), Some("Стр."));
+        assert_eq!(custom.translate("line", Language::EN.code()), None);
+    }
+
+    #[test]
+    fn test_invalid_translations_are_rejected() {
+        assert!(Translations::parse("en: [not, a, map]").is_err());
+        assert!(Translations::parse("e n:\n  line: Line\n").unwrap_err().contains("'e n'"));
+        assert!(Translations::parse("1x:\n  line: Line\n").is_err());
+        assert!(Translations::parse("en:\n  line: [1, 2]\n").is_err());
+    }
+
+    #[test]
+    fn test_custom_language_codes_fall_back_to_english() {
+        let custom = Translations::parse("de:\n  project: Projekt\npt-BR:\n  line: Linha\n").unwrap();
+        let layers = [&custom, &*TRANSLATIONS];
+
+        assert_eq!(translate_with(&layers, "project", "de"), "Projekt");
+        assert_eq!(translate_with(&layers, "project", "DE"), "Projekt");
+        assert_eq!(translate_with(&layers, "line", "pt-br"), "Linha");
+        assert_eq!(translate_with(&layers, "line", "de"), "📍 Line");
+        assert_eq!(get_translation("line", "de"), "📍 Line");
+    }
+
+    #[test]
+    fn test_known_languages() {
+        assert_eq!(Language::known("RU"), Some(Language::RU));
+        assert_eq!(Language::known("en").map(|language| language.code()), Some("en"));
+        assert_eq!(Language::known("xx"), None);
+        let available = Language::available();
+        assert!(available.contains(&"en") && available.contains(&"ru"));
+        assert_eq!(Language::RU.to_string(), "ru");
+    }
+
+    #[test]
+    fn test_load_translations_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wording.yaml");
+        std::fs::write(&path, "en:\n  generated: Produced\n").unwrap();
+
+        let translations = Translations::load(&path).unwrap();
+        assert_eq!(translations.translate("generated", Language::EN.code()), Some("Produced"));
+
+        let missing = Translations::load(&dir.path().join("missing.yaml")).unwrap_err();
+        assert!(format!("{:#}", missing).contains("missing.yaml"));
+        std::fs::write(&path, "en: 3").unwrap();
+        assert!(format!("{:#}", Translations::load(&path).unwrap_err()).contains("Invalid translations file"));
+    }
+}
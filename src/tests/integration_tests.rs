@@ -8,7 +8,7 @@ mod tests {
     #[test]
     fn test_cli_help() {
         let output = Command::new("cargo")
-            .args(&["run", "--", "--help"])
+            .args(["run", "--", "--help"])
             .output()
             .expect("Failed to execute command");
 
@@ -23,7 +23,7 @@ mod tests {
     #[test]
     fn test_cli_missing_required_args() {
         let output = Command::new("cargo")
-            .args(&["run", "--"])
+            .args(["run", "--"])
             .env_remove("SONARQUBE_URL")
             .env_remove("SONARQUBE_TOKEN")
             .env_remove("SONARQUBE_PROJECTS")
@@ -40,7 +40,7 @@ mod tests {
         // This test would require a mock server or real SonarQube instance
         // For now, we'll just test that the CLI accepts environment variables
         let output = Command::new("cargo")
-            .args(&["run", "--"])
+            .args(["run", "--"])
             .env("SONARQUBE_URL", "https://example.com")
             .env("SONARQUBE_TOKEN", "fake_token")
             .env("SONARQUBE_PROJECTS", "test")
@@ -63,7 +63,7 @@ mod tests {
         // This would normally require a real SonarQube instance
         // For demonstration purposes, we'll just verify the CLI accepts the --output flag
         let output = Command::new("cargo")
-            .args(&["run", "--", 
+            .args(["run", "--", 
                 "--url", "https://example.com",
                 "--token", "fake_token",
                 "--projects", "test",
@@ -79,7 +79,7 @@ mod tests {
     fn test_language_selection() {
         // Test English
         let output = Command::new("cargo")
-            .args(&["run", "--", "--help"])
+            .args(["run", "--", "--help"])
             .env("SONARQUBE_REPORT_LANGUAGE", "en")
             .output()
             .expect("Failed to execute command");
@@ -89,7 +89,7 @@ mod tests {
 
         // Test Russian
         let output = Command::new("cargo")
-            .args(&["run", "--", "--help"])
+            .args(["run", "--", "--help"])
             .env("SONARQUBE_REPORT_LANGUAGE", "ru")
             .output()
             .expect("Failed to execute command");
@@ -101,7 +101,7 @@ mod tests {
     #[test]
     fn test_max_issues_parameter() {
         let output = Command::new("cargo")
-            .args(&["run", "--", 
+            .args(["run", "--", 
                 "--url", "https://example.com",
                 "--token", "fake_token",
                 "--projects", "test",
@@ -117,7 +117,7 @@ mod tests {
     #[test]
     fn test_multiple_projects() {
         let output = Command::new("cargo")
-            .args(&["run", "--",
+            .args(["run", "--",
                 "--url", "https://example.com",
                 "--token", "fake_token",
                 "--projects", "project1,project2,project3"])
@@ -140,7 +140,7 @@ mod tests {
             env::var("SONARQUBE_PROJECTS"),
         ) {
             let output = Command::new("cargo")
-                .args(&["run", "--",
+                .args(["run", "--",
                     "--url", &url,
                     "--token", &token,
                     "--projects", &projects])
@@ -166,7 +166,7 @@ SONARQUBE_PROJECTS=env_project
 "#).expect("Failed to write .env file");
 
         let output = Command::new("cargo")
-            .args(&["run", "--"])
+            .args(["run", "--"])
             .current_dir(temp_dir.path())
             .output()
             .expect("Failed to execute command");
@@ -177,4 +177,23 @@ SONARQUBE_PROJECTS=env_project
         assert!(!stderr.contains("--token is required"));
         assert!(!stderr.contains("--projects is required"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_cli_error_on_empty_project_list() {
+        let output = Command::new("cargo")
+            .args(["run", "--", "--url", "https://example.com", "--token", "fake_token", "--projects", " , ", "--error-on-empty"])
+            .output()
+            .expect("Failed to execute command");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success());
+        assert!(stderr.contains("no projects to report on"));
+
+        let output = Command::new("cargo")
+            .args(["run", "--", "--url", "https://example.com", "--token", "fake_token", "--projects", " , "])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+    }
+}
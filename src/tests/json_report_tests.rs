@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::{IssueCounts, IssueData};
+    use crate::report::{render_report, JsonReportGenerator, OutputFormat, ProjectData, ReportOptions};
+    use crate::i18n::Language;
+    use serde_json::Value;
+
+    fn projects() -> Vec<ProjectData> {
+        vec![ProjectData {
+            project_key: "project1".to_string(),
+            last_analysis: Some("2024-01-15T10:30:00+0000".to_string()),
+            issues: vec![IssueData {
+                severity: "MAJOR".to_string(),
+                message: "Split \"this\"\nmethod".to_string(),
+                component: "project1:src/Main.java".to_string(),
+                line: "N/A".to_string(),
+                ..Default::default()
+            }],
+            issue_counts: Some(IssueCounts { total: 1, ..Default::default() }),
+            ..Default::default()
+        }]
+    }
+
+    #[test]
+    fn test_json_report_is_pretty_by_default() {
+        let report = JsonReportGenerator::new().with_timestamp(false).generate_report(&projects());
+
+        assert!(report.starts_with("{\n  \"projects\": [\n"));
+        let parsed: Value = serde_json::from_str(&report).unwrap();
+        assert_eq!(parsed["projects"][0]["project_key"], "project1");
+        assert_eq!(parsed["projects"][0]["issues"][0]["line"], "N/A");
+        assert!(parsed.get("generated").is_none());
+    }
+
+    #[test]
+    fn test_json_compact_report_is_one_line_and_round_trips() {
+        let generator = JsonReportGenerator::new().with_timestamp(false);
+        let compact = generator.with_compact(true).generate_report(&projects());
+        let pretty = JsonReportGenerator::new().with_timestamp(false).generate_report(&projects());
+
+        let body = compact.strip_suffix('\n').unwrap();
+        assert!(!body.contains('\n'));
+        assert!(!body.contains("  "));
+        assert!(!body.contains(": "));
+        let compact_value: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(compact_value, serde_json::from_str::<Value>(&pretty).unwrap());
+        assert_eq!(compact_value["projects"][0]["issues"][0]["message"], "Split \"this\"\nmethod");
+    }
+
+    #[test]
+    fn test_render_report_json_includes_generated_time() {
+        let report = render_report(OutputFormat::JsonCompact, Language::EN, &ReportOptions::default(), &projects());
+
+        let parsed: Value = serde_json::from_str(&report).unwrap();
+        assert!(parsed["generated"].as_str().unwrap().ends_with('Z'));
+    }
+
+    #[test]
+    fn test_json_report_issues_round_trip() {
+        let mut data = projects();
+        data[0].issues.push(IssueData {
+            severity: "N/A".to_string(),
+            message: "N/A".to_string(),
+            component: "N/A".to_string(),
+            line: "N/A".to_string(),
+            ..Default::default()
+        });
+        let report = JsonReportGenerator::new().generate_report(&data);
+
+        let parsed: Value = serde_json::from_str(&report).unwrap();
+        let issues: Vec<IssueData> = serde_json::from_value(parsed["projects"][0]["issues"].clone()).unwrap();
+        assert_eq!(issues, data[0].issues);
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::{IssueCounts, IssueData, IssueFilters};
+    use crate::fetch::FetchOptions;
+    use crate::manifest::RunManifest;
+    use crate::report::ProjectData;
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use serde_json::Value;
+
+    #[test]
+    fn test_manifest_records_run_without_token() {
+        let projects = vec![ProjectData {
+            project_key: "project1".to_string(),
+            last_analysis: Some("2024-01-15T10:30:00+0000".to_string()),
+            issues: vec![IssueData::default(), IssueData::default()],
+            issue_counts: Some(IssueCounts { total: 7, ..Default::default() }),
+            ..Default::default()
+        }];
+        let options = FetchOptions {
+            ignore_rules: vec!["java:S1481".to_string()],
+            compare_branch: Some("main".to_string()),
+            filters: IssueFilters {
+                created_after: NaiveDate::from_ymd_opt(2024, 1, 1),
+                on_component_only: true,
+                branch: Some("feature".to_string()),
+                rules: Some("java:S2259".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let generated_at = Utc.with_ymd_and_hms(2024, 2, 1, 12, 0, 0).unwrap();
+
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("manifest.json");
+        RunManifest::new("https://secret_token@sonar.example.com/", &options, true, &projects, generated_at)
+            .write(&path)
+            .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("secret_token"));
+
+        let manifest: Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(manifest["tool_version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(manifest["server_url"], "https://***@sonar.example.com");
+        assert_eq!(manifest["generated_at"], "2024-02-01T12:00:00+00:00");
+        assert_eq!(manifest["filters"]["max_issues"], 10);
+        assert_eq!(manifest["filters"]["created_after"], "2024-01-01");
+        assert_eq!(manifest["filters"]["created_before"], Value::Null);
+        assert_eq!(manifest["filters"]["on_component_only"], true);
+        assert_eq!(manifest["filters"]["branch"], "feature");
+        assert_eq!(manifest["filters"]["rules"], "java:S2259");
+        assert_eq!(manifest["filters"]["ignore_rules"][0], "java:S1481");
+        assert_eq!(manifest["filters"]["compare_branch"], "main");
+        assert_eq!(manifest["filters"]["new_since_last_run"], true);
+        assert_eq!(manifest["projects"][0]["key"], "project1");
+        assert_eq!(manifest["projects"][0]["last_analysis"], "2024-01-15T10:30:00+0000");
+        assert_eq!(manifest["projects"][0]["issues_listed"], 2);
+        assert_eq!(manifest["projects"][0]["open_issues"], 7);
+    }
+}
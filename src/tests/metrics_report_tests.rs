@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::IssueCounts;
+    use crate::report::{MetricsReportGenerator, ProjectData};
+    use chrono::{TimeZone, Utc};
+    use serde_json::Value;
+    use std::collections::BTreeMap;
+
+    fn create_counts(by_severity: &[(&str, u64)]) -> IssueCounts {
+        IssueCounts {
+            total: by_severity.iter().map(|(_, count)| count).sum(),
+            by_severity: by_severity.iter().map(|(severity, count)| (severity.to_string(), *count)).collect::<BTreeMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_generate_report_emits_one_numeric_line_per_metric() {
+        let projects = vec![ProjectData {
+            project_key: "project1".to_string(),
+            issue_counts: Some(create_counts(&[("CRITICAL", 3), ("MAJOR", 2)])),
+            new_code_counts: Some(create_counts(&[("CRITICAL", 1)])),
+            ..Default::default()
+        }];
+        let timestamp = Utc.with_ymd_and_hms(2024, 1, 15, 10, 30, 0).unwrap();
+
+        let report = MetricsReportGenerator::new().generate_report_at(&projects, timestamp);
+        let lines: Vec<Value> = report.lines()
+            .map(|line| serde_json::from_str(line).expect("each line is JSON"))
+            .collect();
+
+        assert_eq!(lines.len(), 5);
+        for line in &lines {
+            assert_eq!(line["project"], "project1");
+            assert_eq!(line["ts"], 1705314600);
+            assert!(line["value"].is_u64());
+        }
+        assert_eq!(lines[0]["metric"], "issues");
+        assert!(lines[0].get("severity").is_none());
+        assert_eq!(lines[0]["value"], 5);
+        assert_eq!(lines[1]["severity"], "CRITICAL");
+        assert_eq!(lines[1]["value"], 3);
+        assert_eq!(lines[3]["metric"], "new_code_issues");
+        assert_eq!(lines[4]["value"], 1);
+    }
+
+    #[test]
+    fn test_generate_report_skips_projects_without_counts() {
+        let projects = vec![ProjectData {
+            project_key: "project1".to_string(),
+            ..Default::default()
+        }];
+
+        assert_eq!(MetricsReportGenerator::new().generate_report(&projects), "");
+    }
+}
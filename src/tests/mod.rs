@@ -1,4 +1,28 @@
+mod badges_tests;
+mod cache_tests;
 mod client_tests;
+mod comments_tests;
+mod compat_tests;
+mod env_tests;
+mod fetch_tests;
+mod har_tests;
+mod hooks_tests;
+mod manifest_tests;
 mod report_tests;
+mod github_report_tests;
+mod github_comment_tests;
+mod gitlab_report_tests;
+mod json_report_tests;
+mod confluence_report_tests;
+mod csv_report_tests;
+mod html_report_tests;
+mod plain_report_tests;
+mod metrics_report_tests;
 mod i18n_tests;
+mod severity_tests;
+mod state_tests;
+mod sink_tests;
+mod suppressions_tests;
+mod theme_tests;
+mod thresholds_tests;
 mod integration_tests;
\ No newline at end of file
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::{IssueCounts, IssueData};
+    use crate::i18n::Language;
+    use crate::report::{PlainReportGenerator, ProjectData};
+    use crate::theme::Theme;
+
+    fn create_test_issue(severity: &str, message: &str, component: &str, line: &str) -> IssueData {
+        IssueData {
+            severity: severity.to_string(),
+            message: message.to_string(),
+            component: component.to_string(),
+            line: line.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_generate_report_has_no_markdown_syntax() {
+        let generator = PlainReportGenerator::new(Language::EN).with_timestamp(false);
+        let projects = vec![ProjectData {
+            project_key: "my-project".to_string(),
+            last_analysis: Some("2024-01-15T10:30:00+0000".to_string()),
+            issues: vec![
+                create_test_issue("CRITICAL", "Null\ndereference", "my-project:src/Main.java", "42"),
+                create_test_issue("MAJOR", "Module too complex", "my-project:src/lib.rs", "N/A"),
+            ],
+            ..Default::default()
+        }];
+
+        let report = generator.generate_report(&projects);
+
+        assert_eq!(report, "SonarQube Analysis Report\n\
+            =========================\n\n\
+            Project: my-project\n\
+            ===================\n\n\
+            Last Analysis: 2024-01-15T10:30:00+0000\n\n\
+            Latest Issues:\n\
+            \x20 - CRITICAL  my-project:src/Main.java:42  Null dereference\n\
+            \x20 - MAJOR     my-project:src/lib.rs  Module too complex\n\n");
+        for markdown in ["|", "#", "**", "---"] {
+            assert!(!report.contains(markdown), "found {:?}", markdown);
+        }
+    }
+
+    #[test]
+    fn test_generate_project_section_counts_and_empty() {
+        let generator = PlainReportGenerator::new(Language::EN);
+        let counted = ProjectData {
+            project_key: "counted".to_string(),
+            issue_counts: Some(IssueCounts {
+                total: 3,
+                by_severity: [("BLOCKER".to_string(), 1), ("MINOR".to_string(), 2)].into_iter().collect(),
+            }),
+            counts_only: true,
+            ..Default::default()
+        };
+        let clean = ProjectData { project_key: "clean".to_string(), ..Default::default() };
+
+        assert!(generator.generate_project_section(&counted).contains("Open issues: 3 (BLOCKER: 1, MINOR: 2)\n"));
+        let section = generator.generate_project_section(&clean);
+        assert!(section.contains("Last Analysis: No analysis available\n"));
+        assert!(section.contains("Latest Issues:\n  No open issues found.\n"));
+    }
+
+    #[test]
+    fn test_issue_lines_colored_with_theme() {
+        let issues = vec![
+            create_test_issue("BLOCKER", "Null dereference", "a.rs", "3"),
+            create_test_issue("MINOR", "Unused import", "b.rs", "N/A"),
+        ];
+        let theme = Theme::parse("minor: {ansi: \"38;5;208\"}").unwrap();
+
+        let colored = PlainReportGenerator::new(Language::EN).with_colors(Some(theme)).generate_issue_lines(&issues);
+        assert_eq!(colored, "  - \x1b[1;31mBLOCKER\x1b[0m  a.rs:3  Null dereference\n\
+            \x20 - \x1b[38;5;208mMINOR  \x1b[0m  b.rs  Unused import\n");
+        let plain = PlainReportGenerator::new(Language::EN).generate_issue_lines(&issues);
+        assert!(!plain.contains('\x1b'));
+    }
+}
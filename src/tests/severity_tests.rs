@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod tests {
+    use crate::severity::{is_known_severity, meets_threshold, severity_rank, SEVERITIES, UNKNOWN_SEVERITY_RANK};
+
+    #[test]
+    fn test_severity_rank_legacy_severities() {
+        assert_eq!(severity_rank("INFO"), 0);
+        assert_eq!(severity_rank("MINOR"), 1);
+        assert_eq!(severity_rank("MAJOR"), 2);
+        assert_eq!(severity_rank("CRITICAL"), 3);
+        assert_eq!(severity_rank("BLOCKER"), 4);
+    }
+
+    #[test]
+    fn test_severity_rank_clean_code_severities() {
+        assert_eq!(severity_rank("LOW"), severity_rank("MINOR"));
+        assert_eq!(severity_rank("MEDIUM"), severity_rank("MAJOR"));
+        assert_eq!(severity_rank("HIGH"), severity_rank("CRITICAL"));
+    }
+
+    #[test]
+    fn test_severity_rank_is_case_insensitive() {
+        assert_eq!(severity_rank("blocker"), 4);
+        assert_eq!(severity_rank("Medium"), 2);
+    }
+
+    #[test]
+    fn test_severity_rank_unknown() {
+        assert_eq!(severity_rank(""), UNKNOWN_SEVERITY_RANK);
+        assert_eq!(severity_rank("N/A"), UNKNOWN_SEVERITY_RANK);
+        assert_eq!(severity_rank("SEVERE"), UNKNOWN_SEVERITY_RANK);
+        assert!(!is_known_severity("N/A"));
+    }
+
+    #[test]
+    fn test_severities_are_ordered_worst_first() {
+        let ranks: Vec<u8> = SEVERITIES.iter().map(|s| severity_rank(s)).collect();
+        assert_eq!(ranks, vec![4, 3, 2, 1, 0]);
+        assert!(SEVERITIES.iter().all(|s| is_known_severity(s)));
+    }
+
+    #[test]
+    fn test_meets_threshold() {
+        assert!(meets_threshold("BLOCKER", "MAJOR"));
+        assert!(meets_threshold("MAJOR", "MAJOR"));
+        assert!(meets_threshold("HIGH", "CRITICAL"));
+        assert!(!meets_threshold("MINOR", "MAJOR"));
+        assert!(!meets_threshold("N/A", "INFO"));
+        assert!(!meets_threshold("BLOCKER", "SEVERE"));
+    }
+}
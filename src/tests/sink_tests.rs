@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::IssueData;
+    use crate::i18n::Language;
+    use crate::report::{render_report, OutputFormat, ProjectData, ReportOptions};
+    use crate::sink::{bucket_and_key, encode_report, parse_encoding, sink_for, GithubSummarySink, OutputSink, GITHUB_STEP_SUMMARY};
+    use std::fs;
+
+    #[test]
+    fn test_sink_for_plain_path_writes_file() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("report.md");
+
+        let sink = sink_for(path.to_str().unwrap()).unwrap();
+        sink.write("# Report\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "# Report\n");
+        assert_eq!(sink.location(), path.display().to_string());
+    }
+
+    #[test]
+    fn test_bucket_and_key() {
+        assert_eq!(bucket_and_key("s3://reports/sonar/report.md").unwrap(), ("reports", "sonar/report.md"));
+        assert!(bucket_and_key("s3://reports").is_err());
+        assert!(bucket_and_key("gs:///report.md").is_err());
+    }
+
+    #[test]
+    fn test_sink_for_unknown_scheme() {
+        let error = sink_for("ftp://host/report.md").err().expect("scheme should be rejected");
+        assert!(error.to_string().contains("unsupported output URL scheme"));
+    }
+
+    #[cfg(not(feature = "s3"))]
+    #[test]
+    fn test_sink_for_s3_without_feature() {
+        let error = sink_for("s3://reports/report.md").err().expect("s3 should need the feature");
+        assert!(error.to_string().contains("--features s3"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_sink_streams_into_fifo() {
+        use std::io::Read;
+        use std::process::Command;
+
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let fifo = temp_dir.path().join("report.fifo");
+        let status = Command::new("mkfifo").arg(&fifo).status().expect("Failed to run mkfifo");
+        assert!(status.success());
+
+        let reader_path = fifo.clone();
+        let reader = std::thread::spawn(move || {
+            let mut contents = String::new();
+            fs::File::open(reader_path).unwrap().read_to_string(&mut contents).unwrap();
+            contents
+        });
+
+        sink_for(fifo.to_str().unwrap()).unwrap().write("# Report\n").unwrap();
+
+        assert_eq!(reader.join().unwrap(), "# Report\n");
+    }
+
+    #[test]
+    fn test_parse_encoding() {
+        assert_eq!(parse_encoding("cp1251").unwrap(), encoding_rs::WINDOWS_1251);
+        assert_eq!(parse_encoding("UTF-8").unwrap(), encoding_rs::UTF_8);
+        assert!(parse_encoding("klingon").unwrap_err().contains("unknown encoding"));
+        assert!(parse_encoding("utf-16le").is_err());
+    }
+
+    #[test]
+    fn test_russian_report_round_trips_through_windows_1251() {
+        let projects = vec![ProjectData {
+            project_key: "проект".to_string(),
+            issues: vec![IssueData {
+                severity: "MAJOR".to_string(),
+                message: "Удалите неиспользуемую переменную".to_string(),
+                component: "проект:src/main.rs".to_string(),
+                line: "7".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }];
+        let options = ReportOptions { no_timestamp: true, ..Default::default() };
+        let report = render_report(OutputFormat::Markdown, Language::RU, &options, &projects);
+
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("report.md");
+        let (bytes, replaced) = encode_report(&report, encoding_rs::WINDOWS_1251);
+        sink_for(path.to_str().unwrap()).unwrap().write_bytes(&bytes).unwrap();
+
+        let written = fs::read(&path).unwrap();
+        assert!(std::str::from_utf8(&written).is_err());
+        let (decoded, _, malformed) = encoding_rs::WINDOWS_1251.decode(&written);
+        assert!(!malformed);
+        assert!(decoded.contains("| MAJOR | N/A | Удалите неиспользуемую переменную | проект:src/main.rs | 7 |"));
+
+        // The report's emoji have no Windows-1251 code, e.g. 📊 (U+1F4CA) in the title.
+        assert!(replaced);
+        assert!(decoded.starts_with("# &#128202; Отчёт анализа SonarQube"));
+    }
+
+    // The only test touching $GITHUB_STEP_SUMMARY, so parallel tests can't race on it.
+    #[test]
+    fn test_github_summary_sink_appends_to_env_file() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("step_summary.md");
+        fs::write(&path, "# Build\n\n").unwrap();
+
+        std::env::set_var(GITHUB_STEP_SUMMARY, &path);
+        let sink = GithubSummarySink::from_env().unwrap();
+        sink.write("# Report 1\n").unwrap();
+        sink.write("# Report 2\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "# Build\n\n# Report 1\n# Report 2\n");
+        assert!(sink.location().contains("step_summary.md"));
+
+        std::env::remove_var(GITHUB_STEP_SUMMARY);
+        let error = GithubSummarySink::from_env().err().unwrap();
+        assert!(error.to_string().contains("--github-summary needs $GITHUB_STEP_SUMMARY"));
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::{IssueCounts, IssueData};
+    use crate::i18n::Language;
+    use crate::report::{MarkdownReportGenerator, ProjectData};
+    use crate::state::{truncated_projects, RunState};
+    use tempfile::tempdir;
+
+    fn issue(key: &str) -> IssueData {
+        IssueData { key: Some(key.to_string()), ..Default::default() }
+    }
+
+    fn keys(issues: &[IssueData]) -> Vec<&str> {
+        issues.iter().map(|issue| issue.key.as_deref().unwrap_or("-")).collect()
+    }
+
+    #[test]
+    fn test_first_run_reports_everything_and_seeds_state() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("state.json");
+
+        let mut state = RunState::load(&path).unwrap();
+        assert_eq!(state, RunState::default());
+        assert!(!state.has_project("p"));
+
+        let issues = vec![issue("AX1"), issue("AX2")];
+        assert_eq!(keys(&state.new_since("p", &issues)), ["AX1", "AX2"]);
+        state.record("p", &issues);
+        state.save(&path).unwrap();
+
+        let reloaded = RunState::load(&path).unwrap();
+        assert!(reloaded.has_project("p"));
+        assert_eq!(reloaded, state);
+    }
+
+    #[test]
+    fn test_next_run_reports_only_new_issues() {
+        let mut state = RunState::default();
+        state.record("p", &[issue("AX1"), issue("AX2")]);
+        state.record("other", &[issue("AX9")]);
+
+        let current = vec![issue("AX3"), issue("AX2"), IssueData::default(), issue("AX9")];
+        assert_eq!(keys(&state.new_since("p", &current)), ["AX3", "-", "AX9"]);
+
+        // The state rolls forward: resolved AX1 is forgotten, other projects are untouched.
+        state.record("p", &current);
+        assert_eq!(state.projects["p"].iter().collect::<Vec<_>>(), ["AX2", "AX3", "AX9"]);
+        assert_eq!(state.projects["other"].len(), 1);
+        assert!(state.new_since("p", &current).iter().all(|issue| issue.key.is_none()));
+    }
+
+    #[test]
+    fn test_load_rejects_invalid_state() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        std::fs::write(&path, "not json").unwrap();
+
+        let error = RunState::load(&path).unwrap_err();
+        assert!(error.to_string().contains("Invalid state file"));
+    }
+
+    #[test]
+    fn test_truncated_projects_are_found_by_their_counts() {
+        let project = |key: &str, listed: &[&str], total: Option<u64>| ProjectData {
+            project_key: key.to_string(),
+            issues: listed.iter().map(|key| issue(key)).collect(),
+            issue_counts: total.map(|total| IssueCounts { total, ..Default::default() }),
+            ..Default::default()
+        };
+        // With --max-issues 2, "capped" lists 2 of its 3 open issues.
+        let projects = vec![
+            project("capped", &["AX1", "AX2"], Some(3)),
+            project("complete", &["AX3", "AX4"], Some(2)),
+            project("empty", &[], Some(0)),
+            project("uncounted", &["AX5"], None),
+        ];
+        assert_eq!(truncated_projects(&projects), ["capped"]);
+    }
+
+    #[test]
+    fn test_empty_delta_renders_no_issues_rather_than_counts() {
+        let mut state = RunState::default();
+        let listed = vec![issue("AX1"), issue("AX2")];
+        state.record("p", &listed);
+
+        // --state-file fetches counts for the truncation check; a run with nothing new must
+        // still render the empty issue list, not the project's open issue counts.
+        let project = ProjectData {
+            project_key: "p".to_string(),
+            issues: state.new_since("p", &listed),
+            issue_counts: Some(IssueCounts { total: 2, ..Default::default() }),
+            ..Default::default()
+        };
+        let report = MarkdownReportGenerator::new(Language::EN).generate_report(&[project]);
+        assert!(report.contains("No open issues found."));
+        assert!(!report.contains("Open issues:**"));
+    }
+}
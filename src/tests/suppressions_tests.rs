@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::SonarQubeClient;
+    use crate::i18n::Language;
+    use crate::suppressions::{load_suppressions, SuppressionCheck};
+    use mockito::Server;
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn test_load_suppressions_skips_comments_and_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("suppressions.txt");
+        fs::write(&path, "# accepted risks\nAYx1  legacy parser, see #123\n\n  AYx2\nAYx1\n").unwrap();
+
+        assert_eq!(load_suppressions(&path).unwrap(), vec!["AYx1", "AYx2"]);
+        assert!(load_suppressions(&dir.path().join("missing.txt")).is_err());
+    }
+
+    #[test]
+    fn test_check_suppressions_mixed_keys() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::UrlEncoded("issues".to_string(), "open-1,gone-1,fixed-1".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"issues": [
+                {"key": "open-1", "status": "OPEN", "severity": "MAJOR"},
+                {"key": "fixed-1", "status": "CLOSED", "severity": "MINOR"}
+            ]}).to_string())
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        let keys = vec!["open-1".to_string(), "gone-1".to_string(), "fixed-1".to_string()];
+        let check = SuppressionCheck::run(&client, &keys).unwrap();
+
+        mock.assert();
+        assert_eq!(check, SuppressionCheck {
+            still_open: vec!["open-1".to_string()],
+            resolved: vec![("fixed-1".to_string(), "CLOSED".to_string())],
+            missing: vec!["gone-1".to_string()],
+        });
+
+        let report = check.render(Language::EN);
+        assert!(report.contains("## Can be removed (2)\n\n- `fixed-1` (CLOSED)\n- `gone-1` (not found)\n"));
+        assert!(report.contains("## Still open (1)\n\n- `open-1`\n"));
+    }
+
+    #[test]
+    fn test_check_suppressions_fails_instead_of_reporting_everything_missing() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/api/issues/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(403)
+            .create();
+
+        let client = SonarQubeClient::new(server.url(), "test_token".to_string()).unwrap();
+        assert!(SuppressionCheck::run(&client, &["AYx1".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod tests {
+    use crate::theme::{SeverityColor, Theme};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_theme_overrides_only_given_colors() {
+        let theme = Theme::parse("blocker:\n  html: \"#000000\"\nMinor: {ansi: \"38;5;208\"}\n").unwrap();
+        let defaults = Theme::default();
+
+        assert_eq!(theme.color("BLOCKER").unwrap().html, "#000000");
+        assert_eq!(theme.color("BLOCKER").unwrap().ansi, defaults.color("BLOCKER").unwrap().ansi);
+        assert_eq!(theme.color("minor").unwrap(), &SeverityColor {
+            html: defaults.color("MINOR").unwrap().html.clone(),
+            ansi: "38;5;208".to_string(),
+        });
+        assert_eq!(theme.color("MAJOR"), defaults.color("MAJOR"));
+        assert!(theme.color("UNKNOWN").is_none());
+    }
+
+    #[test]
+    fn test_theme_accepts_json() {
+        let theme = Theme::parse(r##"{"CRITICAL": {"html": "#f0a", "ansi": "35"}}"##).unwrap();
+
+        assert_eq!(theme.paint("CRITICAL", "x"), "\x1b[35mx\x1b[0m");
+        assert_eq!(theme.color("CRITICAL").unwrap().html, "#f0a");
+        assert_eq!(theme.paint("N/A", "x"), "x");
+    }
+
+    #[test]
+    fn test_malformed_themes_error_clearly() {
+        assert!(Theme::parse("urgent: {html: \"#fff\"}").unwrap_err().contains("unknown severity 'urgent'"));
+        assert!(Theme::parse("major: {html: red}").unwrap_err().contains("not a hex color"));
+        assert!(Theme::parse("major: {ansi: \"\\e[31m\"}").unwrap_err().contains("not an ANSI code"));
+        assert!(Theme::parse("major: {colour: \"#fff\"}").unwrap_err().contains("unknown field"));
+        assert!(Theme::parse("- blocker").is_err());
+    }
+
+    #[test]
+    fn test_load_theme_names_the_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("theme.yaml");
+        fs::write(&path, "info: {html: blue}").unwrap();
+
+        let error = Theme::load(&path).unwrap_err().to_string();
+        assert!(error.contains("theme.yaml") && error.contains("not a hex color"));
+        assert!(Theme::load(&dir.path().join("missing.yaml")).is_err());
+    }
+}
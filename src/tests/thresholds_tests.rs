@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests {
+    use crate::client::{IssueCounts, IssueData, QualityGateStatus};
+    use crate::report::ProjectData;
+    use crate::thresholds::{failed_gates, unchecked_gates, SeverityExitCodes, Thresholds, DEFAULT_SEVERITY_EXIT_CODES};
+
+    fn create_counts_project(key: &str, by_severity: &[(&str, u64)]) -> ProjectData {
+        ProjectData {
+            project_key: key.to_string(),
+            issue_counts: Some(IssueCounts {
+                total: by_severity.iter().map(|(_, count)| count).sum(),
+                by_severity: by_severity.iter().map(|(severity, count)| (severity.to_string(), *count)).collect(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_thresholds() {
+        let thresholds = Thresholds::parse("blocker=0, Critical=2").unwrap();
+        assert_eq!(thresholds, Thresholds::parse("CRITICAL=2,BLOCKER=0").unwrap());
+
+        assert!(Thresholds::parse("blocker").unwrap_err().contains("severity=max"));
+        assert!(Thresholds::parse("urgent=1").unwrap_err().contains("unknown severity"));
+        assert!(Thresholds::parse("major=-1").is_err());
+        assert!(Thresholds::parse("").is_err());
+    }
+
+    #[test]
+    fn test_thresholds_pass_within_limits() {
+        let thresholds = Thresholds::parse("blocker=0,critical=2").unwrap();
+        let projects = vec![
+            create_counts_project("a", &[("CRITICAL", 1), ("MAJOR", 40)]),
+            create_counts_project("b", &[("BLOCKER", 0), ("CRITICAL", 1)]),
+        ];
+
+        assert!(thresholds.breaches(&projects).is_empty());
+    }
+
+    #[test]
+    fn test_thresholds_report_every_breach_worst_first() {
+        let thresholds = Thresholds::parse("major=10,blocker=0,critical=2").unwrap();
+        let projects = vec![
+            create_counts_project("a", &[("CRITICAL", 2), ("MAJOR", 4)]),
+            create_counts_project("b", &[("BLOCKER", 1), ("CRITICAL", 1), ("MAJOR", 5)]),
+        ];
+
+        assert_eq!(thresholds.breaches(&projects), vec![
+            "BLOCKER: 1 open issue(s), at most 0 allowed",
+            "CRITICAL: 3 open issue(s), at most 2 allowed",
+        ]);
+    }
+
+    #[test]
+    fn test_thresholds_fall_back_to_listed_issues() {
+        let thresholds = Thresholds::parse("minor=1").unwrap();
+        let issue = IssueData { severity: "MINOR".to_string(), ..Default::default() };
+        let projects = vec![ProjectData {
+            project_key: "a".to_string(),
+            issues: vec![issue.clone(), issue],
+            ..Default::default()
+        }];
+
+        assert_eq!(thresholds.breaches(&projects), vec!["MINOR: 2 open issue(s), at most 1 allowed"]);
+    }
+
+    #[test]
+    fn test_parse_severity_exit_codes() {
+        assert_eq!(SeverityExitCodes::parse("Blocker=5, critical=4").unwrap(), SeverityExitCodes::parse("CRITICAL=4,BLOCKER=5").unwrap());
+
+        assert!(SeverityExitCodes::parse("blocker").unwrap_err().contains("severity=code"));
+        assert!(SeverityExitCodes::parse("urgent=1").unwrap_err().contains("unknown severity"));
+        assert!(SeverityExitCodes::parse("major=256").is_err());
+        assert!(SeverityExitCodes::parse("").is_err());
+    }
+
+    #[test]
+    fn test_severity_exit_code_follows_worst_severity() {
+        let exit_codes = SeverityExitCodes::parse(DEFAULT_SEVERITY_EXIT_CODES).unwrap();
+        let projects = vec![
+            create_counts_project("a", &[("BLOCKER", 0), ("MAJOR", 3)]),
+            create_counts_project("b", &[("CRITICAL", 1), ("INFO", 7)]),
+        ];
+
+        assert_eq!(exit_codes.exit_code(&projects), 4);
+        assert_eq!(exit_codes.exit_code(&projects[..1]), 3);
+        assert_eq!(exit_codes.exit_code(&[create_counts_project("c", &[("BLOCKER", 0)])]), 0);
+        assert_eq!(exit_codes.exit_code(&[]), 0);
+    }
+
+    #[test]
+    fn test_severity_exit_code_is_zero_for_unmapped_worst_severity() {
+        let exit_codes = SeverityExitCodes::parse("blocker=5,critical=4").unwrap();
+        let issue = IssueData { severity: "MAJOR".to_string(), ..Default::default() };
+        let projects = vec![ProjectData { project_key: "a".to_string(), issues: vec![issue], ..Default::default() }];
+
+        assert_eq!(exit_codes.exit_code(&projects), 0);
+    }
+
+    #[test]
+    fn test_failed_gates_lists_projects_in_error() {
+        let gate = |status: &str| Some(QualityGateStatus { status: status.to_string(), conditions: Vec::new() });
+        let projects = vec![
+            ProjectData { project_key: "failing".to_string(), quality_gate: gate("ERROR"), ..Default::default() },
+            ProjectData { project_key: "passing".to_string(), quality_gate: gate("OK"), ..Default::default() },
+            ProjectData { project_key: "no-gate".to_string(), quality_gate: gate("NONE"), ..Default::default() },
+            ProjectData { project_key: "not-fetched".to_string(), ..Default::default() },
+            ProjectData { project_key: "also-failing".to_string(), quality_gate: gate("ERROR"), ..Default::default() },
+        ];
+
+        assert_eq!(failed_gates(&projects), vec!["failing", "also-failing"]);
+        assert!(failed_gates(&projects[1..4]).is_empty());
+        assert_eq!(unchecked_gates(&projects), vec!["not-fetched"]);
+    }
+
+    #[test]
+    fn test_unchecked_gates_include_denied_gates() {
+        let denied = ProjectData {
+            project_key: "denied".to_string(),
+            denied_sections: vec!["quality_gate".to_string()],
+            ..Default::default()
+        };
+        let checked = ProjectData {
+            project_key: "checked".to_string(),
+            quality_gate: Some(QualityGateStatus { status: "OK".to_string(), conditions: Vec::new() }),
+            ..Default::default()
+        };
+        let projects = vec![denied, checked];
+
+        assert_eq!(unchecked_gates(&projects), vec!["denied"]);
+        assert!(failed_gates(&projects).is_empty());
+    }
+}
@@ -0,0 +1,104 @@
+//! Severity colors for colored output, overridable from a YAML or JSON theme file.
+
+use crate::severity::SEVERITIES;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Colors of one severity: a CSS hex color and ANSI SGR parameters (e.g. `1;31`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeverityColor {
+    pub html: String,
+    pub ansi: String,
+}
+
+/// One theme file entry; either color may be left out to keep its default.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeEntry {
+    html: Option<String>,
+    ansi: Option<String>,
+}
+
+/// Default colors, worst severity first, matching [`SEVERITIES`].
+const DEFAULT_COLORS: [(&str, &str); 5] = [
+    ("#b71c1c", "1;31"),
+    ("#e53935", "31"),
+    ("#fb8c00", "33"),
+    ("#fdd835", "93"),
+    ("#1e88e5", "34"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    colors: BTreeMap<String, SeverityColor>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let colors = SEVERITIES.iter().zip(DEFAULT_COLORS)
+            .map(|(severity, (html, ansi))| (severity.to_string(), SeverityColor { html: html.to_string(), ansi: ansi.to_string() }))
+            .collect();
+        Self { colors }
+    }
+}
+
+impl Theme {
+    /// Parses a mapping such as `blocker: {html: "#ff0000", ansi: "1;31"}`. Severities are
+    /// case-insensitive; severities and colors left out keep their defaults.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let entries: BTreeMap<String, ThemeEntry> = serde_yaml::from_str(text)
+            .map_err(|error| format!("expected a severity: {{html, ansi}} mapping: {}", error))?;
+
+        let mut theme = Self::default();
+        for (severity, entry) in entries {
+            let color = theme.colors.get_mut(&severity.to_uppercase())
+                .ok_or_else(|| format!("unknown severity '{}' (expected one of {})", severity, SEVERITIES.join(", ")))?;
+            if let Some(html) = entry.html {
+                if !is_hex_color(&html) {
+                    return Err(format!("'{}' for {} is not a hex color like #d32f2f", html, severity));
+                }
+                color.html = html;
+            }
+            if let Some(ansi) = entry.ansi {
+                if !is_sgr(&ansi) {
+                    return Err(format!("'{}' for {} is not an ANSI code like 1;31", ansi, severity));
+                }
+                color.ansi = ansi;
+            }
+        }
+        Ok(theme)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read theme {}", path.display()))?;
+        Self::parse(&text)
+            .map_err(|error| anyhow::anyhow!("Invalid theme {}: {}", path.display(), error))
+    }
+
+    /// Colors of a severity, case-insensitively; `None` for severities this tool doesn't know.
+    pub fn color(&self, severity: &str) -> Option<&SeverityColor> {
+        self.colors.get(&severity.to_uppercase())
+    }
+
+    /// Wraps `text` in the severity's ANSI color; unknown severities stay uncolored.
+    pub fn paint(&self, severity: &str, text: &str) -> String {
+        match self.color(severity) {
+            Some(color) => format!("\x1b[{}m{}\x1b[0m", color.ansi, text),
+            None => text.to_string(),
+        }
+    }
+}
+
+fn is_hex_color(value: &str) -> bool {
+    value.strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// SGR parameters: numbers separated by `;`, e.g. `1;38;5;208`.
+fn is_sgr(value: &str) -> bool {
+    value.split(';').all(|part| !part.is_empty() && part.len() <= 3 && part.chars().all(|c| c.is_ascii_digit()))
+}
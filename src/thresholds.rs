@@ -0,0 +1,127 @@
+use crate::report::{severity_counts, ProjectData};
+use crate::severity::{severity_rank, SEVERITIES};
+use std::collections::BTreeMap;
+
+/// Maximum allowed open issues per severity, e.g. from `--thresholds blocker=0,critical=2`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Thresholds {
+    limits: BTreeMap<String, u64>,
+}
+
+impl Thresholds {
+    /// Parses `severity=max` pairs separated by commas; severities are case-insensitive.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut limits = BTreeMap::new();
+
+        for pair in spec.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (severity, max) = pair.split_once('=')
+                .ok_or_else(|| format!("'{}' is not severity=max", pair))?;
+            let severity = severity.trim().to_uppercase();
+            if !SEVERITIES.contains(&severity.as_str()) {
+                return Err(format!("unknown severity '{}' (expected one of {})", severity, SEVERITIES.join(", ")));
+            }
+            let max = max.trim().parse::<u64>()
+                .map_err(|_| format!("'{}' is not a non-negative count for {}", max.trim(), severity))?;
+            limits.insert(severity, max);
+        }
+
+        if limits.is_empty() {
+            return Err("no thresholds given".to_string());
+        }
+        Ok(Self { limits })
+    }
+
+    /// Describes every severity whose open issues across all projects exceed its limit,
+    /// worst severity first. Empty when the run passes.
+    pub fn breaches(&self, projects_data: &[ProjectData]) -> Vec<String> {
+        let totals = severity_totals(projects_data);
+
+        let mut breaches: Vec<(&String, u64, u64)> = self.limits.iter()
+            .map(|(severity, max)| (severity, totals.get(severity).copied().unwrap_or(0), *max))
+            .filter(|(_, count, max)| count > max)
+            .collect();
+        breaches.sort_by_key(|(severity, _, _)| std::cmp::Reverse(severity_rank(severity)));
+
+        breaches.into_iter()
+            .map(|(severity, count, max)| format!("{}: {} open issue(s), at most {} allowed", severity, count, max))
+            .collect()
+    }
+}
+
+/// Open issues per severity across projects, from the counts facet where it was fetched and
+/// from the listed issues otherwise.
+pub fn severity_totals(projects_data: &[ProjectData]) -> BTreeMap<String, u64> {
+    let mut totals = BTreeMap::new();
+    for project in projects_data {
+        for (severity, count) in severity_counts(project) {
+            *totals.entry(severity).or_default() += count;
+        }
+    }
+    totals
+}
+
+/// Mapping used by a bare `--severity-exit-codes`.
+pub const DEFAULT_SEVERITY_EXIT_CODES: &str = "blocker=5,critical=4,major=3,minor=2,info=1";
+
+/// Process exit code per worst severity found, e.g. from `--severity-exit-codes blocker=5,critical=4`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeverityExitCodes {
+    codes: BTreeMap<String, u8>,
+}
+
+impl SeverityExitCodes {
+    /// Parses `severity=code` pairs separated by commas; severities are case-insensitive and
+    /// unlisted ones map to 0.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut codes = BTreeMap::new();
+
+        for pair in spec.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (severity, code) = pair.split_once('=')
+                .ok_or_else(|| format!("'{}' is not severity=code", pair))?;
+            let severity = severity.trim().to_uppercase();
+            if !SEVERITIES.contains(&severity.as_str()) {
+                return Err(format!("unknown severity '{}' (expected one of {})", severity, SEVERITIES.join(", ")));
+            }
+            let code = code.trim().parse::<u8>()
+                .map_err(|_| format!("'{}' is not an exit code (0-255) for {}", code.trim(), severity))?;
+            codes.insert(severity, code);
+        }
+
+        if codes.is_empty() {
+            return Err("no exit codes given".to_string());
+        }
+        Ok(Self { codes })
+    }
+
+    /// Exit code for the worst severity with open issues across all projects; 0 when there are
+    /// none or that severity has no code.
+    pub fn exit_code(&self, projects_data: &[ProjectData]) -> u8 {
+        severity_totals(projects_data).into_iter()
+            .filter(|(severity, count)| *count > 0 && SEVERITIES.contains(&severity.as_str()))
+            .max_by_key(|(severity, _)| severity_rank(severity))
+            .and_then(|(severity, _)| self.codes.get(&severity).copied())
+            .unwrap_or(0)
+    }
+}
+
+/// Exit code of a run where `--fail-on-gate` found a failed quality gate; runtime errors exit 1.
+pub const GATE_FAILED_EXIT_CODE: u8 = 2;
+
+/// Keys of the projects whose quality gate is in `ERROR`, in report order. Projects without a
+/// fetched gate are listed by [`unchecked_gates`] instead.
+pub fn failed_gates(projects_data: &[ProjectData]) -> Vec<&str> {
+    projects_data.iter()
+        .filter(|project| project.quality_gate.as_ref().is_some_and(|gate| gate.status == "ERROR"))
+        .map(|project| project.project_key.as_str())
+        .collect()
+}
+
+/// Keys of the projects whose quality gate couldn't be fetched (an HTTP error, or denied
+/// without `--strict-permissions`), in report order. `--fail-on-gate` must not pass them.
+/// A gate status of `NONE` (no gate assigned) was checked and isn't listed.
+pub fn unchecked_gates(projects_data: &[ProjectData]) -> Vec<&str> {
+    projects_data.iter()
+        .filter(|project| project.quality_gate.is_none())
+        .map(|project| project.project_key.as_str())
+        .collect()
+}